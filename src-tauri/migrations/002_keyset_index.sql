@@ -0,0 +1,9 @@
+-- Migration: 002_keyset_index.sql
+-- Description: 游标分页索引
+-- Created: 2026-10-18
+--
+-- 列表按 (is_pinned, created_at, id) 倒序排列，游标分页直接走该索引，
+-- 避免深分页时 OFFSET 逐行跳过。
+
+CREATE INDEX IF NOT EXISTS idx_records_page
+    ON records(is_pinned DESC, created_at DESC, id DESC);
@@ -0,0 +1,13 @@
+-- Migration: 003_saved_searches.sql
+-- Description: 保存的搜索（智能分组）
+-- Created: 2026-10-18
+--
+-- filter 字段为 JSON 格式的 ClipFilter，计数在查询时实时计算。
+
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,        -- 分组名称
+    filter TEXT NOT NULL,             -- JSON 格式的过滤条件
+    created_at INTEGER NOT NULL,      -- 创建时间戳 (微秒)
+    updated_at INTEGER NOT NULL       -- 修改时间戳 (微秒)
+);
@@ -0,0 +1,23 @@
+-- Migration: 004_boards.sql
+-- Description: 看板（手动整理的分组）
+-- Created: 2026-10-18
+--
+-- 一条记录可以属于多个看板，看板内按 sort_order 排序。
+-- 看板中的记录不会被“清空未置顶”等批量清理删除。
+
+CREATE TABLE IF NOT EXISTS boards (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,        -- 看板名称
+    created_at INTEGER NOT NULL       -- 创建时间戳 (微秒)
+);
+
+CREATE TABLE IF NOT EXISTS board_items (
+    board_id INTEGER NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    record_id INTEGER NOT NULL REFERENCES records(id) ON DELETE CASCADE,
+    sort_order INTEGER NOT NULL,      -- 看板内排序，从 0 开始
+    added_at INTEGER NOT NULL,        -- 加入时间戳 (微秒)
+    PRIMARY KEY (board_id, record_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_board_items_order ON board_items(board_id, sort_order);
+CREATE INDEX IF NOT EXISTS idx_board_items_record ON board_items(record_id);
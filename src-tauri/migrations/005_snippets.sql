@@ -0,0 +1,15 @@
+-- Migration: 005_snippets.sql
+-- Description: 代码片段库
+-- Created: 2026-10-18
+--
+-- 用户编写的可复用片段，body 中可包含 {date}、{input:Name} 等模板占位符，
+-- 粘贴前由 template 模块在后端展开。
+
+CREATE TABLE IF NOT EXISTS snippets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,               -- 片段名称
+    abbreviation TEXT UNIQUE,         -- 缩写 (可选，用于快速查找)
+    body TEXT NOT NULL,               -- 模板正文
+    created_at INTEGER NOT NULL,      -- 创建时间戳 (微秒)
+    updated_at INTEGER NOT NULL       -- 修改时间戳 (微秒)
+);
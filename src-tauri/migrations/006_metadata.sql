@@ -0,0 +1,8 @@
+-- Migration: 006_metadata.sql
+-- Description: 内容分类元数据
+-- Created: 2026-10-18
+--
+-- metadata 字段为 JSON 对象，以分类标签为键，例如 {"url": {"domains": ["example.com"]}}。
+-- 迁移完成后由 Storage::reclassify 对已有的文本 / HTML 记录回填标签和元数据。
+
+ALTER TABLE records ADD COLUMN metadata TEXT;
@@ -0,0 +1,8 @@
+-- Migration: 007_color.sql
+-- Description: 颜色记录的规范化 RGBA 值
+-- Created: 2026-10-18
+--
+-- color_rgba 为打包的 0xRRGGBBAA 整数，仅颜色记录有值。
+-- 迁移完成后由 Storage::recolor 按新的颜色解析器重新判定已有的文本 / 颜色记录。
+
+ALTER TABLE records ADD COLUMN color_rgba INTEGER;
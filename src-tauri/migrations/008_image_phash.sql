@@ -0,0 +1,8 @@
+-- Migration: 008_image_phash.sql
+-- Description: 图片记录的感知哈希，用于查找相似图片
+-- Created: 2026-10-18
+--
+-- image_phash 为 64 位 dHash（按 i64 存储），仅图片记录有值。
+-- 已有图片的哈希需要读取缩略图文件计算，启动后在后台补齐（Storage::images_to_hash / save_image_phash）。
+
+ALTER TABLE records ADD COLUMN image_phash INTEGER;
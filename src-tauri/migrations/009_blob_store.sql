@@ -0,0 +1,43 @@
+-- Migration: 009_blob_store.sql
+-- Description: 按内容寻址的文件存储（图片原图、缩略图）及引用计数
+-- Created: 2026-10-18
+--
+-- 文件按内容类型分命名空间、以完整 BLAKE3 哈希为键，相同内容只保存一份。
+-- 记录通过 blob_refs 引用文件，触发器维护 refcount；删除记录时级联删除引用，
+-- 引用计数归零的文件由 BlobStore::collect_garbage 清理。
+-- blob_refs 的外键保证仍被引用的 blobs 行无法删除。
+-- 已有的 YYYYMM/original、YYYYMM/thumbnail 文件由 Storage::migrate_image_files 在启动时迁入。
+
+CREATE TABLE IF NOT EXISTS blobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    namespace TEXT NOT NULL,              -- 内容类型，如 image/png
+    digest TEXT NOT NULL,                 -- 文件内容的完整 BLAKE3 哈希（十六进制）
+    path TEXT NOT NULL,                   -- 相对图片目录的路径
+    size INTEGER NOT NULL,                -- 文件大小 (字节)
+    refcount INTEGER NOT NULL DEFAULT 0,  -- 引用该文件的记录数
+    created_at INTEGER NOT NULL,          -- 创建时间戳 (微秒)
+    UNIQUE (namespace, digest)
+);
+
+CREATE TABLE IF NOT EXISTS blob_refs (
+    record_id INTEGER NOT NULL REFERENCES records(id) ON DELETE CASCADE,
+    blob_id INTEGER NOT NULL REFERENCES blobs(id),
+    role TEXT NOT NULL,                   -- 文件在记录中的用途：original / thumbnail
+    PRIMARY KEY (record_id, role)
+);
+
+CREATE INDEX IF NOT EXISTS idx_blob_refs_blob ON blob_refs(blob_id);
+CREATE INDEX IF NOT EXISTS idx_blobs_refcount ON blobs(refcount);
+
+CREATE TRIGGER IF NOT EXISTS blob_refs_ai AFTER INSERT ON blob_refs BEGIN
+    UPDATE blobs SET refcount = refcount + 1 WHERE id = new.blob_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS blob_refs_ad AFTER DELETE ON blob_refs BEGIN
+    UPDATE blobs SET refcount = refcount - 1 WHERE id = old.blob_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS blob_refs_au AFTER UPDATE OF blob_id ON blob_refs BEGIN
+    UPDATE blobs SET refcount = refcount - 1 WHERE id = old.blob_id;
+    UPDATE blobs SET refcount = refcount + 1 WHERE id = new.blob_id;
+END;
@@ -0,0 +1,10 @@
+-- Migration: 010_barcode_rescan.sql
+-- Description: 重新识别没有找到二维码的图片，补充识别条形码
+-- Created: 2026-10-19
+--
+-- 之前只识别 QR 码，没有识别到内容的图片记有空的 qr 列表，不会再被识别。
+-- 去掉空列表后这些图片由启动时的后台识别（Storage::images_to_scan）重新识别，
+-- 已经识别出 QR 码的图片保持不变。
+
+UPDATE records SET metadata = json_remove(metadata, '$.qr')
+WHERE type = 'image' AND json_array_length(metadata, '$.qr') = 0;
@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::classify;
+use crate::color::{self, Notation};
+use crate::persist::ClipData;
+
+// 剪贴内容动作
+//
+// 根据记录内容给出可执行的动作（打开链接、在文件管理器中显示、写邮件、复制颜色等）。
+// 动作只负责算出要执行的 Effect，真正打开浏览器 / 写剪贴板由调用方完成，
+// 这样界面和命令行共用同一套判断逻辑，也便于测试。
+
+/// 动作的执行结果，由调用方落实
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "target", rename_all = "snake_case")]
+pub enum Effect {
+    /// 用默认浏览器 / 邮件客户端打开（http、https、ftp、mailto）
+    OpenUrl(String),
+    /// 在文件管理器中显示
+    Reveal(PathBuf),
+    /// 写入剪贴板
+    Copy(String),
+}
+
+/// 供前端展示的动作信息
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActionInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// 动作的目标，例如要打开的链接或转换后的颜色值
+    pub detail: String,
+}
+
+/// 已注册的动作：resolve 返回 None 表示不适用于该记录
+struct Action {
+    id: &'static str,
+    label: &'static str,
+    resolve: fn(&ClipData) -> Option<Effect>,
+}
+
+static ACTIONS: &[Action] = &[
+    Action { id: "open_url", label: "在浏览器中打开", resolve: open_url },
+    Action { id: "reveal_path", label: "在文件管理器中显示", resolve: reveal_path },
+    Action { id: "compose_mail", label: "写邮件", resolve: compose_mail },
+    Action { id: "copy_hex", label: "复制为 HEX", resolve: copy_hex },
+    Action { id: "copy_rgb", label: "复制为 RGB", resolve: copy_rgb },
+    Action { id: "copy_hsl", label: "复制为 HSL", resolve: copy_hsl },
+];
+
+/// 记录可用的动作
+pub fn available(data: &ClipData) -> Vec<ActionInfo> {
+    ACTIONS.iter()
+        .filter_map(|action| {
+            let effect = (action.resolve)(data)?;
+            Some(ActionInfo { id: action.id, label: action.label, detail: describe(&effect) })
+        })
+        .collect()
+}
+
+/// 按 id 解析动作，不适用于该记录时返回错误
+pub fn resolve(id: &str, data: &ClipData) -> Result<Effect> {
+    let action = ACTIONS.iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| anyhow!("Unknown action: {}", id))?;
+    (action.resolve)(data).ok_or_else(|| anyhow!("Action {} is not available for this clip", id))
+}
+
+fn describe(effect: &Effect) -> String {
+    match effect {
+        Effect::OpenUrl(url) => url.clone(),
+        Effect::Reveal(path) => path.display().to_string(),
+        Effect::Copy(text) => text.clone(),
+    }
+}
+
+fn clip_text(data: &ClipData) -> Option<&str> {
+    match data {
+        ClipData::Text(text) => Some(text),
+        ClipData::Html { text, .. } => Some(text),
+        _ => None,
+    }
+}
+
+// ==========================================
+// 动作
+// ==========================================
+
+/// 文本中的第一个链接
+fn open_url(data: &ClipData) -> Option<Effect> {
+    let url = classify::find_urls(clip_text(data)?).into_iter().next()?;
+    Some(Effect::OpenUrl(url.to_string()))
+}
+
+/// 文件列表中的第一个文件，或整段文本为已存在的路径
+fn reveal_path(data: &ClipData) -> Option<Effect> {
+    let path = match data {
+        ClipData::Files(paths) => PathBuf::from(paths.first()?),
+        ClipData::Text(text) => {
+            let text = text.trim();
+            if text.contains('\n') {
+                return None;
+            }
+            expand_home(text)
+        }
+        _ => return None,
+    };
+    path.exists().then_some(Effect::Reveal(path))
+}
+
+/// 文本中的第一个邮箱地址
+fn compose_mail(data: &ClipData) -> Option<Effect> {
+    let email = classify::find_emails(clip_text(data)?).into_iter().next()?;
+    Some(Effect::OpenUrl(format!("mailto:{}", email)))
+}
+
+fn copy_hex(data: &ClipData) -> Option<Effect> {
+    copy_color(data, Notation::Hex)
+}
+
+fn copy_rgb(data: &ClipData) -> Option<Effect> {
+    copy_color(data, Notation::Rgb)
+}
+
+fn copy_hsl(data: &ClipData) -> Option<Effect> {
+    copy_color(data, Notation::Hsl)
+}
+
+fn copy_color(data: &ClipData, notation: Notation) -> Option<Effect> {
+    match data {
+        ClipData::Color(text) => color::parse(text)?.format(notation).map(Effect::Copy),
+        _ => None,
+    }
+}
+
+/// 展开开头的 ~/
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
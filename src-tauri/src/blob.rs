@@ -0,0 +1,202 @@
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fs;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+// 按内容寻址的文件存储
+//
+// 文件保存在 {root}/blobs/{内容类型}/{哈希前两位}/{哈希}.{扩展名}，
+// 以内容类型为命名空间、以文件内容的完整 BLAKE3 哈希为键，相同内容只保存一份。
+// blobs 表记录每个文件及其引用计数，记录通过 blob_refs 引用文件（计数由触发器维护）。
+// 只有引用计数为 0 的文件才会被 collect_garbage 删除：先删 blobs 行（外键保证
+// 仍有引用时删除失败），再删文件，所以仍在使用的文件不会被删除。
+// 文件先于数据库事务写入，事务失败时会留下没有 blobs 行的文件，由 collect_orphans 在启动时清理。
+
+/// 临时文件编号，并发写入同一内容时互不覆盖
+static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+/// 超过该时长的临时文件视为写入中断后的残留
+const STALE_TMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// 存储中的一个文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blob {
+    pub id: i64,
+    pub digest: String,
+    /// 相对存储根目录的路径
+    pub path: String,
+    pub size: u64,
+}
+
+/// 已写入文件、尚未登记到数据库的文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedBlob {
+    pub namespace: String,
+    pub digest: String,
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+impl BlobStore {
+    /// root 为存储根目录，数据库中的路径都相对于它
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+
+    /// 相对路径对应的绝对路径
+    pub fn full_path(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// 保存文件内容，内容已存在时直接返回已有的文件
+    ///
+    /// 新文件的引用计数为 0，需要由调用方在 blob_refs 中添加引用，
+    /// 否则会在下次 collect_garbage 时被清理；所在事务回滚时由 collect_orphans 清理。
+    pub fn put(&self, conn: &Connection, namespace: &str, bytes: &[u8]) -> Result<Blob> {
+        let staged = self.stage(namespace, bytes)?;
+        self.commit(conn, &staged, bytes)
+    }
+
+    /// 计算哈希并写入文件，不访问数据库，可以在持有数据库锁之前完成
+    pub fn stage(&self, namespace: &str, bytes: &[u8]) -> Result<StagedBlob> {
+        let digest = blake3::hash(bytes).to_hex().to_string();
+        let path = Self::relative_path(namespace, &digest)?;
+        self.write_file(&path, bytes)?;
+        Ok(StagedBlob { namespace: namespace.to_string(), digest, path, size: bytes.len() as u64 })
+    }
+
+    /// 登记 stage 写入的文件；期间文件被清理时用 bytes 重新写入
+    pub fn commit(&self, conn: &Connection, staged: &StagedBlob, bytes: &[u8]) -> Result<Blob> {
+        self.write_file(&staged.path, bytes)?;
+        self.register(conn, &staged.namespace, &staged.digest, &staged.path, staged.size)
+    }
+
+    /// 文件不存在时写入
+    fn write_file(&self, path: &str, bytes: &[u8]) -> Result<()> {
+        let full_path = self.full_path(path);
+        if full_path.exists() {
+            return Ok(());
+        }
+        let dir = full_path.parent().context("Invalid blob path")?;
+        fs::create_dir_all(dir).context("Failed to create blob dir")?;
+        // 先写临时文件再替换，避免留下写到一半的文件；并发写入同一内容时各用各的临时文件
+        let tmp_path = full_path.with_extension(format!("{}.tmp", NEXT_TMP.fetch_add(1, Ordering::Relaxed)));
+        fs::write(&tmp_path, bytes).context("Failed to write blob")?;
+        fs::rename(&tmp_path, &full_path).context("Failed to store blob")?;
+        Ok(())
+    }
+
+    /// 把已有文件复制进存储（迁移旧文件用）
+    ///
+    /// 源文件保持不变，由调用方在事务提交后删除；事务回滚时记录仍指向完整的源文件。
+    pub fn put_file(&self, conn: &Connection, namespace: &str, source: &Path) -> Result<Blob> {
+        let bytes = fs::read(source).with_context(|| format!("Failed to read {}", source.display()))?;
+        let digest = blake3::hash(&bytes).to_hex().to_string();
+        let path = Self::relative_path(namespace, &digest)?;
+        self.write_file(&path, &bytes)?;
+        self.register(conn, namespace, &digest, &path, bytes.len() as u64)
+    }
+
+    /// 删除引用计数为 0 的文件，返回删除的文件数
+    pub fn collect_garbage(&self, conn: &Connection) -> Result<usize> {
+        let unused = conn.prepare("SELECT id, path FROM blobs WHERE refcount <= 0")?
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut removed = 0;
+        for (id, path) in unused {
+            // 计数出错时外键约束会拒绝删除，文件保留
+            if let Err(e) = conn.execute("DELETE FROM blobs WHERE id = ?1", params![id]) {
+                eprintln!("❌ 文件仍被引用，跳过清理 {}: {:?}", path, e);
+                continue;
+            }
+            match fs::remove_file(self.full_path(&path)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => eprintln!("❌ 删除文件失败 {}: {:?}", path, e),
+            }
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// 删除存储目录中没有登记的文件和残留的临时文件，返回删除的文件数
+    ///
+    /// 写入文件后事务失败时会留下这类文件。stage 写入、尚未 commit 的文件也会被删除，
+    /// commit 时会重新写入，但仍应在没有并发写入时（启动时）调用。
+    pub fn collect_orphans(&self, conn: &Connection) -> Result<usize> {
+        let registered = conn.prepare("SELECT path FROM blobs")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<HashSet<_>>>()?;
+        let mut removed = 0;
+        self.remove_orphans(&self.root.join("blobs"), &registered, &mut removed);
+        Ok(removed)
+    }
+
+    fn remove_orphans(&self, dir: &Path, registered: &HashSet<String>, removed: &mut usize) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                self.remove_orphans(&path, registered, removed);
+                let _ = fs::remove_dir(&path); // 非空时失败，保留
+                continue;
+            }
+            let Some(relative) = path.strip_prefix(&self.root).ok().and_then(|p| p.to_str()) else { continue };
+            let relative = relative.replace('\\', "/");
+            let is_tmp = path.extension().is_some_and(|ext| ext == "tmp");
+            let orphan = if is_tmp {
+                // 正在写入的临时文件不删除
+                fs::metadata(&path).and_then(|m| m.modified()).ok()
+                    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                    .is_some_and(|age| age > STALE_TMP_AGE)
+            } else {
+                !registered.contains(&relative)
+            };
+            if !orphan {
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => *removed += 1,
+                Err(e) => eprintln!("❌ 删除未登记的文件失败 {}: {:?}", relative, e),
+            }
+        }
+    }
+
+    fn register(&self, conn: &Connection, namespace: &str, digest: &str, path: &str, size: u64) -> Result<Blob> {
+        conn.execute(
+            "INSERT INTO blobs (namespace, digest, path, size, created_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(namespace, digest) DO NOTHING",
+            params![namespace, digest, path, size as i64, Utc::now().timestamp_micros()],
+        )?;
+        let id: Option<i64> = conn.query_row(
+            "SELECT id FROM blobs WHERE namespace = ?1 AND digest = ?2",
+            params![namespace, digest],
+            |row| row.get(0),
+        ).optional()?;
+        let id = id.context("Failed to register blob")?;
+        Ok(Blob { id, digest: digest.to_string(), path: path.to_string(), size })
+    }
+
+    /// 命名空间为 MIME 类型（如 image/png），扩展名取子类型
+    fn relative_path(namespace: &str, digest: &str) -> Result<String> {
+        let valid = |part: &str| !part.is_empty()
+            && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "+-.".contains(c))
+            && part != "." && part != "..";
+        let Some((kind, subtype)) = namespace.split_once('/') else {
+            bail!("Invalid blob namespace: {}", namespace);
+        };
+        if !valid(kind) || !valid(subtype) {
+            bail!("Invalid blob namespace: {}", namespace);
+        }
+        Ok(format!("blobs/{}/{}/{}/{}.{}", kind, subtype, &digest[..2], digest, subtype))
+    }
+}
@@ -0,0 +1,307 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::net::IpAddr;
+use std::sync::LazyLock;
+use url::Url;
+
+use crate::highlight;
+use crate::json;
+use crate::markdown;
+use crate::table;
+
+// 内容分类
+//
+// 每个分类器识别一种内容并给出标签和结构化元数据（如 URL 的域名）。
+// url、email 只要文本中包含即命中；其余分类器要求整段文本就是该类内容，
+// 避免一段普通文字因为包含一个数字或日期就被打上标签。
+// 新增分类只需在 CLASSIFIERS 中注册；已有记录通过 Storage::reclassify_all 回填。
+
+/// 分类结果：标签按注册顺序排列，元数据以标签为键
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Classification {
+    pub tags: Vec<String>,
+    pub metadata: Map<String, Value>,
+}
+
+impl Classification {
+    pub fn has(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    fn push(&mut self, tag: &str, metadata: Map<String, Value>) {
+        self.tags.push(tag.to_string());
+        if !metadata.is_empty() {
+            self.metadata.insert(tag.to_string(), Value::Object(metadata));
+        }
+    }
+}
+
+/// 已注册的分类器
+pub struct Classifier {
+    pub tag: &'static str,
+    /// 已命中其中任一标签时跳过（例如 JSON 不再判定为 Markdown 或代码）
+    skip_if: &'static [&'static str],
+    classify: fn(&str) -> Option<Map<String, Value>>,
+}
+
+static CLASSIFIERS: &[Classifier] = &[
+    Classifier { tag: "json", skip_if: &[], classify: classify_json },
+    Classifier { tag: "markdown", skip_if: &["json"], classify: classify_markdown },
+    Classifier { tag: "table", skip_if: &[], classify: classify_table },
+    Classifier { tag: "url", skip_if: &[], classify: classify_url },
+    Classifier { tag: "email", skip_if: &[], classify: classify_email },
+    Classifier { tag: "phone", skip_if: &[], classify: classify_phone },
+    Classifier { tag: "path", skip_if: &[], classify: classify_path },
+    Classifier { tag: "ip", skip_if: &[], classify: classify_ip },
+    Classifier { tag: "uuid", skip_if: &[], classify: classify_uuid },
+    Classifier { tag: "number", skip_if: &[], classify: classify_number },
+    Classifier { tag: "date", skip_if: &[], classify: classify_date },
+    Classifier { tag: "code", skip_if: &["json", "markdown", "table"], classify: classify_code },
+];
+
+/// 所有已注册的分类器
+pub fn classifiers() -> &'static [Classifier] {
+    CLASSIFIERS
+}
+
+/// 对纯文本分类
+pub fn classify(text: &str) -> Classification {
+    let mut result = Classification::default();
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return result;
+    }
+    for classifier in CLASSIFIERS {
+        if classifier.skip_if.iter().any(|tag| result.has(tag)) {
+            continue;
+        }
+        if let Some(metadata) = (classifier.classify)(trimmed) {
+            result.push(classifier.tag, metadata);
+        }
+    }
+    result
+}
+
+/// 对 HTML 剪贴内容分类：文本部分走普通分类，表格以 HTML 结构为准
+pub fn classify_html(text: &str, html: &str) -> Classification {
+    let mut result = classify(text);
+    result.tags.retain(|tag| tag != "table");
+    result.metadata.remove("table");
+    if let Some(table) = table::parse_html(html) {
+        result.push("table", table_metadata(&table));
+    }
+    result
+}
+
+// ==========================================
+// 分类器
+// ==========================================
+
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\b(?:https?|ftp)://[^\s<>"'`]+"#).unwrap());
+static EMAIL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b").unwrap()
+});
+static PHONE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\+?[0-9][0-9 ()-]*[0-9]$").unwrap());
+/// 形如 2024-01-15 的文本即使不是合法日期也不当作电话
+static DATE_SHAPE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d{4}-\d{1,2}-\d{1,2}$").unwrap());
+static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[+-]?(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?(?:[eE][+-]?\d+)?$").unwrap()
+});
+static WINDOWS_PATH_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:[A-Za-z]:[\\/]|\\\\[^\\\s]+\\)").unwrap());
+
+fn classify_json(text: &str) -> Option<Map<String, Value>> {
+    if !json::looks_like_json(text) {
+        return None;
+    }
+    let root = if text.starts_with('{') { "object" } else { "array" };
+    Some(object(json!({ "root": root })))
+}
+
+fn classify_markdown(text: &str) -> Option<Map<String, Value>> {
+    markdown::looks_like_markdown(text).then(Map::new)
+}
+
+fn classify_table(text: &str) -> Option<Map<String, Value>> {
+    table::detect(text).map(|table| table_metadata(&table))
+}
+
+fn table_metadata(table: &table::Table) -> Map<String, Value> {
+    let format = match table.format {
+        table::TableFormat::Tsv => "tsv",
+        table::TableFormat::Csv => "csv",
+        table::TableFormat::Html => "html",
+    };
+    object(json!({ "format": format, "rows": table.rows.len(), "columns": table.columns }))
+}
+
+/// 文本中出现的链接，元数据为去重后的域名
+fn classify_url(text: &str) -> Option<Map<String, Value>> {
+    let urls = find_urls(text);
+    let mut domains: Vec<&str> = Vec::new();
+    for host in urls.iter().filter_map(Url::host_str) {
+        if !domains.contains(&host) {
+            domains.push(host);
+        }
+    }
+    (!urls.is_empty()).then(|| object(json!({ "count": urls.len(), "domains": domains })))
+}
+
+/// 文本中出现的邮箱地址，元数据为去重后的域名
+fn classify_email(text: &str) -> Option<Map<String, Value>> {
+    let emails = find_emails(text);
+    let mut domains: Vec<String> = Vec::new();
+    for email in &emails {
+        let domain = email.rsplit_once('@').map(|(_, d)| d.to_lowercase()).unwrap_or_default();
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+    (!emails.is_empty()).then(|| object(json!({ "count": emails.len(), "domains": domains })))
+}
+
+/// 文本中出现的带主机名的链接（去掉句末标点）
+pub fn find_urls(text: &str) -> Vec<Url> {
+    URL_RE.find_iter(text)
+        .filter_map(|found| {
+            // 句末标点通常不属于链接
+            let candidate = found.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+            Url::parse(candidate).ok().filter(|url| url.host_str().is_some())
+        })
+        .collect()
+}
+
+/// 文本中出现的邮箱地址
+pub fn find_emails(text: &str) -> Vec<String> {
+    EMAIL_RE.find_iter(text)
+        // 排除 URL 中的认证信息（https://user@host）
+        .filter(|found| !text[..found.start()].ends_with("//"))
+        .map(|found| found.as_str().to_string())
+        .collect()
+}
+
+/// 电话号码：7-15 位数字，需带 + 前缀或分隔符，以区别于普通数字
+fn classify_phone(text: &str) -> Option<Map<String, Value>> {
+    if !PHONE_RE.is_match(text) || DATE_SHAPE_RE.is_match(text) {
+        return None;
+    }
+    let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+    let international = text.starts_with('+');
+    let separated = text.contains([' ', '-', '(']);
+    if !(7..=15).contains(&digits.len()) || !(international || separated) {
+        return None;
+    }
+    let normalized = if international { format!("+{}", digits) } else { digits };
+    Some(object(json!({ "number": normalized, "international": international })))
+}
+
+/// 单行文件路径（Unix 绝对/相对路径、Windows 盘符路径或 UNC 路径）
+fn classify_path(text: &str) -> Option<Map<String, Value>> {
+    if text.contains('\n') || text.contains("://") {
+        return None;
+    }
+    let style = if WINDOWS_PATH_RE.is_match(text) {
+        "windows"
+    } else if (text.starts_with('/') && !text.starts_with("//") && text.len() > 1)
+        || text.starts_with("~/")
+        || text.starts_with("./")
+        || text.starts_with("../")
+    {
+        "unix"
+    } else {
+        return None;
+    };
+    // 带空格的长句不太可能是路径
+    if text.split_whitespace().count() > 4 {
+        return None;
+    }
+
+    let separators: &[char] = if style == "windows" { &['\\', '/'] } else { &['/'] };
+    let name = text.trim_end_matches(separators).rsplit(separators).next().unwrap_or_default();
+    let mut metadata = object(json!({ "style": style, "name": name }));
+    if let Some((stem, extension)) = name.rsplit_once('.') {
+        if !stem.is_empty() && !extension.is_empty() {
+            metadata.insert("extension".to_string(), json!(extension.to_lowercase()));
+        }
+    }
+    Some(metadata)
+}
+
+/// IPv4 / IPv6 地址，可带 CIDR 前缀长度
+fn classify_ip(text: &str) -> Option<Map<String, Value>> {
+    let (address, prefix) = match text.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix.parse::<u8>().ok()?)),
+        None => (text, None),
+    };
+    let ip: IpAddr = address.parse().ok()?;
+    let (version, max_prefix, private) = match ip {
+        IpAddr::V4(v4) => (4, 32, v4.is_private() || v4.is_loopback() || v4.is_link_local()),
+        // fc00::/7 为唯一本地地址
+        IpAddr::V6(v6) => (6, 128, v6.is_loopback() || (v6.segments()[0] & 0xfe00) == 0xfc00),
+    };
+    if prefix.is_some_and(|p| p > max_prefix) {
+        return None;
+    }
+    let mut metadata = object(json!({ "version": version, "private": private }));
+    if let Some(prefix) = prefix {
+        metadata.insert("prefix".to_string(), json!(prefix));
+    }
+    Some(metadata)
+}
+
+/// 带连字符的 UUID（可带花括号）
+fn classify_uuid(text: &str) -> Option<Map<String, Value>> {
+    let inner = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')).unwrap_or(text);
+    if inner.len() != 36 {
+        return None;
+    }
+    let uuid = uuid::Uuid::parse_str(inner).ok()?;
+    Some(object(json!({ "version": uuid.get_version_num() })))
+}
+
+/// 整数或小数，允许千分位逗号和科学计数法
+fn classify_number(text: &str) -> Option<Map<String, Value>> {
+    if !NUMBER_RE.is_match(text) {
+        return None;
+    }
+    let plain = text.replace(',', "");
+    let value = match plain.parse::<i64>() {
+        Ok(integer) => json!(integer),
+        Err(_) => serde_json::Number::from_f64(plain.parse().ok()?).map(Value::Number)?,
+    };
+    Some(object(json!({ "value": value, "integer": value.is_i64() })))
+}
+
+/// 常见日期 / 日期时间格式，元数据为 ISO 8601 形式
+fn classify_date(text: &str) -> Option<Map<String, Value>> {
+    let iso = if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        datetime.to_rfc3339()
+    } else if let Ok(datetime) = DateTime::parse_from_rfc2822(text) {
+        datetime.to_rfc3339()
+    } else if let Some(datetime) = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y/%m/%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    {
+        datetime.format("%Y-%m-%dT%H:%M:%S").to_string()
+    } else if let Some(date) = ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%d.%m.%Y", "%B %d, %Y", "%b %d, %Y", "%d %B %Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+    {
+        date.format("%Y-%m-%d").to_string()
+    } else {
+        return None;
+    };
+    Some(object(json!({ "iso": iso })))
+}
+
+fn classify_code(text: &str) -> Option<Map<String, Value>> {
+    let language = highlight::detect_language(text)?;
+    Some(object(json!({ "language": language })))
+}
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
@@ -1,6 +1,9 @@
-use arboard::Clipboard;
+use arboard::{Clipboard, ImageData};
 use clipboard_master::{CallbackResult, ClipboardHandler};
 use crossbeam_channel::Sender;
+use crate::guard;
+use crate::persist::ClipData;
+use std::borrow::Cow;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -75,7 +78,14 @@ impl ClipboardHandler for SystemHook {
             let _ = self.sender.send(ClipEvent::FileList(file_list));
         }
         else if let Ok(img) = ctx.get_image() {
-            let data = img.bytes.to_vec();
+            // 超出像素上限的图片不进入通道，避免排队的大图耗尽内存
+            if !guard::image_fits(img.width, img.height) {
+                let _ = self.sender.send(ClipEvent::Error(format!(
+                    "Image {}x{} exceeds the pixel limit", img.width, img.height
+                )));
+                return CallbackResult::Next;
+            }
+            let data = img.bytes.into_owned();
             if !self.update_latest(&data) {
                 return CallbackResult::Next;
             }
@@ -110,6 +120,31 @@ impl ClipboardHandler for SystemHook {
     }
 }
 
+/// 将记录内容写回系统剪贴板
+/// HTML 同时写入纯文本备用格式，图片解码为 RGBA 后写入
+pub fn write(data: &ClipData) -> anyhow::Result<()> {
+    let mut ctx = Clipboard::new()?;
+    match data {
+        ClipData::Text(text) | ClipData::Color(text) => ctx.set_text(text.as_str())?,
+        ClipData::Html { text, html } => ctx.set().html(html.as_str(), Some(text.as_str()))?,
+        ClipData::Files(paths) => ctx.set().file_list(paths)?,
+        ClipData::Image(bytes) => {
+            let rgba = image::load_from_memory(bytes)?.into_rgba8();
+            ctx.set_image(ImageData {
+                width: rgba.width() as usize,
+                height: rgba.height() as usize,
+                bytes: Cow::Owned(rgba.into_raw()),
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// 读取当前剪贴板中的文本，无文本时返回 None
+pub fn read_text() -> Option<String> {
+    Clipboard::new().and_then(|mut ctx| ctx.get_text()).ok()
+}
+
 // 辅助函数：计算哈希 (使用 Blake3)
 fn compute_hash(data: &[u8]) -> String {
     let hash = blake3::hash(data);
@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+// 图片原图的存储编码
+//
+// 原图可以保存为 PNG、无损 WebP 或有损 WebP，并可限制最大边长。
+// 原图在粘贴回剪贴板、生成缩略图、重新压缩和识别二维码时都要由 image 解码，
+// 所以只提供能解码的格式。AVIF 暂不提供：image 的 avif 特性只能编码，
+// 解码（avif-native）依赖系统安装的 libdav1d，不能随应用一起打包；
+// 存成 AVIF 的原图在没有 dav1d 的机器上既不能粘贴也不能生成缩略图。
+
+/// 原图的存储编码
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageCodec {
+    /// 无损 PNG
+    #[default]
+    Png,
+    /// 无损 WebP，通常比 PNG 小 20%–30%
+    WebpLossless,
+    /// 有损 WebP，按设置的质量压缩
+    WebpLossy,
+}
+
+impl ImageCodec {
+    /// 文件的 MIME 类型，也是文件存储的命名空间
+    pub fn mime(self) -> &'static str {
+        match self {
+            ImageCodec::Png => "image/png",
+            ImageCodec::WebpLossless | ImageCodec::WebpLossy => "image/webp",
+        }
+    }
+
+    /// 记录中的 image_format
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageCodec::Png => "png",
+            ImageCodec::WebpLossless | ImageCodec::WebpLossy => "webp",
+        }
+    }
+
+    pub fn is_lossy(self) -> bool {
+        matches!(self, ImageCodec::WebpLossy)
+    }
+}
+
+/// 按最大边长等比缩小；max_dimension 为 0 或图片未超出时返回 None，直接使用原图
+pub fn fit(image: &DynamicImage, max_dimension: u32) -> Option<DynamicImage> {
+    if max_dimension == 0 || (image.width() <= max_dimension && image.height() <= max_dimension) {
+        return None;
+    }
+    Some(image.resize(max_dimension, max_dimension, FilterType::Lanczos3))
+}
+
+/// 编码图片，quality（1–100）只对有损编码有效
+pub fn encode(image: &DynamicImage, codec: ImageCodec, quality: u8) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    match codec {
+        ImageCodec::Png => {
+            image.write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+                .context("Failed to encode PNG")?;
+        }
+        ImageCodec::WebpLossless => {
+            let encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut buffer);
+            image.to_rgba8().write_with_encoder(encoder).context("Failed to encode WebP")?;
+        }
+        ImageCodec::WebpLossy => {
+            let rgba = image.to_rgba8();
+            let encoded = webp::Encoder::from_rgba(rgba.as_raw(), rgba.width(), rgba.height())
+                .encode(quality.clamp(1, 100) as f32);
+            buffer.extend_from_slice(&encoded);
+        }
+    }
+    Ok(buffer)
+}
@@ -0,0 +1,568 @@
+use serde::{Deserialize, Serialize};
+
+// 颜色模型
+//
+// 解析 CSS Color 4 的常见写法：HEX（#RGB、#RGBA、#RRGGBB、#RRGGBBAA）、颜色名、
+// rgb()/rgba()、hsl()/hsla()（逗号写法和空格 + "/ alpha" 写法）、hwb()、lab()、lch()、
+// oklab() 和 oklch()。解析结果统一为 8 位 sRGB + alpha，超出 sRGB 色域的颜色按通道裁剪。
+// 输出时 rgb / hsl 使用逗号写法，其他函数使用空格写法，不透明时省略 alpha。
+
+/// 规范化的 sRGB 颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// 输出写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Notation {
+    Hex,
+    Rgb,
+    Hsl,
+    Hwb,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+    /// CSS 颜色名，仅当颜色与某个名称完全一致时可用
+    Name,
+}
+
+/// WCAG 2.x 对比度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Contrast {
+    pub ratio: f64,
+    /// 普通文字 AA（≥ 4.5）
+    pub aa: bool,
+    /// 大号文字 AA（≥ 3）
+    pub aa_large: bool,
+    /// 普通文字 AAA（≥ 7）
+    pub aaa: bool,
+    /// 大号文字 AAA（≥ 4.5）
+    pub aaa_large: bool,
+}
+
+/// 浅色和深色序列（HEX 写法，均由浅到深）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Variants {
+    pub tints: Vec<String>,
+    pub shades: Vec<String>,
+}
+
+/// 解析颜色文本，格式不合法或数值越界时返回 None
+pub fn parse(text: &str) -> Option<Rgba> {
+    let text = text.trim().to_ascii_lowercase();
+    if let Some(hex) = text.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    if let Some(rgba) = named(&text) {
+        return Some(rgba);
+    }
+
+    let (name, body) = text.strip_suffix(')')?.split_once('(')?;
+    let (components, alpha) = split_arguments(name.trim(), body)?;
+    let alpha = match alpha {
+        Some(alpha) => parse_alpha(alpha)?,
+        None => 1.0,
+    };
+    let c = |i: usize| components[i];
+
+    let (r, g, b) = match name.trim() {
+        "rgb" | "rgba" => (parse_channel(c(0))?, parse_channel(c(1))?, parse_channel(c(2))?),
+        "hsl" | "hsla" => hsl_to_rgb(parse_hue(c(0))?, parse_fraction(c(1), 100.0)?, parse_fraction(c(2), 100.0)?),
+        "hwb" => hwb_to_rgb(parse_hue(c(0))?, parse_fraction(c(1), 100.0)?, parse_fraction(c(2), 100.0)?),
+        "lab" => lab_to_rgb(parse_number(c(0), 100.0)?, parse_number(c(1), 125.0)?, parse_number(c(2), 125.0)?),
+        "lch" => {
+            let (a, b) = polar_to_ab(parse_number(c(1), 150.0)?, parse_hue(c(2))?);
+            lab_to_rgb(parse_number(c(0), 100.0)?, a, b)
+        }
+        "oklab" => oklab_to_rgb(parse_number(c(0), 1.0)?, parse_number(c(1), 0.4)?, parse_number(c(2), 0.4)?),
+        "oklch" => {
+            let (a, b) = polar_to_ab(parse_number(c(1), 0.4)?, parse_hue(c(2))?);
+            oklab_to_rgb(parse_number(c(0), 1.0)?, a, b)
+        }
+        _ => return None,
+    };
+    Some(Rgba { r, g, b, a: to_byte(alpha) })
+}
+
+/// 是否为颜色值
+pub fn is_color(text: &str) -> bool {
+    parse(text).is_some()
+}
+
+/// WCAG 对比度；半透明前景先叠加到背景上，半透明背景先叠加到白色上
+pub fn contrast(foreground: Rgba, background: Rgba) -> Contrast {
+    let background = background.over(Rgba::WHITE);
+    let foreground = foreground.over(background);
+    let (l1, l2) = (foreground.luminance(), background.luminance());
+    let ratio = (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05);
+    let ratio = (ratio * 100.0).round() / 100.0;
+    Contrast {
+        ratio,
+        aa: ratio >= 4.5,
+        aa_large: ratio >= 3.0,
+        aaa: ratio >= 7.0,
+        aaa_large: ratio >= 4.5,
+    }
+}
+
+impl Rgba {
+    pub const WHITE: Rgba = Rgba { r: 255, g: 255, b: 255, a: 255 };
+    pub const BLACK: Rgba = Rgba { r: 0, g: 0, b: 0, a: 255 };
+
+    /// 打包为 0xRRGGBBAA，用于数据库存储
+    pub fn to_u32(self) -> u32 {
+        u32::from_be_bytes([self.r, self.g, self.b, self.a])
+    }
+
+    pub fn from_u32(value: u32) -> Self {
+        let [r, g, b, a] = value.to_be_bytes();
+        Self { r, g, b, a }
+    }
+
+    /// 按指定写法输出；Name 在没有完全一致的颜色名时返回 None
+    pub fn format(&self, notation: Notation) -> Option<String> {
+        let alpha = self.alpha_suffix();
+        Some(match notation {
+            Notation::Hex => {
+                let hex = format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b);
+                if self.a == 255 { hex } else { format!("{}{:02X}", hex, self.a) }
+            }
+            Notation::Rgb => match self.a {
+                255 => format!("rgb({}, {}, {})", self.r, self.g, self.b),
+                _ => format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, trim_number(self.alpha(), 2)),
+            },
+            Notation::Hsl => {
+                let (h, s, l) = rgb_to_hsl(self.rgb());
+                let (h, s, l) = (h.round(), (s * 100.0).round(), (l * 100.0).round());
+                match self.a {
+                    255 => format!("hsl({}, {}%, {}%)", h, s, l),
+                    _ => format!("hsla({}, {}%, {}%, {})", h, s, l, trim_number(self.alpha(), 2)),
+                }
+            }
+            Notation::Hwb => {
+                let (h, w, b) = rgb_to_hwb(self.rgb());
+                format!("hwb({} {}% {}%{})", h.round(), (w * 100.0).round(), (b * 100.0).round(), alpha)
+            }
+            Notation::Lab => {
+                let (l, a, b) = rgb_to_lab(self.rgb());
+                format!("lab({}% {} {}{})", trim_number(l, 2), trim_number(a, 2), trim_number(b, 2), alpha)
+            }
+            Notation::Lch => {
+                let (l, a, b) = rgb_to_lab(self.rgb());
+                let (c, h) = ab_to_polar(a, b);
+                format!("lch({}% {} {}{})", trim_number(l, 2), trim_number(c, 2), trim_number(h, 2), alpha)
+            }
+            Notation::Oklab => {
+                let (l, a, b) = rgb_to_oklab(self.rgb());
+                format!("oklab({}% {} {}{})", trim_number(l * 100.0, 2), trim_number(a, 4), trim_number(b, 4), alpha)
+            }
+            Notation::Oklch => {
+                let (l, a, b) = rgb_to_oklab(self.rgb());
+                let (c, h) = ab_to_polar(a, b);
+                format!("oklch({}% {} {}{})", trim_number(l * 100.0, 2), trim_number(c, 4), trim_number(h, 2), alpha)
+            }
+            Notation::Name => {
+                let name = NAMED_COLORS.iter().find(|(_, rgb)| *rgb == (self.r, self.g, self.b))?.0;
+                match self.a {
+                    255 => name.to_string(),
+                    0 if name == "black" => "transparent".to_string(),
+                    _ => return None,
+                }
+            }
+        })
+    }
+
+    /// 与白色混合得到的浅色，steps 个，由浅到深
+    pub fn tints(&self, steps: usize) -> Vec<Rgba> {
+        (1..=steps).rev().map(|i| self.mix(Rgba::WHITE, i as f64 / (steps + 1) as f64)).collect()
+    }
+
+    /// 与黑色混合得到的深色，steps 个，由浅到深
+    pub fn shades(&self, steps: usize) -> Vec<Rgba> {
+        (1..=steps).map(|i| self.mix(Rgba::BLACK, i as f64 / (steps + 1) as f64)).collect()
+    }
+
+    pub fn variants(&self, steps: usize) -> Variants {
+        let hex = |colors: Vec<Rgba>| colors.iter().filter_map(|c| c.format(Notation::Hex)).collect();
+        Variants { tints: hex(self.tints(steps)), shades: hex(self.shades(steps)) }
+    }
+
+    /// WCAG 相对亮度
+    pub fn luminance(&self) -> f64 {
+        let (r, g, b) = linear_rgb(self.rgb());
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    fn alpha(&self) -> f64 {
+        self.a as f64 / 255.0
+    }
+
+    fn alpha_suffix(&self) -> String {
+        match self.a {
+            255 => String::new(),
+            _ => format!(" / {}", trim_number(self.alpha(), 2)),
+        }
+    }
+
+    fn rgb(&self) -> (f64, f64, f64) {
+        (self.r as f64 / 255.0, self.g as f64 / 255.0, self.b as f64 / 255.0)
+    }
+
+    /// 在 sRGB 空间中按比例 t 向 other 混合，保留自身的 alpha
+    fn mix(&self, other: Rgba, t: f64) -> Rgba {
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgba { r: channel(self.r, other.r), g: channel(self.g, other.g), b: channel(self.b, other.b), a: self.a }
+    }
+
+    /// 叠加到不透明背景上
+    fn over(&self, background: Rgba) -> Rgba {
+        let mut mixed = background.mix(*self, self.alpha());
+        mixed.a = 255;
+        mixed
+    }
+}
+
+// ==========================================
+// 解析
+// ==========================================
+
+fn parse_hex(hex: &str) -> Option<Rgba> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let (r, g, b, a) = match hex.len() {
+        3 => (digit(0)?, digit(1)?, digit(2)?, 255),
+        4 => (digit(0)?, digit(1)?, digit(2)?, digit(3)?),
+        6 => (pair(0)?, pair(2)?, pair(4)?, 255),
+        8 => (pair(0)?, pair(2)?, pair(4)?, pair(6)?),
+        _ => return None,
+    };
+    Some(Rgba { r, g, b, a })
+}
+
+fn named(name: &str) -> Option<Rgba> {
+    if name == "transparent" {
+        return Some(Rgba { r: 0, g: 0, b: 0, a: 0 });
+    }
+    let (_, (r, g, b)) = NAMED_COLORS.iter().find(|(n, _)| *n == name)?;
+    Some(Rgba { r: *r, g: *g, b: *b, a: 255 })
+}
+
+/// 拆分函数参数，返回三个分量和可选的 alpha
+///
+/// 逗号写法只用于 rgb / hsl（"rgb(255, 0, 0, 0.5)"），
+/// 空格写法的 alpha 写在 "/" 之后（"rgb(255 0 0 / 50%)"）
+fn split_arguments<'a>(name: &str, body: &'a str) -> Option<(Vec<&'a str>, Option<&'a str>)> {
+    if body.contains(',') {
+        if !matches!(name, "rgb" | "rgba" | "hsl" | "hsla") || body.contains('/') {
+            return None;
+        }
+        let mut parts: Vec<&str> = body.split(',').map(str::trim).collect();
+        return match parts.len() {
+            3 => Some((parts, None)),
+            4 => {
+                let alpha = parts.pop();
+                Some((parts, alpha))
+            }
+            _ => None,
+        };
+    }
+
+    let (components, alpha) = match body.split_once('/') {
+        Some((components, alpha)) => (components, Some(alpha.trim())),
+        None => (body, None),
+    };
+    let components: Vec<&str> = components.split_whitespace().collect();
+    (components.len() == 3 && alpha.is_none_or(|a| !a.is_empty())).then_some((components, alpha))
+}
+
+/// 数字或百分比；percent_scale 为 100% 对应的数值。"none" 视为 0
+fn parse_number(s: &str, percent_scale: f64) -> Option<f64> {
+    if s == "none" {
+        return Some(0.0);
+    }
+    let value = match s.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? / 100.0 * percent_scale,
+        None => s.parse::<f64>().ok()?,
+    };
+    value.is_finite().then_some(value)
+}
+
+/// 0-255 或百分比
+fn parse_channel(s: &str) -> Option<u8> {
+    let value = parse_number(s, 255.0)?;
+    (0.0..=255.0).contains(&value).then(|| value.round() as u8)
+}
+
+/// 0-1 或百分比
+fn parse_alpha(s: &str) -> Option<f64> {
+    let value = parse_number(s, 1.0)?;
+    (0.0..=1.0).contains(&value).then_some(value)
+}
+
+/// 饱和度 / 亮度 / 白度 / 黑度，返回 0-1
+fn parse_fraction(s: &str, scale: f64) -> Option<f64> {
+    let value = parse_number(s, scale)? / scale;
+    (0.0..=1.0).contains(&value).then_some(value)
+}
+
+/// 色相，支持 deg / rad / grad / turn，返回角度
+fn parse_hue(s: &str) -> Option<f64> {
+    let units = [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / std::f64::consts::PI), ("turn", 360.0)];
+    for (unit, factor) in units {
+        if let Some(value) = s.strip_suffix(unit) {
+            return value.parse::<f64>().ok().filter(|v| v.is_finite()).map(|v| v * factor);
+        }
+    }
+    if s == "none" {
+        return Some(0.0);
+    }
+    s.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+// ==========================================
+// 颜色空间转换
+// ==========================================
+
+/// D50 参考白点（CSS lab / lch 使用）
+const D50_WHITE: [f64; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+const LINEAR_SRGB_TO_XYZ_D65: [[f64; 3]; 3] = [
+    [0.412_390_799_265_959_5, 0.357_584_339_383_878, 0.180_480_788_401_834_3],
+    [0.212_639_005_871_510_36, 0.715_168_678_767_756, 0.072_192_315_360_733_71],
+    [0.019_330_818_715_591_85, 0.119_194_779_794_625_99, 0.950_532_152_249_660_6],
+];
+const XYZ_D65_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [3.240_969_941_904_521_3, -1.537_383_177_570_093_5, -0.498_610_760_293_003_3],
+    [-0.969_243_636_280_879_8, 1.875_967_501_507_720_6, 0.041_555_057_407_175_61],
+    [0.055_630_079_696_993_61, -0.203_976_958_888_976_57, 1.056_971_514_242_878_6],
+];
+/// Bradford 色适应
+const D65_TO_D50: [[f64; 3]; 3] = [
+    [1.047_929_792_544_997, 0.022_946_870_601_609_652, -0.050_192_266_289_205_24],
+    [0.029_627_808_770_055_99, 0.990_434_426_753_879_9, -0.017_073_799_063_418_826],
+    [-0.009_243_040_646_204_504, 0.015_055_191_490_298_152, 0.751_874_281_428_137_1],
+];
+const D50_TO_D65: [[f64; 3]; 3] = [
+    [0.955_473_421_488_075, -0.023_098_454_948_764_71, 0.063_259_243_200_570_72],
+    [-0.028_369_709_333_863_7, 1.009_995_398_081_304_1, 0.021_041_441_191_917_323],
+    [0.012_314_014_864_481_998, -0.020_507_649_298_898_964, 1.330_365_926_242_124],
+];
+
+fn multiply(m: &[[f64; 3]; 3], (x, y, z): (f64, f64, f64)) -> (f64, f64, f64) {
+    (
+        m[0][0] * x + m[0][1] * y + m[0][2] * z,
+        m[1][0] * x + m[1][1] * y + m[1][2] * z,
+        m[2][0] * x + m[2][1] * y + m[2][2] * z,
+    )
+}
+
+fn linear_rgb((r, g, b): (f64, f64, f64)) -> (f64, f64, f64) {
+    let f = |c: f64| if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+    (f(r), f(g), f(b))
+}
+
+/// 线性 sRGB 转 8 位通道，超出色域的值裁剪到 0-1
+fn encode_rgb((r, g, b): (f64, f64, f64)) -> (u8, u8, u8) {
+    let f = |c: f64| {
+        let c = c.clamp(0.0, 1.0);
+        let encoded = if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+        to_byte(encoded)
+    };
+    (f(r), f(g), f(b))
+}
+
+fn to_byte(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let h = h.rem_euclid(360.0) / 360.0;
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let channel = |t: f64| {
+        let t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        to_byte(v)
+    };
+    (channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0))
+}
+
+/// 返回 (色相 0-360, 饱和度 0-1, 亮度 0-1)
+fn rgb_to_hsl((r, g, b): (f64, f64, f64)) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+    (hue((r, g, b), max, d), s, l)
+}
+
+fn hwb_to_rgb(h: f64, w: f64, b: f64) -> (u8, u8, u8) {
+    // 白度与黑度之和超过 1 时按比例缩放为灰色
+    if w + b >= 1.0 {
+        let gray = to_byte(w / (w + b));
+        return (gray, gray, gray);
+    }
+    let (r, g, bl) = hsl_to_rgb(h, 1.0, 0.5);
+    let f = |c: u8| to_byte(c as f64 / 255.0 * (1.0 - w - b) + w);
+    (f(r), f(g), f(bl))
+}
+
+/// 返回 (色相 0-360, 白度 0-1, 黑度 0-1)
+fn rgb_to_hwb((r, g, b): (f64, f64, f64)) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let d = max - min;
+    let h = if d == 0.0 { 0.0 } else { hue((r, g, b), max, d) };
+    (h, min, 1.0 - max)
+}
+
+fn hue((r, g, b): (f64, f64, f64), max: f64, d: f64) -> f64 {
+    let h = if max == r {
+        (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    h * 60.0
+}
+
+const LAB_E: f64 = 216.0 / 24389.0;
+const LAB_K: f64 = 24389.0 / 27.0;
+
+fn rgb_to_lab(rgb: (f64, f64, f64)) -> (f64, f64, f64) {
+    let xyz = multiply(&D65_TO_D50, multiply(&LINEAR_SRGB_TO_XYZ_D65, linear_rgb(rgb)));
+    let f = |t: f64| if t > LAB_E { t.cbrt() } else { (LAB_K * t + 16.0) / 116.0 };
+    let (fx, fy, fz) = (f(xyz.0 / D50_WHITE[0]), f(xyz.1 / D50_WHITE[1]), f(xyz.2 / D50_WHITE[2]));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+fn lab_to_rgb(l: f64, a: f64, b: f64) -> (u8, u8, u8) {
+    let fy = (l + 16.0) / 116.0;
+    let fx = a / 500.0 + fy;
+    let fz = fy - b / 200.0;
+    let f = |t: f64| if t.powi(3) > LAB_E { t.powi(3) } else { (116.0 * t - 16.0) / LAB_K };
+    let y = if l > LAB_K * LAB_E { fy.powi(3) } else { l / LAB_K };
+    let xyz = (f(fx) * D50_WHITE[0], y * D50_WHITE[1], f(fz) * D50_WHITE[2]);
+    encode_rgb(multiply(&XYZ_D65_TO_LINEAR_SRGB, multiply(&D50_TO_D65, xyz)))
+}
+
+fn rgb_to_oklab(rgb: (f64, f64, f64)) -> (f64, f64, f64) {
+    let (r, g, b) = linear_rgb(rgb);
+    let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+    let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+    let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+    (
+        0.210_454_255_3 * l + 0.793_617_785 * m - 0.004_072_046_8 * s,
+        1.977_998_495_1 * l - 2.428_592_205 * m + 0.450_593_709_9 * s,
+        0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766 * s,
+    )
+}
+
+fn oklab_to_rgb(l: f64, a: f64, b: f64) -> (u8, u8, u8) {
+    let l_ = (l + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m_ = (l - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s_ = (l - 0.089_484_177_5 * a - 1.291_485_548 * b).powi(3);
+    encode_rgb((
+        4.076_741_662_1 * l_ - 3.307_711_591_3 * m_ + 0.230_969_929_2 * s_,
+        -1.268_438_004_6 * l_ + 2.609_757_401_1 * m_ - 0.341_319_396_5 * s_,
+        -0.004_196_086_3 * l_ - 0.703_418_614_7 * m_ + 1.707_614_701 * s_,
+    ))
+}
+
+fn polar_to_ab(chroma: f64, hue: f64) -> (f64, f64) {
+    let radians = hue.to_radians();
+    (chroma * radians.cos(), chroma * radians.sin())
+}
+
+/// 返回 (色度, 色相 0-360)；色度极小时色相记为 0
+fn ab_to_polar(a: f64, b: f64) -> (f64, f64) {
+    let chroma = a.hypot(b);
+    let hue = if chroma < 1e-4 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    (chroma, hue)
+}
+
+/// 保留指定小数位并去掉末尾的 0
+fn trim_number(value: f64, decimals: usize) -> String {
+    let text = format!("{:.*}", decimals, value);
+    let text = if text.contains('.') { text.trim_end_matches('0').trim_end_matches('.') } else { &text };
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+// ==========================================
+// CSS 颜色名
+// ==========================================
+
+static NAMED_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (240, 248, 255)), ("antiquewhite", (250, 235, 215)), ("aqua", (0, 255, 255)),
+    ("aquamarine", (127, 255, 212)), ("azure", (240, 255, 255)), ("beige", (245, 245, 220)),
+    ("bisque", (255, 228, 196)), ("black", (0, 0, 0)), ("blanchedalmond", (255, 235, 205)),
+    ("blue", (0, 0, 255)), ("blueviolet", (138, 43, 226)), ("brown", (165, 42, 42)),
+    ("burlywood", (222, 184, 135)), ("cadetblue", (95, 158, 160)), ("chartreuse", (127, 255, 0)),
+    ("chocolate", (210, 105, 30)), ("coral", (255, 127, 80)), ("cornflowerblue", (100, 149, 237)),
+    ("cornsilk", (255, 248, 220)), ("crimson", (220, 20, 60)), ("cyan", (0, 255, 255)),
+    ("darkblue", (0, 0, 139)), ("darkcyan", (0, 139, 139)), ("darkgoldenrod", (184, 134, 11)),
+    ("darkgray", (169, 169, 169)), ("darkgreen", (0, 100, 0)), ("darkgrey", (169, 169, 169)),
+    ("darkkhaki", (189, 183, 107)), ("darkmagenta", (139, 0, 139)), ("darkolivegreen", (85, 107, 47)),
+    ("darkorange", (255, 140, 0)), ("darkorchid", (153, 50, 204)), ("darkred", (139, 0, 0)),
+    ("darksalmon", (233, 150, 122)), ("darkseagreen", (143, 188, 143)), ("darkslateblue", (72, 61, 139)),
+    ("darkslategray", (47, 79, 79)), ("darkslategrey", (47, 79, 79)), ("darkturquoise", (0, 206, 209)),
+    ("darkviolet", (148, 0, 211)), ("deeppink", (255, 20, 147)), ("deepskyblue", (0, 191, 255)),
+    ("dimgray", (105, 105, 105)), ("dimgrey", (105, 105, 105)), ("dodgerblue", (30, 144, 255)),
+    ("firebrick", (178, 34, 34)), ("floralwhite", (255, 250, 240)), ("forestgreen", (34, 139, 34)),
+    ("fuchsia", (255, 0, 255)), ("gainsboro", (220, 220, 220)), ("ghostwhite", (248, 248, 255)),
+    ("gold", (255, 215, 0)), ("goldenrod", (218, 165, 32)), ("gray", (128, 128, 128)),
+    ("green", (0, 128, 0)), ("greenyellow", (173, 255, 47)), ("grey", (128, 128, 128)),
+    ("honeydew", (240, 255, 240)), ("hotpink", (255, 105, 180)), ("indianred", (205, 92, 92)),
+    ("indigo", (75, 0, 130)), ("ivory", (255, 255, 240)), ("khaki", (240, 230, 140)),
+    ("lavender", (230, 230, 250)), ("lavenderblush", (255, 240, 245)), ("lawngreen", (124, 252, 0)),
+    ("lemonchiffon", (255, 250, 205)), ("lightblue", (173, 216, 230)), ("lightcoral", (240, 128, 128)),
+    ("lightcyan", (224, 255, 255)), ("lightgoldenrodyellow", (250, 250, 210)), ("lightgray", (211, 211, 211)),
+    ("lightgreen", (144, 238, 144)), ("lightgrey", (211, 211, 211)), ("lightpink", (255, 182, 193)),
+    ("lightsalmon", (255, 160, 122)), ("lightseagreen", (32, 178, 170)), ("lightskyblue", (135, 206, 250)),
+    ("lightslategray", (119, 136, 153)), ("lightslategrey", (119, 136, 153)), ("lightsteelblue", (176, 196, 222)),
+    ("lightyellow", (255, 255, 224)), ("lime", (0, 255, 0)), ("limegreen", (50, 205, 50)),
+    ("linen", (250, 240, 230)), ("magenta", (255, 0, 255)), ("maroon", (128, 0, 0)),
+    ("mediumaquamarine", (102, 205, 170)), ("mediumblue", (0, 0, 205)), ("mediumorchid", (186, 85, 211)),
+    ("mediumpurple", (147, 112, 219)), ("mediumseagreen", (60, 179, 113)), ("mediumslateblue", (123, 104, 238)),
+    ("mediumspringgreen", (0, 250, 154)), ("mediumturquoise", (72, 209, 204)), ("mediumvioletred", (199, 21, 133)),
+    ("midnightblue", (25, 25, 112)), ("mintcream", (245, 255, 250)), ("mistyrose", (255, 228, 225)),
+    ("moccasin", (255, 228, 181)), ("navajowhite", (255, 222, 173)), ("navy", (0, 0, 128)),
+    ("oldlace", (253, 245, 230)), ("olive", (128, 128, 0)), ("olivedrab", (107, 142, 35)),
+    ("orange", (255, 165, 0)), ("orangered", (255, 69, 0)), ("orchid", (218, 112, 214)),
+    ("palegoldenrod", (238, 232, 170)), ("palegreen", (152, 251, 152)), ("paleturquoise", (175, 238, 238)),
+    ("palevioletred", (219, 112, 147)), ("papayawhip", (255, 239, 213)), ("peachpuff", (255, 218, 185)),
+    ("peru", (205, 133, 63)), ("pink", (255, 192, 203)), ("plum", (221, 160, 221)),
+    ("powderblue", (176, 224, 230)), ("purple", (128, 0, 128)), ("rebeccapurple", (102, 51, 153)),
+    ("red", (255, 0, 0)), ("rosybrown", (188, 143, 143)), ("royalblue", (65, 105, 225)),
+    ("saddlebrown", (139, 69, 19)), ("salmon", (250, 128, 114)), ("sandybrown", (244, 164, 96)),
+    ("seagreen", (46, 139, 87)), ("seashell", (255, 245, 238)), ("sienna", (160, 82, 45)),
+    ("silver", (192, 192, 192)), ("skyblue", (135, 206, 235)), ("slateblue", (106, 90, 205)),
+    ("slategray", (112, 128, 144)), ("slategrey", (112, 128, 144)), ("snow", (255, 250, 250)),
+    ("springgreen", (0, 255, 127)), ("steelblue", (70, 130, 180)), ("tan", (210, 180, 140)),
+    ("teal", (0, 128, 128)), ("thistle", (216, 191, 216)), ("tomato", (255, 99, 71)),
+    ("turquoise", (64, 224, 208)), ("violet", (238, 130, 238)), ("wheat", (245, 222, 179)),
+    ("white", (255, 255, 255)), ("whitesmoke", (245, 245, 245)), ("yellow", (255, 255, 0)),
+    ("yellowgreen", (154, 205, 50)),
+];
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::clipboard::ClipEvent;
+
+// 捕获内容的大小上限
+//
+// 一次复制超大截图或日志时，完整内容会经过剪贴板通道进入内存和数据库。
+// 图片按像素数限制，超限直接丢弃（监听线程在复制像素数据之前就先检查）；
+// 文本和 HTML 按字节数限制，超限时按设置丢弃、截断，或把完整内容另存为文件、
+// 记录中只保存截断后的部分。
+//
+// 未超限但超过大文本阈值的正文同样另存为文件，记录中的 HTML 只保留开头一段作为预览。
+// 纯文本保留多少由搜索文本上限决定：默认与大文本阈值相同，数据库和全文索引里
+// 每条记录最多保存这么多，超出部分搜不到；调大可以搜到更多内容，代价是数据库变大。
+// 搜索文本已经能放下完整正文时不再另存，避免同一内容存两份。
+// 另存为文件的正文都用 zstd 压缩。
+
+/// 默认图片像素上限（约 8K × 8K）
+pub const DEFAULT_MAX_IMAGE_PIXELS: u64 = 64_000_000;
+/// 默认文本上限
+pub const DEFAULT_MAX_TEXT_BYTES: usize = 4 * 1024 * 1024;
+/// 默认 HTML 上限
+pub const DEFAULT_MAX_HTML_BYTES: usize = 8 * 1024 * 1024;
+/// 文本和 HTML 上限的下限
+pub const MIN_CONTENT_BYTES: usize = 1024;
+/// 默认大文本阈值，超过时正文另存为文件
+pub const DEFAULT_LARGE_BODY_BYTES: usize = 64 * 1024;
+/// 正文另存为文件时记录中保留的 HTML 字节数（也是大文本阈值和搜索文本上限的下限）
+pub const BODY_PREVIEW_BYTES: usize = 4096;
+/// 默认搜索文本上限：正文另存为文件时记录中保留的纯文本字节数
+pub const DEFAULT_SEARCH_TEXT_BYTES: usize = DEFAULT_LARGE_BODY_BYTES;
+/// 图片像素上限的下限
+pub const MIN_IMAGE_PIXELS: u64 = 1_000_000;
+
+/// 监听线程使用的图片像素上限，设置变更时由 publish 更新
+static MAX_IMAGE_PIXELS: AtomicU64 = AtomicU64::new(DEFAULT_MAX_IMAGE_PIXELS);
+
+/// 文本或 HTML 超出上限时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OversizedContent {
+    /// 丢弃，不保存
+    Drop,
+    /// 截断到上限后保存
+    Truncate,
+    /// 记录中保存截断后的内容，完整内容另存为文件
+    #[default]
+    Spill,
+}
+
+/// 捕获内容的大小上限（取自设置）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureLimits {
+    pub max_image_pixels: u64,
+    pub max_text_bytes: usize,
+    pub max_html_bytes: usize,
+    pub oversized: OversizedContent,
+}
+
+impl Default for CaptureLimits {
+    fn default() -> Self {
+        Self {
+            max_image_pixels: DEFAULT_MAX_IMAGE_PIXELS,
+            max_text_bytes: DEFAULT_MAX_TEXT_BYTES,
+            max_html_bytes: DEFAULT_MAX_HTML_BYTES,
+            oversized: OversizedContent::default(),
+        }
+    }
+}
+
+/// 检查结果
+#[derive(Debug)]
+pub enum Guarded {
+    /// 未超限，或已截断到上限内
+    Keep(ClipEvent),
+    /// 超限，需要按上限截断保存并另存完整内容（事件中为完整内容）
+    Spill(ClipEvent),
+    /// 丢弃，附原因
+    Drop(String),
+}
+
+/// 按上限检查一个捕获事件
+pub fn check(event: ClipEvent, limits: &CaptureLimits) -> Guarded {
+    match event {
+        ClipEvent::Image { width, height, rgba_data } => {
+            let pixels = (width as u64).saturating_mul(height as u64);
+            if pixels > limits.max_image_pixels {
+                return Guarded::Drop(format!(
+                    "Image {}x{} exceeds the limit of {} pixels", width, height, limits.max_image_pixels
+                ));
+            }
+            if rgba_data.len() as u64 != pixels.saturating_mul(4) {
+                return Guarded::Drop(format!(
+                    "Image data is {} bytes, expected {} for {}x{}", rgba_data.len(), pixels * 4, width, height
+                ));
+            }
+            Guarded::Keep(ClipEvent::Image { width, height, rgba_data })
+        }
+        ClipEvent::Text(text) => check_content(text, limits.max_text_bytes, limits.oversized, "Text", ClipEvent::Text),
+        ClipEvent::Html(html) => check_content(html, limits.max_html_bytes, limits.oversized, "HTML", ClipEvent::Html),
+        event => Guarded::Keep(event),
+    }
+}
+
+fn check_content(
+    mut content: String,
+    limit: usize,
+    oversized: OversizedContent,
+    kind: &str,
+    wrap: fn(String) -> ClipEvent,
+) -> Guarded {
+    if content.len() <= limit {
+        return Guarded::Keep(wrap(content));
+    }
+    match oversized {
+        OversizedContent::Drop => Guarded::Drop(format!(
+            "{} of {} bytes exceeds the limit of {} bytes", kind, content.len(), limit
+        )),
+        OversizedContent::Truncate => {
+            let end = truncate(&content, limit).len();
+            content.truncate(end);
+            Guarded::Keep(wrap(content))
+        }
+        OversizedContent::Spill => Guarded::Spill(wrap(content)),
+    }
+}
+
+/// 截断到不超过 max_bytes 字节，不切断 UTF-8 字符
+pub fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// 更新监听线程使用的上限
+pub fn publish(limits: &CaptureLimits) {
+    MAX_IMAGE_PIXELS.store(limits.max_image_pixels, Ordering::Relaxed);
+}
+
+/// 监听线程在复制像素数据前检查图片尺寸
+pub fn image_fits(width: usize, height: usize) -> bool {
+    (width as u64).saturating_mul(height as u64) <= MAX_IMAGE_PIXELS.load(Ordering::Relaxed)
+}
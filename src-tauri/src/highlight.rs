@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::sync::LazyLock;
+use syntect::highlighting::ThemeSet;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+// 代码语言识别与语法高亮
+//
+// 高亮结果为内联样式的 HTML（<pre style=...><span style=...>），
+// 不依赖外部 CSS，可直接粘贴到幻灯片、文档和邮件中。
+
+/// 默认高亮主题（浅色背景，适合粘贴到文档）
+pub const DEFAULT_THEME: &str = "InspiredGitHub";
+
+/// 判定为某种语言所需的最少命中特征数
+const DETECT_THRESHOLD: usize = 2;
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEMES: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+/// 语言特征：每条正则命中一次记 1 分
+struct LanguageRule {
+    /// syntect 语法名
+    syntax: &'static str,
+    patterns: &'static [&'static str],
+}
+
+static RULES: &[LanguageRule] = &[
+    LanguageRule { syntax: "Rust", patterns: &[
+        r"\bfn\s+\w+\s*[(<]",
+        r"\blet\s+(mut\s+)?\w+\s*(:\s*[\w<>&]+\s*)?=",
+        r"\bimpl\b.*\{",
+        r"\bpub\s+(fn|struct|enum|mod|trait)\b",
+        r"(?m)^\s*use\s+\w+(::\w+)+",
+        r"&(mut\s+)?self\b",
+        r"\b(println|vec|format)!",
+    ]},
+    LanguageRule { syntax: "Python", patterns: &[
+        r"(?m)^\s*def\s+\w+\s*\(.*\)\s*(->\s*[\w\[\], ]+)?:\s*$",
+        r"(?m)^\s*(import\s+\w+|from\s+[\w.]+\s+import\b)",
+        r"(?m)^\s*class\s+\w+(\(.*\))?:\s*$",
+        r"\bself\.\w+",
+        r"(?m)^\s*(if|elif|for|while|with)\b.*:\s*$",
+        r"\bprint\(",
+        r"\b(None|True|False)\b",
+    ]},
+    LanguageRule { syntax: "JavaScript", patterns: &[
+        r"\b(const|let|var)\s+\w+\s*=",
+        r"\bfunction\s*\w*\s*\(",
+        r"=>",
+        r"\bconsole\.\w+\(",
+        r"(?m)^\s*import\s+.+\s+from\s+['\x22]",
+        r"\bexport\s+(default|const|function|class)\b",
+        r"===|!==",
+        r"\b(document|window)\.\w+",
+    ]},
+    LanguageRule { syntax: "Go", patterns: &[
+        r"(?m)^\s*package\s+\w+\s*$",
+        r"\bfunc\s+(\(\w+\s+\*?\w+\)\s*)?\w+\(",
+        r":=",
+        r"\bfmt\.\w+\(",
+        r"(?m)^\s*import\s+\(",
+    ]},
+    LanguageRule { syntax: "Java", patterns: &[
+        r"\bpublic\s+(static\s+)?(final\s+)?(class|void|interface)\b",
+        r"\bSystem\.out\.print",
+        r"\bprivate\s+(final\s+)?\w+(<.*>)?\s+\w+\s*[;=]",
+        r"@Override\b",
+        r"\bString\[\]",
+        r"(?m)^\s*import\s+java\.",
+    ]},
+    LanguageRule { syntax: "C", patterns: &[
+        r"#include\s*<\w+\.h>",
+        r"\bint\s+main\s*\(",
+        r"\bprintf\s*\(",
+        r"\b(malloc|free)\s*\(",
+        r"\bsizeof\b",
+    ]},
+    LanguageRule { syntax: "C++", patterns: &[
+        r"#include\s*<\w+>",
+        r"\bstd::",
+        r"\b(cout|cin)\s*(<<|>>)",
+        r"\btemplate\s*<",
+        r"\bnamespace\s+\w+",
+    ]},
+    LanguageRule { syntax: "C#", patterns: &[
+        r"(?m)^\s*using\s+System",
+        r"\bConsole\.Write(Line)?\(",
+        r"\bpublic\s+(static\s+)?(class|void|async)\b",
+        r"\{\s*get;\s*(set;)?\s*\}",
+        r"(?m)^\s*namespace\s+[\w.]+",
+    ]},
+    LanguageRule { syntax: "SQL", patterns: &[
+        r"(?i)\bselect\s+[\w*,\s.()]+\s+from\s+\w+",
+        r"(?i)\binsert\s+into\s+\w+",
+        r"(?i)\bcreate\s+(table|index|view)\b",
+        r"(?i)\bwhere\s+\w+(\.\w+)?\s*(=|<|>|like\b|in\b|is\b)",
+        r"(?i)\bupdate\s+\w+\s+set\b",
+        r"(?i)\b(inner|left|right|outer)\s+join\b",
+        r"(?i)\b(group|order)\s+by\b",
+    ]},
+    LanguageRule { syntax: "Bourne Again Shell (bash)", patterns: &[
+        r"(?m)^#!.*\b(ba|z)?sh\b",
+        r"(?m)^\s*(sudo|apt(-get)?|brew|cd|ls|echo|export|mkdir|rm|cp|mv|chmod|curl|wget)\s",
+        r"\$\{\w+\}|\$\(\w+",
+        r"\|\s*(grep|awk|sed|xargs|sort|head|tail)\b",
+        r"(?m)^\s*(then|fi|done|esac)\s*$",
+        r"\s(&&|\|\|)\s",
+    ]},
+    LanguageRule { syntax: "CSS", patterns: &[
+        r"(?m)^\s*[.#]?[\w-]+([\s.#:>,\w-]*)\{\s*$",
+        r"(?m)^\s*[\w-]+\s*:\s*[^;{}]+;\s*$",
+        r"@(media|import|keyframes)\b",
+        r"#[0-9a-fA-F]{3,6}\b|\b\d+(px|em|rem|vh|vw)\b",
+    ]},
+    LanguageRule { syntax: "YAML", patterns: &[
+        r"(?m)^\s*[\w-]+:\s+\S",
+        r"(?m)^\s*-\s+[\w-]+:\s",
+        r"(?m)^---\s*$",
+        r"(?m)^\s+[\w-]+:\s*$",
+    ]},
+    LanguageRule { syntax: "Ruby", patterns: &[
+        r"(?m)^\s*def\s+\w+[?!]?(\(.*\))?\s*$",
+        r"(?m)^\s*end\s*$",
+        r"\bputs\b",
+        r"(?m)^\s*require\s+['\x22]",
+        r"\bdo\s*\|\w+(,\s*\w+)*\|",
+    ]},
+    LanguageRule { syntax: "PHP", patterns: &[
+        r"<\?php",
+        r"\$\w+\s*=",
+        r"\becho\b",
+        r"\$this->\w+",
+        r"\bfunction\s+\w+\s*\(",
+    ]},
+];
+
+static COMPILED_RULES: LazyLock<Vec<(&'static str, Vec<Regex>)>> = LazyLock::new(|| {
+    RULES.iter()
+        .map(|rule| (rule.syntax, rule.patterns.iter().map(|p| Regex::new(p).unwrap()).collect()))
+        .collect()
+});
+
+/// 识别文本的编程语言，返回 syntect 语法名；无法判断时返回 None
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    // 结构化格式直接解析
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Some("JSON");
+    }
+    if trimmed.starts_with("<?xml") {
+        return Some("XML");
+    }
+    if trimmed.starts_with('<') && trimmed.ends_with('>') && !trimmed.starts_with("<?php") {
+        return Some("HTML");
+    }
+
+    // shebang 等首行特征由 syntect 识别
+    if let Some(syntax) = SYNTAXES.find_syntax_by_first_line(trimmed) {
+        return Some(syntax.name.as_str());
+    }
+
+    let mut best: Option<(&'static str, usize)> = None;
+    for (syntax, patterns) in COMPILED_RULES.iter() {
+        let score = patterns.iter().filter(|re| re.is_match(trimmed)).count();
+        if score >= DETECT_THRESHOLD && best.is_none_or(|(_, top)| score > top) {
+            best = Some((syntax, score));
+        }
+    }
+    best.map(|(syntax, _)| syntax)
+}
+
+/// 所有可用的高亮主题名
+pub fn theme_names() -> Vec<&'static str> {
+    THEMES.themes.keys().map(String::as_str).collect()
+}
+
+/// 渲染为内联样式的高亮 HTML
+///
+/// language 可以是语法名（"Rust"）或扩展名（"rs"），为 None 时自动识别，
+/// 仍无法确定时按纯文本输出。
+pub fn to_html(code: &str, language: Option<&str>, theme: &str) -> Result<String> {
+    let theme = THEMES.themes.get(theme).ok_or_else(|| anyhow!("Unknown highlight theme: {}", theme))?;
+    let syntax = match language {
+        Some(name) => find_syntax(name).ok_or_else(|| anyhow!("Unknown language: {}", name))?,
+        None => detect_language(code)
+            .and_then(find_syntax)
+            .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text()),
+    };
+    Ok(highlighted_html_for_string(code, &SYNTAXES, syntax, theme)?)
+}
+
+fn find_syntax(name: &str) -> Option<&'static SyntaxReference> {
+    SYNTAXES.find_syntax_by_name(name).or_else(|| SYNTAXES.find_syntax_by_token(name))
+}
@@ -0,0 +1,436 @@
+use scraper::{ElementRef, Html, Node};
+
+// HTML 解析
+//
+// 基于 html5ever 的标准解析器，负责：
+// - 提取纯文本预览（实体解码、块级元素换行、<pre> 保留空白）
+// - 将 HTML 剪贴内容转换为 Markdown
+
+/// 不产生可见文本的元素，连同内容一起跳过
+const SKIPPED: &[&str] = &["head", "script", "style", "template", "noscript", "title"];
+
+/// 前后以空行分隔的块级元素
+const PARAGRAPH_BLOCKS: &[&str] = &[
+    "p", "h1", "h2", "h3", "h4", "h5", "h6", "pre", "blockquote", "table",
+];
+
+/// 前后换行的块级元素
+const LINE_BLOCKS: &[&str] = &[
+    "address", "article", "aside", "body", "caption", "center", "dd", "details", "dialog", "div",
+    "dl", "dt", "fieldset", "figcaption", "figure", "footer", "form", "header", "hgroup", "hr",
+    "html", "li", "main", "nav", "ol", "section", "summary", "tbody", "tfoot", "thead", "tr", "ul",
+];
+
+fn is_block(name: &str) -> bool {
+    PARAGRAPH_BLOCKS.contains(&name) || LINE_BLOCKS.contains(&name)
+}
+
+fn is_cell(name: &str) -> bool {
+    name == "td" || name == "th"
+}
+
+// ==========================================
+// 纯文本
+// ==========================================
+
+/// 将 HTML 转换为纯文本
+pub fn to_plain_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut writer = TextWriter::default();
+    write_text(document.root_element(), &mut writer, false);
+    writer.finish()
+}
+
+/// 纯文本输出缓冲：合并空白，块级元素之间按需补换行
+#[derive(Default)]
+struct TextWriter {
+    out: String,
+    pending_breaks: usize,
+    pending_space: bool,
+}
+
+impl TextWriter {
+    /// 普通文本，连续空白合并为一个空格
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.pending_space = true;
+            } else {
+                self.flush();
+                self.out.push(c);
+            }
+        }
+    }
+
+    /// 原样输出（<pre> 内容、表格分隔符）
+    fn raw(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.pending_space = false;
+        self.flush();
+        self.out.push_str(text);
+    }
+
+    /// <br> 强制换行
+    fn line_break(&mut self) {
+        self.flush();
+        self.trim_trailing_spaces();
+        self.out.push('\n');
+        self.pending_space = false;
+    }
+
+    /// 块级边界：确保之后的内容与之前至少隔开 breaks 个换行
+    fn block(&mut self, breaks: usize) {
+        if breaks == 0 {
+            return;
+        }
+        self.pending_breaks = self.pending_breaks.max(breaks);
+        self.pending_space = false;
+    }
+
+    fn flush(&mut self) {
+        if !self.out.is_empty() {
+            if self.pending_breaks > 0 {
+                self.trim_trailing_spaces();
+                let existing = self.out.len() - self.out.trim_end_matches('\n').len();
+                for _ in existing..self.pending_breaks {
+                    self.out.push('\n');
+                }
+            } else if self.pending_space && !self.out.ends_with(char::is_whitespace) {
+                self.out.push(' ');
+            }
+        }
+        self.pending_breaks = 0;
+        self.pending_space = false;
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+    }
+
+    fn finish(self) -> String {
+        self.out.trim().to_string()
+    }
+}
+
+fn write_text(element: ElementRef, writer: &mut TextWriter, preformatted: bool) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) if preformatted => writer.raw(text),
+            Node::Text(text) => writer.text(text),
+            Node::Element(el) => {
+                let name = el.name();
+                if SKIPPED.contains(&name) {
+                    continue;
+                }
+                let Some(child) = ElementRef::wrap(child) else { continue };
+                match name {
+                    "br" => writer.line_break(),
+                    "td" | "th" => {
+                        // 同一行的单元格以制表符分隔
+                        let follows_cell = child.prev_siblings()
+                            .any(|s| s.value().as_element().is_some_and(|e| is_cell(e.name())));
+                        if follows_cell {
+                            writer.raw("\t");
+                        }
+                        write_text(child, writer, preformatted);
+                    }
+                    _ => {
+                        let breaks = if PARAGRAPH_BLOCKS.contains(&name) {
+                            2
+                        } else if LINE_BLOCKS.contains(&name) {
+                            1
+                        } else {
+                            0
+                        };
+                        writer.block(breaks);
+                        write_text(child, writer, preformatted || name == "pre");
+                        writer.block(breaks);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// ==========================================
+// Markdown
+// ==========================================
+
+/// 将 HTML 转换为 Markdown
+pub fn to_markdown(html: &str) -> String {
+    let document = Html::parse_document(html);
+    render_blocks(document.root_element()).join("\n\n")
+}
+
+/// 渲染子节点为 Markdown 块，相邻的行内内容合并为段落
+fn render_blocks(element: ElementRef) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut paragraph = String::new();
+
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => paragraph.push_str(&escape_text(text)),
+            Node::Element(el) => {
+                let name = el.name();
+                if SKIPPED.contains(&name) {
+                    continue;
+                }
+                let Some(child) = ElementRef::wrap(child) else { continue };
+                if is_block(name) {
+                    flush_paragraph(&mut paragraph, &mut blocks);
+                    blocks.extend(render_block(child));
+                } else {
+                    paragraph.push_str(&render_inline(child));
+                }
+            }
+            _ => {}
+        }
+    }
+    flush_paragraph(&mut paragraph, &mut blocks);
+    blocks
+}
+
+/// 段落收尾：行内缓冲中的 '\n' 来自 <br>，输出为 Markdown 硬换行
+fn flush_paragraph(paragraph: &mut String, blocks: &mut Vec<String>) {
+    let lines: Vec<String> = paragraph
+        .split('\n')
+        .map(|line| line.split(' ').filter(|w| !w.is_empty()).collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect();
+    if !lines.is_empty() {
+        blocks.push(lines.join("\\\n"));
+    }
+    paragraph.clear();
+}
+
+fn render_block(element: ElementRef) -> Vec<String> {
+    let name = element.value().name();
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse().unwrap_or(1);
+            let text = single_line(&render_inline_children(element));
+            if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![format!("{} {}", "#".repeat(level), text)]
+            }
+        }
+        "pre" => vec![render_code_block(element)],
+        "blockquote" => {
+            let inner = render_blocks(element).join("\n\n");
+            if inner.is_empty() {
+                return Vec::new();
+            }
+            let quoted = inner
+                .lines()
+                .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                .collect::<Vec<_>>()
+                .join("\n");
+            vec![quoted]
+        }
+        "ul" | "ol" => {
+            let list = render_list(element);
+            if list.is_empty() { Vec::new() } else { vec![list] }
+        }
+        "hr" => vec!["---".to_string()],
+        "table" => {
+            let table = render_table(element);
+            if table.is_empty() { Vec::new() } else { vec![table] }
+        }
+        _ => render_blocks(element),
+    }
+}
+
+fn render_list(list: ElementRef) -> String {
+    let ordered = list.value().name() == "ol";
+    let start: usize = list.value().attr("start").and_then(|s| s.trim().parse().ok()).unwrap_or(1);
+
+    let mut items = Vec::new();
+    for item in list.children().filter_map(ElementRef::wrap) {
+        if item.value().name() != "li" {
+            continue;
+        }
+        let marker = if ordered { format!("{}. ", start + items.len()) } else { "- ".to_string() };
+        let indent = " ".repeat(marker.len());
+        // 列表项内部保持紧凑，嵌套列表按标记宽度缩进
+        let content = render_blocks(item).join("\n");
+        let mut lines = content.lines();
+        let mut rendered = format!("{}{}", marker, lines.next().unwrap_or_default());
+        for line in lines {
+            rendered.push('\n');
+            if !line.is_empty() {
+                rendered.push_str(&indent);
+                rendered.push_str(line);
+            }
+        }
+        items.push(rendered.trim_end().to_string());
+    }
+    items.join("\n")
+}
+
+fn render_code_block(pre: ElementRef) -> String {
+    let code: String = pre.text().collect();
+    let code = code.trim_end_matches('\n');
+
+    // <pre><code class="language-rust"> 中的语言标记
+    let language = pre
+        .children()
+        .filter_map(ElementRef::wrap)
+        .find(|el| el.value().name() == "code")
+        .and_then(|el| {
+            el.value().classes().find_map(|class| {
+                class.strip_prefix("language-").or_else(|| class.strip_prefix("lang-"))
+            })
+        })
+        .unwrap_or_default();
+
+    let mut fence = "```".to_string();
+    while code.contains(fence.as_str()) {
+        fence.push('`');
+    }
+    format!("{}{}\n{}\n{}", fence, language, code, fence)
+}
+
+fn render_table(table: ElementRef) -> String {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    collect_rows(table, &mut rows);
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let format_row = |cells: &[String]| {
+        let mut line = String::from("|");
+        for i in 0..columns {
+            line.push(' ');
+            line.push_str(cells.get(i).map(String::as_str).unwrap_or_default());
+            line.push_str(" |");
+        }
+        line
+    };
+
+    // 第一行作为表头
+    let mut lines = vec![format_row(&rows[0]), format_row(&vec!["---".to_string(); columns])];
+    lines.extend(rows[1..].iter().map(|row| format_row(row)));
+    lines.join("\n")
+}
+
+/// 收集表格行（含 thead/tbody/tfoot 中的行，不进入嵌套表格）
+fn collect_rows(element: ElementRef, rows: &mut Vec<Vec<String>>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        match child.value().name() {
+            "thead" | "tbody" | "tfoot" => collect_rows(child, rows),
+            "tr" => {
+                let cells: Vec<String> = child
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| is_cell(cell.value().name()))
+                    .map(|cell| single_line(&render_inline_children(cell)).replace('|', "\\|"))
+                    .collect();
+                if !cells.is_empty() {
+                    rows.push(cells);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn render_inline_children(element: ElementRef) -> String {
+    let mut output = String::new();
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => output.push_str(&escape_text(text)),
+            Node::Element(el) if !SKIPPED.contains(&el.name()) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    output.push_str(&render_inline(child));
+                }
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+fn render_inline(element: ElementRef) -> String {
+    match element.value().name() {
+        "br" => "\n".to_string(),
+        "strong" | "b" => emphasize(&render_inline_children(element), "**"),
+        "em" | "i" => emphasize(&render_inline_children(element), "*"),
+        "del" | "s" | "strike" => emphasize(&render_inline_children(element), "~~"),
+        "code" | "kbd" | "samp" | "tt" => {
+            let code = collapse_whitespace(&element.text().collect::<String>());
+            if code.is_empty() {
+                String::new()
+            } else if code.contains('`') {
+                format!("`` {} ``", code)
+            } else {
+                format!("`{}`", code)
+            }
+        }
+        "a" => {
+            let text = render_inline_children(element);
+            match element.value().attr("href").map(str::trim) {
+                Some(href) if !href.is_empty() && !href.starts_with("javascript:") => {
+                    let label = single_line(&text);
+                    let label = if label.is_empty() { escape_text(href) } else { label };
+                    format!("[{}]({})", label, href.replace(' ', "%20"))
+                }
+                _ => text,
+            }
+        }
+        "img" => match element.value().attr("src") {
+            Some(src) if !src.trim().is_empty() => {
+                let alt = element.value().attr("alt").map(escape_text).unwrap_or_default();
+                format!("![{}]({})", single_line(&alt), src.trim().replace(' ', "%20"))
+            }
+            _ => String::new(),
+        },
+        _ => render_inline_children(element),
+    }
+}
+
+/// 用标记包裹内容，首尾空白移到标记外侧
+fn emphasize(content: &str, marker: &str) -> String {
+    let trimmed = content.trim_matches([' ', '\n']);
+    if trimmed.is_empty() {
+        return content.to_string();
+    }
+    let leading = if content.starts_with(' ') { " " } else { "" };
+    let trailing = if content.ends_with(' ') { " " } else { "" };
+    format!("{}{}{}{}{}", leading, marker, trimmed, marker, trailing)
+}
+
+/// 文本节点：合并空白并转义 Markdown 特殊字符
+fn escape_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !last_space {
+                output.push(' ');
+            }
+            last_space = true;
+            continue;
+        }
+        last_space = false;
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<') {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    output
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 去掉 <br> 产生的换行，用于标题、表格单元格等单行场景
+fn single_line(text: &str) -> String {
+    text.split([' ', '\n']).filter(|w| !w.is_empty()).collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,37 @@
+use image::imageops::FilterType;
+use image::DynamicImage;
+use std::path::PathBuf;
+
+// 图片感知哈希
+//
+// 使用 dHash（差值哈希）：把图片缩小为 9×8 的灰度图，逐行比较相邻像素的明暗，
+// 得到 64 位指纹。缩放、重新编码或改动少量像素后指纹基本不变，
+// 两张图片指纹的汉明距离越小越相似。
+
+/// 指纹位数，即汉明距离的上限
+pub const HASH_BITS: u32 = 64;
+
+/// 计算图片的 64 位 dHash
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+/// 两个指纹的汉明距离
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 依次读取文件，返回第一张能打开的图片的 dHash；都打不开时返回 None
+pub fn dhash_files(paths: &[PathBuf]) -> Option<u64> {
+    paths.iter().find_map(|path| image::open(path).ok()).map(|img| dhash(&img))
+}
@@ -0,0 +1,220 @@
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use md5::Md5;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+// 剪贴内容检查器
+//
+// 每个检测器识别一种格式并给出派生视图（时间戳 → 日期、Base64 → 文本等）。
+// 检测器按注册顺序运行，不匹配时返回 None；新增格式只需在 DETECTORS 中注册。
+
+/// 单个派生字段
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+}
+
+/// 一个检测器的结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Inspection {
+    pub detector: &'static str,
+    pub label: &'static str,
+    pub fields: Vec<Field>,
+}
+
+/// 已注册的检测器
+pub struct Detector {
+    pub id: &'static str,
+    pub label: &'static str,
+    inspect: fn(&str) -> Option<Vec<Field>>,
+}
+
+static DETECTORS: &[Detector] = &[
+    Detector { id: "epoch", label: "Unix 时间戳", inspect: inspect_epoch },
+    Detector { id: "jwt", label: "JWT", inspect: inspect_jwt },
+    Detector { id: "url", label: "URL", inspect: inspect_url },
+    Detector { id: "hex", label: "十六进制", inspect: inspect_hex },
+    Detector { id: "base64", label: "Base64", inspect: inspect_base64 },
+    Detector { id: "digest", label: "摘要", inspect: inspect_digest },
+];
+
+/// 所有已注册的检测器
+pub fn detectors() -> &'static [Detector] {
+    DETECTORS
+}
+
+/// 运行所有检测器，返回匹配的结果
+pub fn inspect(text: &str) -> Vec<Inspection> {
+    DETECTORS.iter().filter_map(|detector| run_detector(detector, text)).collect()
+}
+
+/// 运行指定检测器
+pub fn run(id: &str, text: &str) -> Option<Inspection> {
+    DETECTORS.iter().find(|d| d.id == id).and_then(|detector| run_detector(detector, text))
+}
+
+fn run_detector(detector: &Detector, text: &str) -> Option<Inspection> {
+    let fields = (detector.inspect)(text)?;
+    Some(Inspection { detector: detector.id, label: detector.label, fields })
+}
+
+fn field(name: impl Into<String>, value: impl Into<String>) -> Field {
+    Field { name: name.into(), value: value.into() }
+}
+
+// ==========================================
+// 检测器
+// ==========================================
+
+/// 合理的时间戳范围：1973-03 至 2100-01（秒）
+const EPOCH_MIN_SECS: i64 = 100_000_000;
+const EPOCH_MAX_SECS: i64 = 4_102_444_800;
+
+/// 秒或毫秒级 Unix 时间戳
+fn inspect_epoch(text: &str) -> Option<Vec<Field>> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() || trimmed.len() > 13 || !trimmed.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value: i64 = trimmed.parse().ok()?;
+    let (unit, millis) = if (EPOCH_MIN_SECS..EPOCH_MAX_SECS).contains(&value) {
+        ("seconds", value * 1000)
+    } else if (EPOCH_MIN_SECS * 1000..EPOCH_MAX_SECS * 1000).contains(&value) {
+        ("milliseconds", value)
+    } else {
+        return None;
+    };
+
+    let utc = Utc.timestamp_millis_opt(millis).single()?;
+    let local: DateTime<Local> = utc.with_timezone(&Local);
+    Some(vec![
+        field("Unit", unit),
+        field("UTC", utc.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)),
+        field("Local", local.format("%Y-%m-%d %H:%M:%S %:z").to_string()),
+    ])
+}
+
+/// JWT：解码头部和载荷，不校验签名
+fn inspect_jwt(text: &str) -> Option<Vec<Field>> {
+    let trimmed = text.trim();
+    let token = trimmed.strip_prefix("Bearer ").unwrap_or(trimmed);
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let decode_json = |segment: &str| -> Option<serde_json::Value> {
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(segment.trim_end_matches('=')).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    };
+    let header = decode_json(parts[0]).filter(|h| h.get("alg").is_some() || h.get("typ").is_some())?;
+    let claims = decode_json(parts[1]).filter(serde_json::Value::is_object)?;
+
+    let mut fields = vec![
+        field("Header", serde_json::to_string_pretty(&header).ok()?),
+        field("Claims", serde_json::to_string_pretty(&claims).ok()?),
+    ];
+    for (claim, name) in [("iat", "Issued at"), ("nbf", "Not before"), ("exp", "Expires")] {
+        if let Some(time) = claims.get(claim).and_then(serde_json::Value::as_i64)
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        {
+            fields.push(field(name, time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)));
+        }
+    }
+    fields.push(field("Signature", "Not verified"));
+    Some(fields)
+}
+
+/// URL 的各部分和查询参数
+fn inspect_url(text: &str) -> Option<Vec<Field>> {
+    let trimmed = text.trim();
+    if trimmed.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = Url::parse(trimmed).ok()?;
+    let host = url.host_str()?;
+
+    let mut fields = vec![field("Scheme", url.scheme()), field("Host", host)];
+    if let Some(port) = url.port() {
+        fields.push(field("Port", port.to_string()));
+    }
+    if !url.username().is_empty() {
+        fields.push(field("User", url.username()));
+    }
+    fields.push(field("Path", url.path()));
+    for (key, value) in url.query_pairs() {
+        fields.push(field(format!("Query: {}", key), value));
+    }
+    if let Some(fragment) = url.fragment() {
+        fields.push(field("Fragment", fragment));
+    }
+    Some(fields)
+}
+
+/// 十六进制字节串（可带 0x 前缀、空格或冒号分隔），需包含 a-f 或 0x 前缀以区别于普通数字
+fn inspect_hex(text: &str) -> Option<Vec<Field>> {
+    let trimmed = text.trim();
+    let prefixed = trimmed.starts_with("0x") || trimmed.starts_with("0X");
+    let digits: String = trimmed.get(if prefixed { 2 } else { 0 }..)?
+        .chars()
+        .filter(|c| !matches!(c, ' ' | ':' | '-'))
+        .collect();
+    if digits.len() < 2 || !digits.len().is_multiple_of(2) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    if !prefixed && !digits.chars().any(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let bytes = hex::decode(&digits).ok()?;
+    let ascii: String = bytes.iter()
+        .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+        .collect();
+    let mut fields = vec![field("Bytes", bytes.len().to_string()), field("ASCII", ascii)];
+    if bytes.len() <= 8 {
+        let value = bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        fields.push(field("Decimal", value.to_string()));
+    }
+    Some(fields)
+}
+
+/// Base64 / Base64url，仅当解码结果为可读文本时报告
+fn inspect_base64(text: &str) -> Option<Vec<Field>> {
+    // 允许按行折叠的 Base64，但含空格的文本视为普通句子
+    if text.trim().contains(' ') {
+        return None;
+    }
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let unpadded = compact.trim_end_matches('=');
+    if unpadded.len() < 4 || unpadded.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let url_safe = unpadded.contains(['-', '_']);
+    let engine = if url_safe { &general_purpose::URL_SAFE_NO_PAD } else { &general_purpose::STANDARD_NO_PAD };
+    let bytes = engine.decode(unpadded).ok()?;
+    let decoded = String::from_utf8(bytes).ok()?;
+    let printable = decoded.chars().filter(|c| !c.is_control() || c.is_whitespace()).count();
+    if decoded.is_empty() || printable * 10 < decoded.chars().count() * 9 {
+        return None;
+    }
+    Some(vec![
+        field("Alphabet", if url_safe { "Base64url" } else { "Base64" }),
+        field("Text", decoded),
+    ])
+}
+
+/// 内容摘要
+fn inspect_digest(text: &str) -> Option<Vec<Field>> {
+    if text.is_empty() {
+        return None;
+    }
+    let bytes = text.as_bytes();
+    Some(vec![
+        field("SHA-256", hex::encode(Sha256::digest(bytes))),
+        field("MD5", hex::encode(Md5::digest(bytes))),
+        field("BLAKE3", blake3::hash(bytes).to_hex().to_string()),
+    ])
+}
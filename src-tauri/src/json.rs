@@ -0,0 +1,312 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+// JSON 检查器
+//
+// - 校验 JSON 并给出错误位置
+// - 格式化 / 压缩
+// - 路径查询，支持 JSONPath 和 jq 的常用子集：
+//   $.store.book[0].title   $..author   $.items[*].id   $.list[1:3]   $['key with space']
+//   .store.book[0].title    .items[].id  ."key with space"  .a | .b
+
+/// 带位置的解析错误（行列从 1 开始，offset 为字节偏移）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JsonError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+/// 校验结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JsonReport {
+    pub valid: bool,
+    /// 根节点类型：object / array / string / number / boolean / null
+    pub root: Option<&'static str>,
+    pub error: Option<JsonError>,
+}
+
+/// 解析 JSON，失败时返回带位置的错误
+pub fn parse(text: &str) -> std::result::Result<Value, JsonError> {
+    serde_json::from_str(text).map_err(|e| {
+        let message = e.to_string();
+        let suffix = format!(" at line {} column {}", e.line(), e.column());
+        JsonError {
+            message: message.strip_suffix(&suffix).unwrap_or(&message).to_string(),
+            line: e.line(),
+            column: e.column(),
+            offset: byte_offset(text, e.line(), e.column()),
+        }
+    })
+}
+
+/// 校验 JSON 文本
+pub fn validate(text: &str) -> JsonReport {
+    match parse(text) {
+        Ok(value) => JsonReport { valid: true, root: Some(type_name(&value)), error: None },
+        Err(error) => JsonReport { valid: false, root: None, error: Some(error) },
+    }
+}
+
+/// 是否为 JSON 对象或数组（纯数字、字符串等标量不算）
+pub fn looks_like_json(text: &str) -> bool {
+    let trimmed = text.trim();
+    let bracketed = (trimmed.starts_with('{') && trimmed.ends_with('}'))
+        || (trimmed.starts_with('[') && trimmed.ends_with(']'));
+    bracketed && serde_json::from_str::<serde::de::IgnoredAny>(trimmed).is_ok()
+}
+
+pub fn pretty(text: &str) -> Result<String> {
+    let value = parse(text.trim()).map_err(into_anyhow)?;
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+pub fn minify(text: &str) -> Result<String> {
+    let value = parse(text.trim()).map_err(into_anyhow)?;
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// 对 JSON 文本执行路径查询
+pub fn query_text(text: &str, expression: &str) -> Result<Vec<Value>> {
+    let value = parse(text.trim()).map_err(into_anyhow)?;
+    query(&value, expression)
+}
+
+/// 执行路径查询，返回所有匹配的值
+pub fn query(value: &Value, expression: &str) -> Result<Vec<Value>> {
+    let steps = parse_expression(expression)?;
+    let mut current = vec![value];
+    for step in &steps {
+        current = apply_step(step, current);
+    }
+    Ok(current.into_iter().cloned().collect())
+}
+
+/// 查询结果转为可粘贴的文本：字符串原样输出，其他值格式化为 JSON，多个结果按行分隔
+pub fn format_matches(matches: &[Value]) -> String {
+    matches.iter()
+        .map(|value| match value {
+            Value::String(s) => s.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn into_anyhow(error: JsonError) -> anyhow::Error {
+    anyhow!("{} at line {} column {}", error.message, error.line, error.column)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+/// serde_json 的行列（从 1 开始，列按字节计）转换为字节偏移
+fn byte_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text.split_inclusive('\n').take(line.saturating_sub(1)).map(str::len).sum();
+    (line_start + column.saturating_sub(1)).min(text.len())
+}
+
+// ==========================================
+// 路径表达式
+// ==========================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Wildcard,
+    /// 递归下降：当前节点及其所有后代
+    Descendants,
+}
+
+fn parse_expression(expression: &str) -> Result<Vec<Step>> {
+    let expression = expression.trim();
+    let rest = if let Some(rest) = expression.strip_prefix('$') {
+        rest
+    } else if expression.starts_with('.') {
+        expression
+    } else {
+        bail!("Expression must start with '$' (JSONPath) or '.' (jq)");
+    };
+
+    let chars: Vec<char> = rest.chars().collect();
+    let mut steps = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            // jq 的可选标记不影响结果：不存在的键本来就不产生匹配
+            '?' => i += 1,
+            // jq 管道：路径表达式之间的管道等价于串联
+            '|' => {
+                i += 1;
+                while i < chars.len() && chars[i].is_whitespace() {
+                    i += 1;
+                }
+                if chars.get(i) != Some(&'.') {
+                    bail!("Expected '.' after '|' at position {}", i);
+                }
+            }
+            '.' if chars.get(i + 1) == Some(&'.') => {
+                steps.push(Step::Descendants);
+                i += 2;
+                i = parse_member(&chars, i, &mut steps)?;
+            }
+            '.' => {
+                i += 1;
+                i = parse_member(&chars, i, &mut steps)?;
+            }
+            '[' => i = parse_bracket(&chars, i, &mut steps)?,
+            c => bail!("Unexpected '{}' at position {}", c, i),
+        }
+    }
+    Ok(steps)
+}
+
+/// 解析 '.' 之后的成员：标识符、'*' 或带引号的键；其后为 '[' 或结束时不产生步骤
+fn parse_member(chars: &[char], mut i: usize, steps: &mut Vec<Step>) -> Result<usize> {
+    match chars.get(i) {
+        Some('*') => {
+            steps.push(Step::Wildcard);
+            Ok(i + 1)
+        }
+        Some(&quote @ ('"' | '\'')) => {
+            let (key, next) = parse_quoted(chars, i, quote)?;
+            steps.push(Step::Key(key));
+            Ok(next)
+        }
+        Some(c) if is_ident_char(*c) => {
+            let start = i;
+            while i < chars.len() && is_ident_char(chars[i]) {
+                i += 1;
+            }
+            steps.push(Step::Key(chars[start..i].iter().collect()));
+            Ok(i)
+        }
+        _ => Ok(i),
+    }
+}
+
+fn parse_bracket(chars: &[char], start: usize, steps: &mut Vec<Step>) -> Result<usize> {
+    let mut i = start + 1;
+    while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+        i += 1;
+    }
+    let step = match chars.get(i) {
+        // jq 的 .[] 和 JSONPath 的 [*]
+        Some(']') => Step::Wildcard,
+        Some('*') => {
+            i += 1;
+            Step::Wildcard
+        }
+        Some(&quote @ ('"' | '\'')) => {
+            let (key, next) = parse_quoted(chars, i, quote)?;
+            i = next;
+            Step::Key(key)
+        }
+        _ => {
+            let end = chars[i..].iter().position(|&c| c == ']')
+                .map(|p| i + p)
+                .ok_or_else(|| anyhow!("Unclosed '[' at position {}", start))?;
+            let body: String = chars[i..end].iter().collect();
+            i = end;
+            parse_index(body.trim()).ok_or_else(|| anyhow!("Invalid index '{}' at position {}", body.trim(), start))?
+        }
+    };
+    while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+        i += 1;
+    }
+    if chars.get(i) != Some(&']') {
+        bail!("Expected ']' at position {}", i);
+    }
+    steps.push(step);
+    Ok(i + 1)
+}
+
+fn parse_index(body: &str) -> Option<Step> {
+    match body.split_once(':') {
+        Some((from, to)) => {
+            let bound = |s: &str| if s.trim().is_empty() { Some(None) } else { s.trim().parse().ok().map(Some) };
+            Some(Step::Slice(bound(from)?, bound(to)?))
+        }
+        None => body.parse().ok().map(Step::Index),
+    }
+}
+
+fn parse_quoted(chars: &[char], start: usize, quote: char) -> Result<(String, usize)> {
+    let mut key = String::new();
+    let mut i = start + 1;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            '\\' => {
+                let escaped = chars.get(i + 1).ok_or_else(|| anyhow!("Unterminated string at position {}", start))?;
+                key.push(*escaped);
+                i += 2;
+            }
+            c if c == quote => return Ok((key, i + 1)),
+            c => {
+                key.push(c);
+                i += 1;
+            }
+        }
+    }
+    bail!("Unterminated string at position {}", start)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '$'
+}
+
+fn apply_step<'a>(step: &Step, current: Vec<&'a Value>) -> Vec<&'a Value> {
+    let mut next = Vec::new();
+    for value in current {
+        match step {
+            Step::Key(key) => next.extend(value.get(key.as_str())),
+            Step::Index(index) => {
+                if let Value::Array(items) = value {
+                    let index = if *index < 0 { items.len() as i64 + index } else { *index };
+                    next.extend(usize::try_from(index).ok().and_then(|i| items.get(i)));
+                }
+            }
+            Step::Slice(from, to) => {
+                if let Value::Array(items) = value {
+                    let len = items.len() as i64;
+                    let clamp = |i: i64| (if i < 0 { len + i } else { i }).clamp(0, len) as usize;
+                    let from = clamp(from.unwrap_or(0));
+                    let to = clamp(to.unwrap_or(len));
+                    if from < to {
+                        next.extend(&items[from..to]);
+                    }
+                }
+            }
+            Step::Wildcard => match value {
+                Value::Object(map) => next.extend(map.values()),
+                Value::Array(items) => next.extend(items),
+                _ => {}
+            },
+            Step::Descendants => collect_descendants(value, &mut next),
+        }
+    }
+    next
+}
+
+fn collect_descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(value);
+    match value {
+        Value::Object(map) => map.values().for_each(|v| collect_descendants(v, out)),
+        Value::Array(items) => items.iter().for_each(|v| collect_descendants(v, out)),
+        _ => {}
+    }
+}
@@ -1,19 +1,49 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+pub mod action;
+pub mod blob;
+pub mod classify;
+pub mod codec;
 pub mod clipboard;
+pub mod color;
+pub mod guard;
+pub mod highlight;
+pub mod html;
+pub mod imagehash;
+pub mod inspect;
+pub mod json;
+pub mod markdown;
+pub mod palette;
 pub mod persist;
+pub mod pipeline;
+pub mod protocol;
+pub mod qr;
 pub mod setting;
+pub mod table;
+pub mod template;
+pub mod thumbnail;
+pub mod transform;
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Mutex, Arc};
 use std::thread;
 
 use base64::{Engine as _, engine::general_purpose};
 use chrono::Utc;
 use clipboard::ClipEvent;
-use persist::{ClipItem, Storage};
+use guard::Guarded;
+use persist::{Board, ClipFilter, ClipItem, ClipPage, SavedSearch, SearchPage, SimilarImage, Snippet, Storage};
+use setting::Settings;
 
 use tauri::{Manager, Emitter, AppHandle};
+use tauri_plugin_opener::OpenerExt;
 
-use crate::persist::ClipData;
+use crate::persist::{ClipData, PreparedImage};
+use crate::pipeline::ImagePipeline;
+
+/// 图片流水线的处理线程数和排队上限
+const IMAGE_WORKERS: usize = 2;
+const IMAGE_QUEUE_CAPACITY: usize = 4;
 
 #[tauri::command]
 fn get_recent_clips(
@@ -25,6 +55,16 @@ fn get_recent_clips(
     storage.get_recent(limit, offset).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn get_clips_page(
+    state: tauri::State<AppState>,
+    limit: usize,
+    cursor: Option<String>,
+) -> Result<ClipPage, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.get_page(limit, cursor.as_deref()).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn get_total_count(state: tauri::State<AppState>) -> Result<i64, String> {
     let storage = state.storage.lock().map_err(|_| "Lock error")?;
@@ -40,10 +80,14 @@ fn clear_unpinned_clips(state: tauri::State<AppState>) -> Result<i64, String> {
 #[tauri::command]
 fn search_clips(
     state: tauri::State<AppState>, 
-    query: String
-) -> Result<Vec<ClipItem>, String> {
+    query: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<SearchPage, String> {
     let storage = state.storage.lock().map_err(|_| "Lock error")?;
-    storage.search(&query).map_err(|e| e.to_string())
+    storage
+        .search_page(&query, limit.unwrap_or(50), offset.unwrap_or(0))
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -53,8 +97,12 @@ fn get_clip_content(
 ) -> Result<serde_json::Value, String> {
     let storage = state.storage.lock().map_err(|_| "Lock error")?;
     let content = storage.get_content(id).map_err(|e| e.to_string())?;
-    
-    let json_value = match content {
+    Ok(clip_data_to_json(content))
+}
+
+/// 将记录内容转换为前端使用的 JSON 结构
+fn clip_data_to_json(content: ClipData) -> serde_json::Value {
+    match content {
         ClipData::Text(text) => serde_json::json!({
             "type": "text",
             "data": text
@@ -75,9 +123,7 @@ fn get_clip_content(
             "type": "color",
             "data": color
         }),
-    };
-    
-    Ok(json_value)
+    }
 }
 
 #[tauri::command]
@@ -101,6 +147,475 @@ fn delete_clip(
     Ok(result)
 }
 
+#[tauri::command]
+fn list_saved_searches(state: tauri::State<AppState>) -> Result<Vec<SavedSearch>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.list_saved_searches().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn create_saved_search(
+    state: tauri::State<AppState>,
+    name: String,
+    filter: ClipFilter,
+) -> Result<i64, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.create_saved_search(&name, &filter).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn update_saved_search(
+    state: tauri::State<AppState>,
+    id: i64,
+    name: String,
+    filter: ClipFilter,
+) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.update_saved_search(id, &name, &filter).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn delete_saved_search(state: tauri::State<AppState>, id: i64) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.delete_saved_search(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_saved_search_clips(
+    state: tauri::State<AppState>,
+    id: i64,
+    limit: usize,
+    offset: usize,
+) -> Result<Vec<ClipItem>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.get_saved_search_clips(id, limit, offset).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_boards(state: tauri::State<AppState>) -> Result<Vec<Board>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.list_boards().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn create_board(state: tauri::State<AppState>, name: String) -> Result<i64, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.create_board(&name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn rename_board(state: tauri::State<AppState>, id: i64, name: String) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.rename_board(id, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn delete_board(state: tauri::State<AppState>, id: i64) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.delete_board(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn add_clip_to_board(
+    state: tauri::State<AppState>,
+    board_id: i64,
+    id: i64,
+) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.add_to_board(board_id, id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn remove_clip_from_board(
+    state: tauri::State<AppState>,
+    board_id: i64,
+    id: i64,
+) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.remove_from_board(board_id, id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn reorder_board(
+    state: tauri::State<AppState>,
+    board_id: i64,
+    ids: Vec<i64>,
+) -> Result<(), String> {
+    let mut storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.reorder_board(board_id, &ids).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_board_clips(state: tauri::State<AppState>, board_id: i64) -> Result<Vec<ClipItem>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.get_board_clips(board_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_clip_boards(state: tauri::State<AppState>, id: i64) -> Result<Vec<i64>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.get_clip_boards(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_snippets(state: tauri::State<AppState>) -> Result<Vec<Snippet>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.list_snippets().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn create_snippet(
+    state: tauri::State<AppState>,
+    name: String,
+    abbreviation: Option<String>,
+    body: String,
+) -> Result<i64, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage
+        .create_snippet(&name, abbreviation.as_deref(), &body)
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn update_snippet(
+    state: tauri::State<AppState>,
+    id: i64,
+    name: String,
+    abbreviation: Option<String>,
+    body: String,
+) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage
+        .update_snippet(id, &name, abbreviation.as_deref(), &body)
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn delete_snippet(state: tauri::State<AppState>, id: i64) -> Result<(), String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.delete_snippet(id).map_err(|e| e.to_string())
+}
+
+/// 展开代码片段模板，返回最终文本
+#[tauri::command]
+fn render_snippet(
+    state: tauri::State<AppState>,
+    id: i64,
+    inputs: HashMap<String, String>,
+) -> Result<String, String> {
+    let snippet = {
+        let storage = state.storage.lock().map_err(|_| "Lock error")?;
+        storage.get_snippet(id).map_err(|e| e.to_string())?
+    };
+    let ctx = template::TemplateContext::new(clipboard::read_text(), inputs);
+    template::render(&snippet.body, &ctx).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_transforms() -> Vec<transform::TransformInfo> {
+    transform::list()
+}
+
+/// 预览转换结果（不写入剪贴板）
+#[tauri::command]
+fn preview_transform(
+    state: tauri::State<AppState>,
+    id: i64,
+    transform: String,
+) -> Result<serde_json::Value, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let content = storage.get_content(id).map_err(|e| e.to_string())?;
+    let result = transform::apply_with(&transform, &content, storage.settings()).map_err(|e| e.to_string())?;
+    Ok(clip_data_to_json(result))
+}
+
+/// 识别文本记录的编程语言
+#[tauri::command]
+fn detect_clip_language(state: tauri::State<AppState>, id: i64) -> Result<Option<String>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let text = match storage.get_content(id).map_err(|e| e.to_string())? {
+        ClipData::Text(text) => text,
+        ClipData::Html { text, .. } => text,
+        _ => return Ok(None),
+    };
+    Ok(highlight::detect_language(&text).map(str::to_string))
+}
+
+/// 解析记录中的表格（TSV/CSV 文本或 HTML 表格），不是表格时返回 None
+#[tauri::command]
+fn get_clip_table(state: tauri::State<AppState>, id: i64) -> Result<Option<table::Table>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let table = match storage.get_content(id).map_err(|e| e.to_string())? {
+        ClipData::Text(text) => table::detect(&text),
+        ClipData::Html { text, html } => table::parse_html(&html).or_else(|| table::detect(&text)),
+        _ => None,
+    };
+    Ok(table)
+}
+
+/// 校验 JSON 记录，返回错误位置
+#[tauri::command]
+fn validate_clip_json(state: tauri::State<AppState>, id: i64) -> Result<json::JsonReport, String> {
+    let text = clip_text(&state, id)?;
+    Ok(json::validate(text.trim()))
+}
+
+/// 对 JSON 记录执行 JSONPath / jq 子集查询
+#[tauri::command]
+fn query_clip_json(
+    state: tauri::State<AppState>,
+    id: i64,
+    expression: String,
+) -> Result<Vec<serde_json::Value>, String> {
+    let text = clip_text(&state, id)?;
+    json::query_text(&text, &expression).map_err(|e| e.to_string())
+}
+
+/// 将查询结果写入剪贴板（字符串原样写入，其他值写入 JSON）
+#[tauri::command]
+fn paste_json_query(state: tauri::State<AppState>, id: i64, expression: String) -> Result<(), String> {
+    let text = clip_text(&state, id)?;
+    let matches = json::query_text(&text, &expression).map_err(|e| e.to_string())?;
+    if matches.is_empty() {
+        return Err(format!("No match for {}", expression));
+    }
+    clipboard::write(&ClipData::Text(json::format_matches(&matches))).map_err(|e| e.to_string())
+}
+
+/// 检查文本记录：时间戳、Base64、JWT、十六进制、URL 和摘要等派生视图
+#[tauri::command]
+fn inspect_clip(state: tauri::State<AppState>, id: i64) -> Result<Vec<inspect::Inspection>, String> {
+    let text = clip_text(&state, id)?;
+    Ok(inspect::inspect(&text))
+}
+
+/// 记录的分类元数据，以标签为键（如 {"url": {"domains": [...]}}）
+#[tauri::command]
+fn get_clip_metadata(
+    state: tauri::State<AppState>,
+    id: i64,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.get_metadata(id).map_err(|e| e.to_string())
+}
+
+/// 用当前分类规则重新计算所有文本记录的标签，返回有变化的记录数
+#[tauri::command]
+fn reclassify_clips(state: tauri::State<AppState>) -> Result<usize, String> {
+    let mut storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.reclassify_all().map_err(|e| e.to_string())
+}
+
+/// 在后台按当前编码设置重新压缩已保存的原图，返回待处理的图片数
+///
+/// 读取、解码和编码都在锁外完成，不阻塞剪贴板捕获；进度通过 images://recompress-progress 事件推送。
+#[tauri::command]
+fn recompress_images(app: AppHandle, state: tauri::State<AppState>) -> Result<usize, String> {
+    if state.recompressing.swap(true, Ordering::SeqCst) {
+        return Err("Recompression is already running".to_string());
+    }
+    let ids = match state.storage.lock().map_err(|_| "Lock error".to_string()).and_then(|storage| {
+        storage.images_to_recompress().map_err(|e| e.to_string())
+    }) {
+        Ok(ids) => ids,
+        Err(e) => {
+            state.recompressing.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    let total = ids.len();
+    let storage = Arc::clone(&state.storage);
+    let running = Arc::clone(&state.recompressing);
+    thread::spawn(move || {
+        let mut saved_bytes: i64 = 0;
+        for (done, id) in ids.into_iter().enumerate() {
+            let job = match storage.lock() {
+                Ok(storage) => storage.recompress_job(id).map(|job| job.map(|job| (job, storage.blob_store()))),
+                Err(_) => break,
+            };
+            // 读取、解码和编码在锁外完成，只有登记新文件时持有锁
+            let result = job.and_then(|job| match job {
+                Some((job, blobs)) => {
+                    let image = job.run(&blobs)?;
+                    storage.lock()
+                        .map_err(|_| anyhow::anyhow!("Lock error"))
+                        .and_then(|mut storage| storage.save_recompressed(image))
+                }
+                None => Ok(None),
+            });
+            match result {
+                Ok(Some((old_size, new_size))) => saved_bytes += old_size as i64 - new_size as i64,
+                Ok(None) => {}
+                Err(e) => eprintln!("❌ 重新压缩图片失败 ID {}: {:?}", id, e),
+            }
+            let _ = app.emit("images://recompress-progress", serde_json::json!({
+                "done": done + 1,
+                "total": total,
+                "saved_bytes": saved_bytes
+            }));
+        }
+        running.store(false, Ordering::SeqCst);
+    });
+    Ok(total)
+}
+
+/// 记录可用的动作（打开链接、显示文件、写邮件、复制颜色等）
+#[tauri::command]
+fn list_clip_actions(state: tauri::State<AppState>, id: i64) -> Result<Vec<action::ActionInfo>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let content = storage.get_content(id).map_err(|e| e.to_string())?;
+    Ok(action::available(&content))
+}
+
+/// 执行记录上的动作
+#[tauri::command]
+fn run_clip_action(app: AppHandle, state: tauri::State<AppState>, id: i64, action: String) -> Result<(), String> {
+    let content = {
+        let storage = state.storage.lock().map_err(|_| "Lock error")?;
+        storage.get_content(id).map_err(|e| e.to_string())?
+    };
+    match action::resolve(&action, &content).map_err(|e| e.to_string())? {
+        action::Effect::OpenUrl(url) => app.opener().open_url(url, None::<&str>).map_err(|e| e.to_string()),
+        action::Effect::Reveal(path) => app.opener().reveal_item_in_dir(path).map_err(|e| e.to_string()),
+        action::Effect::Copy(text) => clipboard::write(&ClipData::Text(text)).map_err(|e| e.to_string()),
+    }
+}
+
+/// 将颜色记录转换为指定写法
+#[tauri::command]
+fn convert_color(state: tauri::State<AppState>, id: i64, notation: color::Notation) -> Result<String, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let color = storage.get_color(id).map_err(|e| e.to_string())?;
+    color.format(notation).ok_or_else(|| "No CSS color name matches this color".to_string())
+}
+
+/// 两条颜色记录之间的 WCAG 对比度
+#[tauri::command]
+fn color_contrast(state: tauri::State<AppState>, foreground: i64, background: i64) -> Result<color::Contrast, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let foreground = storage.get_color(foreground).map_err(|e| e.to_string())?;
+    let background = storage.get_color(background).map_err(|e| e.to_string())?;
+    Ok(color::contrast(foreground, background))
+}
+
+/// 颜色记录的浅色 / 深色序列
+#[tauri::command]
+fn color_variants(state: tauri::State<AppState>, id: i64, steps: usize) -> Result<color::Variants, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let color = storage.get_color(id).map_err(|e| e.to_string())?;
+    Ok(color.variants(steps.min(20)))
+}
+
+/// 图片记录的主色调色板
+#[tauri::command]
+fn get_clip_palette(state: tauri::State<AppState>, id: i64) -> Result<Vec<palette::Swatch>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.get_palette(id).map_err(|e| e.to_string())
+}
+
+/// 把调色板中的一种颜色保存为颜色记录
+#[tauri::command]
+fn save_palette_color(state: tauri::State<AppState>, id: i64, index: usize) -> Result<i64, String> {
+    let mut storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.save_palette_color(id, index).map_err(|e| e.to_string())
+}
+
+/// 图片中识别出的二维码和条形码内容
+#[tauri::command]
+fn get_qr_codes(state: tauri::State<AppState>, id: i64) -> Result<Vec<String>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.get_qr_codes(id).map_err(|e| e.to_string())
+}
+
+/// 把识别出的一条二维码内容保存为文本记录
+#[tauri::command]
+fn save_qr_text(state: tauri::State<AppState>, id: i64, index: usize) -> Result<i64, String> {
+    let mut storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.save_qr_text(id, index).map_err(|e| e.to_string())
+}
+
+/// 把文本记录生成为二维码图片记录，返回新记录的 ID
+///
+/// 与图片流水线一致，编码、压缩和写文件都在锁外完成。
+#[tauri::command]
+fn create_qr_image(state: tauri::State<AppState>, id: i64) -> Result<i64, String> {
+    let (text, options, blobs) = {
+        let storage = state.storage.lock().map_err(|_| "Lock error")?;
+        (storage.qr_source_text(id).map_err(|e| e.to_string())?, storage.image_options(), storage.blob_store())
+    };
+    let prepared = PreparedImage::from_qr(&text, options, &blobs).map_err(|e| e.to_string())?;
+    let mut storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.insert_image(prepared).map(|(id, _)| id).map_err(|e| e.to_string())
+}
+
+/// 与指定图片相似的图片，threshold 缺省时使用设置中的阈值
+#[tauri::command]
+fn find_similar_images(state: tauri::State<AppState>, id: i64, threshold: Option<u32>) -> Result<Vec<SimilarImage>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.find_similar_images(id, threshold).map_err(|e| e.to_string())
+}
+
+/// 按相似程度归组的图片记录 ID
+#[tauri::command]
+fn similar_image_groups(state: tauri::State<AppState>) -> Result<Vec<Vec<i64>>, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.similar_image_groups().map_err(|e| e.to_string())
+}
+
+/// 取出文本类记录的文本内容
+fn clip_text(state: &tauri::State<AppState>, id: i64) -> Result<String, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    match storage.get_content(id).map_err(|e| e.to_string())? {
+        ClipData::Text(text) | ClipData::Color(text) => Ok(text),
+        ClipData::Html { text, .. } => Ok(text),
+        _ => Err("Clip is not text".to_string()),
+    }
+}
+
+/// 可用的代码高亮主题
+#[tauri::command]
+fn list_highlight_themes() -> Vec<&'static str> {
+    highlight::theme_names()
+}
+
+#[tauri::command]
+fn get_settings(state: tauri::State<AppState>) -> Result<Settings, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    Ok(storage.settings().clone())
+}
+
+#[tauri::command]
+fn update_settings(state: tauri::State<AppState>, settings: Settings) -> Result<(), String> {
+    let mut storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.update_settings(settings).map_err(|e| e.to_string())?;
+    guard::publish(&storage.settings().capture_limits());
+    Ok(())
+}
+
+/// 导出为 Markdown（HTML 记录转换标记，其他记录返回其文本）
+#[tauri::command]
+fn export_clip_markdown(state: tauri::State<AppState>, id: i64) -> Result<String, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    let content = storage.get_content(id).map_err(|e| e.to_string())?;
+    transform::apply_text("markdown", &content).map_err(|e| e.to_string())
+}
+
+/// 应用转换并写入剪贴板
+#[tauri::command]
+fn paste_transformed(
+    state: tauri::State<AppState>,
+    id: i64,
+    transform: String,
+) -> Result<(), String> {
+    let (content, settings) = {
+        let storage = state.storage.lock().map_err(|_| "Lock error")?;
+        (storage.get_content(id).map_err(|e| e.to_string())?, storage.settings().clone())
+    };
+    let result = transform::apply_with(&transform, &content, &settings).map_err(|e| e.to_string())?;
+    clipboard::write(&result).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn toggle_window(app: AppHandle) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("main") {
@@ -139,6 +654,7 @@ fn open_accessibility_settings() -> Result<(), String> {
     }
 }
 
+/// 原图或默认缩略图在 pastee:// 协议下的路径，前端用 convertFileSrc(path, "pastee") 转为 URL
 #[tauri::command]
 fn get_image_url(
     state: tauri::State<AppState>,
@@ -146,26 +662,76 @@ fn get_image_url(
     thumbnail: bool,
 ) -> Result<String, String> {
     let storage = state.storage.lock().map_err(|_| "Lock error")?;
-    let (image_path, thumbnail_path) = storage
-        .get_image_paths(id)
-        .map_err(|e| e.to_string())?;
-    
-    // 返回相对路径，前端将通过 convertFileSrc 转换
-    let path = if thumbnail { thumbnail_path } else { image_path };
-    Ok(path)
+    // 确认是图片记录，文件由协议按 ID 读取
+    storage.get_image_paths(id).map_err(|e| e.to_string())?;
+    Ok(protocol::image_path(id, thumbnail))
+}
+
+/// 指定尺寸和倍率的缩略图在 pastee:// 协议下的路径，未缓存的缩略图在请求时生成
+#[tauri::command]
+fn get_thumbnail_url(state: tauri::State<AppState>, id: i64, size: String, scale: u32) -> Result<String, String> {
+    let storage = state.storage.lock().map_err(|_| "Lock error")?;
+    storage.find_thumbnail(id, &size, scale).map_err(|e| e.to_string())?;
+    Ok(protocol::thumbnail_path(id, &size, scale))
+}
+
+/// 清理旧规格的缩略图并在后台生成缺失的缩略图，返回待生成的数量
+///
+/// 进度通过 images://thumbnail-progress 事件推送。
+#[tauri::command]
+fn regenerate_thumbnails(app: AppHandle, state: tauri::State<AppState>) -> Result<usize, String> {
+    if state.regenerating_thumbnails.swap(true, Ordering::SeqCst) {
+        return Err("Thumbnail regeneration is already running".to_string());
+    }
+    let jobs = match state.storage.lock().map_err(|_| "Lock error".to_string()).and_then(|mut storage| {
+        storage.prune_thumbnails().and_then(|_| storage.missing_thumbnails()).map_err(|e| e.to_string())
+    }) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            state.regenerating_thumbnails.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    let total = jobs.len();
+    let storage = Arc::clone(&state.storage);
+    let running = Arc::clone(&state.regenerating_thumbnails);
+    thread::spawn(move || {
+        for (done, job) in jobs.into_iter().enumerate() {
+            let saved = job.render().and_then(|bytes| {
+                let mut storage = storage.lock().map_err(|_| anyhow::anyhow!("Lock error"))?;
+                storage.save_thumbnail(&job, &bytes)
+            });
+            if let Err(e) = saved {
+                eprintln!("❌ 生成缩略图失败 ID {}: {:?}", job.record_id, e);
+            }
+            let _ = app.emit("images://thumbnail-progress", serde_json::json!({
+                "done": done + 1,
+                "total": total
+            }));
+        }
+        running.store(false, Ordering::SeqCst);
+    });
+    Ok(total)
 }
 
 struct AppState {
-    storage: Mutex<Storage>,
+    // 与剪贴板监听线程共享同一个 Storage，设置修改对新捕获的内容立即生效
+    storage: Arc<Mutex<Storage>>,
     keep_window_open: Arc<Mutex<bool>>,
+    recompressing: Arc<AtomicBool>,
+    regenerating_thumbnails: Arc<AtomicBool>,
 }
 
 impl AppState {
     fn new(data_dir: std::path::PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
         let storage = Storage::new(&data_dir)?;
+        guard::publish(&storage.settings().capture_limits());
         Ok(AppState {
-            storage: Mutex::new(storage),
+            storage: Arc::new(Mutex::new(storage)),
             keep_window_open: Arc::new(Mutex::new(false)),
+            recompressing: Arc::new(AtomicBool::new(false)),
+            regenerating_thumbnails: Arc::new(AtomicBool::new(false)),
         })
     }
 }
@@ -182,8 +748,23 @@ pub fn run(rx: crossbeam_channel::Receiver<clipboard::ClipEvent>) {
         })
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
+        // 图片只通过 pastee:// 按记录 ID 提供，不开放文件系统
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let response = match app.try_state::<AppState>() {
+                    Some(state) => protocol::handle(&state.storage, &request),
+                    None => tauri::http::Response::builder()
+                        .status(tauri::http::StatusCode::SERVICE_UNAVAILABLE)
+                        .body(Vec::new())
+                        .expect("Static response parts are valid"),
+                };
+                responder.respond(response);
+            });
+        })
         .invoke_handler(tauri::generate_handler![
             get_recent_clips,
+            get_clips_page,
             get_total_count,
             clear_unpinned_clips,
             search_clips,
@@ -194,6 +775,55 @@ pub fn run(rx: crossbeam_channel::Receiver<clipboard::ClipEvent>) {
             set_keep_window_open,
             open_accessibility_settings,
             get_image_url,
+            list_saved_searches,
+            create_saved_search,
+            update_saved_search,
+            delete_saved_search,
+            get_saved_search_clips,
+            list_boards,
+            create_board,
+            rename_board,
+            delete_board,
+            add_clip_to_board,
+            remove_clip_from_board,
+            reorder_board,
+            get_board_clips,
+            get_clip_boards,
+            list_snippets,
+            create_snippet,
+            update_snippet,
+            delete_snippet,
+            render_snippet,
+            list_transforms,
+            preview_transform,
+            paste_transformed,
+            export_clip_markdown,
+            detect_clip_language,
+            get_clip_table,
+            validate_clip_json,
+            query_clip_json,
+            paste_json_query,
+            inspect_clip,
+            get_clip_metadata,
+            reclassify_clips,
+            list_clip_actions,
+            run_clip_action,
+            convert_color,
+            color_contrast,
+            color_variants,
+            get_clip_palette,
+            save_palette_color,
+            get_qr_codes,
+            save_qr_text,
+            create_qr_image,
+            find_similar_images,
+            similar_image_groups,
+            recompress_images,
+            get_thumbnail_url,
+            regenerate_thumbnails,
+            list_highlight_themes,
+            get_settings,
+            update_settings,
         ])
         .on_window_event(|_window, event| {
             match event {
@@ -215,9 +845,14 @@ pub fn handle_clipboard_event(
     app: tauri::AppHandle,
     storage: Arc<Mutex<Storage>>
 ) {
+    let images = ImagePipeline::new(Arc::clone(&storage), IMAGE_WORKERS, IMAGE_QUEUE_CAPACITY);
     loop {
-        match rx.recv() {
-            Ok(ClipEvent::Text(text)) => {
+        let event = match rx.recv() {
+            Ok(event) => guard_event(event, &app, &storage),
+            Err(_) => None,
+        };
+        match event {
+            Some(ClipEvent::Text(text)) => {
                 let trimmed_text = text.trim_start().to_string();
                 println!("✅ 捕获到文本: [ {} ]", trimmed_text);
                 
@@ -234,7 +869,7 @@ pub fn handle_clipboard_event(
                     "preview": trimmed_text
                 }));
             },
-            Ok(ClipEvent::Image { width, height, rgba_data }) => {
+            Some(ClipEvent::Image { width, height, rgba_data }) => {
                 println!("✅ 捕获到图片: [ {}x{}, {} bytes ]", width, height, rgba_data.len());
                 
                 // 立即发送"处理中"事件给前端
@@ -244,62 +879,47 @@ pub fn handle_clipboard_event(
                     "type": "image"
                 }));
                 
-                // 异步处理图片保存和缩略图生成
-                let storage_clone = Arc::clone(&storage);
+                // 交给图片流水线异步保存，编码和缩略图生成不持有数据库锁
                 let app_clone = app.clone();
-                thread::spawn(move || {
-                    if let Ok(mut store) = storage_clone.lock() {
-                        match store.add_image(width, height, rgba_data) {
-                            Ok((id, thumbnail_data)) => {
-                                // 将缩略图数据编码为 base64 发送给前端
-                                let base64_thumbnail = general_purpose::STANDARD.encode(&thumbnail_data);
-                                let _ = app_clone.emit("clipboard://image-ready", serde_json::json!({
-                                    "temp_id": temp_id,
+                let storage_clone = Arc::clone(&storage);
+                let submitted = images.submit(width, height, rgba_data, Box::new(move |result| {
+                    match result {
+                        Ok((id, thumbnail_data)) => {
+                            // 图片中识别出二维码时提示前端，可以另存为文本记录
+                            let codes = storage_clone.lock().ok().and_then(|store| store.get_qr_codes(id).ok());
+                            if let Some(codes) = codes.filter(|codes| !codes.is_empty()) {
+                                let _ = app_clone.emit("clipboard://qr-detected", serde_json::json!({
                                     "id": id,
-                                    "type": "image",
-                                    "thumbnail": base64_thumbnail
-                                }));
-                            }
-                            Err(e) => {
-                                eprintln!("❌ 保存图片失败: {}", e);
-                                let _ = app_clone.emit("clipboard://image-error", serde_json::json!({
-                                    "temp_id": temp_id,
-                                    "error": e.to_string()
+                                    "codes": codes
                                 }));
                             }
+                            // 将缩略图数据编码为 base64 发送给前端
+                            let base64_thumbnail = general_purpose::STANDARD.encode(&thumbnail_data);
+                            let _ = app_clone.emit("clipboard://image-ready", serde_json::json!({
+                                "temp_id": temp_id,
+                                "id": id,
+                                "type": "image",
+                                "thumbnail": base64_thumbnail
+                            }));
+                        }
+                        Err(e) => {
+                            eprintln!("❌ 保存图片失败: {}", e);
+                            let _ = app_clone.emit("clipboard://image-error", serde_json::json!({
+                                "temp_id": temp_id,
+                                "error": e.to_string()
+                            }));
                         }
                     }
-                });
+                }));
+                if let Err(e) = submitted {
+                    eprintln!("❌ 提交图片失败: {}", e);
+                }
             },
-            Ok(ClipEvent::Html(html)) => {
+            Some(ClipEvent::Html(html)) => {
                 println!("✅ 捕获到 HTML: [ {} bytes ]", html.len());
                 
                 // 从 HTML 中提取纯文本作为 preview
-                // 1. 移除 script 和 style 标签及其内容
-                let text_preview = html
-                    .replace(|c| c == '\n' || c == '\r', " ")
-                    .split('<')
-                    .enumerate()
-                    .filter_map(|(i, s)| {
-                        if i == 0 {
-                            Some(s.to_string()) // 第一段（标签前的文本）
-                        } else if let Some(pos) = s.find('>') {
-                            // 检查是否是 script 或 style 标签，跳过其内容
-                            let tag_name = s[..pos].split_whitespace().next().unwrap_or("");
-                            if tag_name.eq_ignore_ascii_case("script") || tag_name.eq_ignore_ascii_case("style") {
-                                None
-                            } else {
-                                Some(s[pos + 1..].to_string()) // 标签后的文本
-                            }
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join(" ")
-                    .split_whitespace()
-                    .collect::<Vec<_>>()
-                    .join(" ");
+                let text_preview = html::to_plain_text(&html);
                 
                 println!("📄 提取文本预览: [ {} ]", text_preview.chars().take(100).collect::<String>());
                 
@@ -315,7 +935,7 @@ pub fn handle_clipboard_event(
                     "preview": html.chars().take(100).collect::<String>()
                 }));
             },
-            Ok(ClipEvent::FileList(files)) => {
+            Some(ClipEvent::FileList(files)) => {
                 println!("✅ 捕获到文件列表: [ {} files ]", files.len());
                 
                 // 转换 PathBuf 为 String
@@ -336,10 +956,50 @@ pub fn handle_clipboard_event(
                     "preview": "Files"
                 }));
             },
-            Ok(ClipEvent::Error(e)) => {
+            Some(ClipEvent::Error(e)) => {
                 eprintln!("❌ 读取失败: {}", e);
             },
-            Err(_) => {}
+            None => {}
+        }
+    }
+}
+
+/// 按设置中的大小上限检查捕获事件，返回需要按原流程保存的事件
+///
+/// 超限时丢弃（推送 clipboard://capture-rejected）或另存完整内容后返回 None。
+fn guard_event(event: ClipEvent, app: &AppHandle, storage: &Mutex<Storage>) -> Option<ClipEvent> {
+    let limits = storage.lock()
+        .map(|store| store.settings().capture_limits())
+        .unwrap_or_default();
+    match guard::check(event, &limits) {
+        Guarded::Keep(event) => Some(event),
+        Guarded::Spill(event) => {
+            // 只有文本和 HTML 会另存
+            let kind = if matches!(event, ClipEvent::Html(_)) { "html" } else { "text" };
+            let saved = storage.lock()
+                .map_err(|_| anyhow::anyhow!("Lock error"))
+                .and_then(|mut store| match &event {
+                    ClipEvent::Html(html) => store.add_spilled_html(html, limits.max_html_bytes),
+                    ClipEvent::Text(text) => store.add_spilled_text(text, limits.max_text_bytes),
+                    _ => Ok(0),
+                });
+            match saved {
+                Ok(id) => {
+                    println!("✅ 超出上限的{}已另存完整内容: [ id={} ]", kind, id);
+                    let _ = app.emit("clipboard://new-clip", serde_json::json!({
+                        "type": kind,
+                        "id": id,
+                        "truncated": true
+                    }));
+                }
+                Err(e) => eprintln!("❌ 保存超出上限的内容失败: {}", e),
+            }
+            None
+        }
+        Guarded::Drop(reason) => {
+            eprintln!("⚠️ 丢弃超出上限的内容: {}", reason);
+            let _ = app.emit("clipboard://capture-rejected", serde_json::json!({ "reason": reason }));
+            None
         }
     }
 }
@@ -450,24 +1110,80 @@ fn setup_storage_and_clipboard(
     let home = dirs::home_dir().ok_or("Failed to get home directory")?;
     let data_dir = home.join("Documents").join("pastee");
     
-    let app_state = AppState::new(data_dir.clone()).map_err(|e| e.to_string())?;
-    let shared_storage = Arc::new(Mutex::new(
-        Storage::new(&data_dir).map_err(|e| e.to_string())?
-    ));
+    let app_state = AppState::new(data_dir).map_err(|e| e.to_string())?;
+    let storage_clone = Arc::clone(&app_state.storage);
     
     app.manage(app_state);
 
     // 获取 app handle 用于事件推送
     let app_handle = app.handle().clone();
-    let storage_clone = Arc::clone(&shared_storage);
     
     thread::spawn(move || {
         handle_clipboard_event(rx, app_handle, storage_clone);
     });
+
+    // 在后台识别旧版保存的图片中的二维码
+    let scan_storage = Arc::clone(&app.state::<AppState>().storage);
+    thread::spawn(move || scan_qr_codes(scan_storage));
+
+    // 在后台补齐迁移前保存的图片的感知哈希
+    let hash_storage = Arc::clone(&app.state::<AppState>().storage);
+    thread::spawn(move || backfill_image_phashes(hash_storage));
     
     Ok(())
 }
 
+/// 识别尚未识别过的图片中的二维码，读取和识别都在锁外完成
+fn scan_qr_codes(storage: Arc<Mutex<Storage>>) {
+    let images = match storage.lock().map_err(|_| anyhow::anyhow!("Lock error")).and_then(|store| store.images_to_scan()) {
+        Ok(images) => images,
+        Err(e) => {
+            eprintln!("❌ 查询待识别的图片失败: {}", e);
+            return;
+        }
+    };
+    for (id, path) in images {
+        // 打不开的图片同样记为已识别，避免每次启动重试
+        let codes = match image::open(&path) {
+            Ok(img) => qr::decode(&img),
+            Err(e) => {
+                eprintln!("❌ 读取图片失败 {}: {}", path.display(), e);
+                Vec::new()
+            }
+        };
+        let saved = storage.lock()
+            .map_err(|_| anyhow::anyhow!("Lock error"))
+            .and_then(|mut store| store.save_qr_codes(id, &codes));
+        if let Err(e) = saved {
+            eprintln!("❌ 保存二维码内容失败: {}", e);
+        }
+    }
+}
+
+/// 补齐没有感知哈希的图片，读取和计算都在锁外完成
+fn backfill_image_phashes(storage: Arc<Mutex<Storage>>) {
+    let images = match storage.lock().map_err(|_| anyhow::anyhow!("Lock error")).and_then(|store| store.images_to_hash()) {
+        Ok(images) => images,
+        Err(e) => {
+            eprintln!("❌ 查询待计算感知哈希的图片失败: {}", e);
+            return;
+        }
+    };
+    for (id, paths) in images {
+        // 打不开的图片记为失败，避免每次启动重试
+        let phash = imagehash::dhash_files(&paths);
+        if phash.is_none() {
+            eprintln!("❌ 无法读取图片，跳过感知哈希 ID: {}", id);
+        }
+        let saved = storage.lock()
+            .map_err(|_| anyhow::anyhow!("Lock error"))
+            .and_then(|mut store| store.save_image_phash(id, phash));
+        if let Err(e) = saved {
+            eprintln!("❌ 保存感知哈希失败: {}", e);
+        }
+    }
+}
+
 /// 设置窗口事件监听
 fn setup_window_events(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     // 窗口失去焦点时自动隐藏（除非设置了保持打开）
@@ -0,0 +1,55 @@
+use pulldown_cmark::{html, Options, Parser};
+use regex::Regex;
+use std::sync::LazyLock;
+
+// Markdown 识别与渲染
+//
+// 识别看起来像 Markdown 的文本记录，并渲染为 HTML，
+// 用于粘贴到邮件、文档等富文本编辑器。
+
+/// 判定为 Markdown 所需的最低得分
+const DETECT_THRESHOLD: u32 = 3;
+
+static HEADING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^#{1,6}\s+\S").unwrap());
+static FENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s{0,3}(```|~~~)").unwrap());
+static TABLE_RULE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*\|?\s*:?-{3,}:?\s*(\|\s*:?-{3,}:?\s*)+\|?\s*$").unwrap());
+static TABLE_ROW: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*\|.*\|\s*$").unwrap());
+static LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*([-*+]|\d{1,3}[.)])\s+\S").unwrap());
+static BLOCKQUOTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s{0,3}>\s?\S").unwrap());
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!?\[[^\]\n]+\]\([^)\s]+\)").unwrap());
+static EMPHASIS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\*\*|__)[^\s*_][^\n]*?(\*\*|__)").unwrap());
+static INLINE_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`[^`\n]+`").unwrap());
+
+/// 文本是否看起来像 Markdown
+///
+/// 按行累计特征得分：标题、代码围栏、表格分隔行、链接记 2 分，
+/// 列表项、引用、表格行、粗体、行内代码记 1 分。单个弱特征（如一行 "- item"）不足以判定。
+pub fn looks_like_markdown(text: &str) -> bool {
+    let mut score = 0;
+    for line in text.lines() {
+        if HEADING.is_match(line) || FENCE.is_match(line) || TABLE_RULE.is_match(line) {
+            score += 2;
+        } else if LIST_ITEM.is_match(line) || BLOCKQUOTE.is_match(line) || TABLE_ROW.is_match(line) {
+            score += 1;
+        }
+        score += 2 * LINK.find_iter(line).count() as u32;
+        score += EMPHASIS.find_iter(line).count() as u32;
+        score += INLINE_CODE.find_iter(line).count() as u32;
+        if score >= DETECT_THRESHOLD {
+            return true;
+        }
+    }
+    false
+}
+
+/// 将 Markdown 渲染为 HTML（支持 GFM 表格、删除线和任务列表）
+pub fn to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(markdown, options);
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, parser);
+    output
+}
@@ -0,0 +1,116 @@
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::color::{Notation, Rgba};
+
+// 图片主色提取
+//
+// 使用中位切分（median-cut）：把像素放进一个盒子，反复选出
+// 「像素数 × 最宽通道跨度」最大的盒子，沿最宽的通道在中位数处切开，
+// 直到盒子数达到要求或无法再切。每个盒子取平均色，按像素占比排序。
+// 提取前先缩小图片，透明像素不参与统计。
+
+/// 默认提取的颜色数量
+pub const DEFAULT_COLORS: usize = 6;
+
+/// 提取前缩小到的最大边长，像素数决定耗时
+const SAMPLE_SIZE: u32 = 128;
+
+/// alpha 低于此值的像素视为透明背景
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// 调色板中的一种颜色
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Swatch {
+    /// 十六进制颜色（#RRGGBB）
+    pub color: String,
+    /// 像素占比（0–1）
+    pub share: f64,
+}
+
+/// 提取图片中占比最高的至多 count 种颜色，按占比从高到低排列
+pub fn extract(image: &DynamicImage, count: usize) -> Vec<Swatch> {
+    let sample = if image.width() > SAMPLE_SIZE || image.height() > SAMPLE_SIZE {
+        // 最近邻采样不混合相邻像素，纯色区域的颜色保持原值
+        image.resize(SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Nearest)
+    } else {
+        image.clone()
+    };
+    let pixels: Vec<[u8; 3]> = sample.to_rgba8()
+        .pixels()
+        .filter(|p| p[3] >= ALPHA_THRESHOLD)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let total = pixels.len() as f64;
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        let Some(index) = boxes.iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1 && widest_channel(b).1 > 0)
+            .max_by_key(|(_, b)| b.len() as u64 * widest_channel(b).1 as u64)
+            .map(|(i, _)| i)
+        else {
+            break;
+        };
+        let (channel, _) = widest_channel(&boxes[index]);
+        let pixels = &mut boxes[index];
+        pixels.sort_unstable_by_key(|p| p[channel]);
+        let upper = pixels.split_off(split_point(pixels, channel));
+        boxes.push(upper);
+    }
+
+    let mut swatches: Vec<(Rgba, usize)> = boxes.iter().map(|b| (average(b), b.len())).collect();
+    swatches.sort_by_key(|&(_, population)| std::cmp::Reverse(population));
+    swatches.into_iter()
+        .filter_map(|(color, population)| {
+            Some(Swatch {
+                color: color.format(Notation::Hex)?,
+                share: (population as f64 / total * 1000.0).round() / 1000.0,
+            })
+        })
+        .collect()
+}
+
+/// 中位数附近的切分位置：同一通道值的像素不拆到两个盒子里，
+/// 否则大块纯色会被切成两个相同的颜色
+fn split_point(sorted: &[[u8; 3]], channel: usize) -> usize {
+    let mid = sorted.len() / 2;
+    let value = sorted[mid][channel];
+    let lower = sorted.partition_point(|p| p[channel] < value);
+    let upper = sorted.partition_point(|p| p[channel] <= value);
+    if lower == 0 || (upper < sorted.len() && upper - mid < mid - lower) {
+        upper
+    } else {
+        lower
+    }
+}
+
+/// 跨度最大的通道及其跨度
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(min, max), p| {
+                (min.min(p[channel]), max.max(p[channel]))
+            });
+            (channel, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn average(pixels: &[[u8; 3]]) -> Rgba {
+    let mut sum = [0u64; 3];
+    for p in pixels {
+        for (total, value) in sum.iter_mut().zip(p) {
+            *total += *value as u64;
+        }
+    }
+    let n = pixels.len().max(1) as u64;
+    let channel = |i: usize| ((sum[i] + n / 2) / n) as u8;
+    Rgba { r: channel(0), g: channel(1), b: channel(2), a: 255 }
+}
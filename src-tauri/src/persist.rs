@@ -1,14 +1,51 @@
 use anyhow::{Context, Result};
+use base64::{Engine as _, engine::general_purpose};
 use chrono::Utc;
-use rusqlite::{params, Connection, OptionalExtension, Transaction};
-use rusqlite_migration::{Migrations, M};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use rusqlite_migration::{HookError, Migrations, M};
 use serde::{Deserialize, Serialize};
+use serde_json::Map;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use image::GenericImageView;
 
+use crate::blob::{Blob, BlobStore, StagedBlob};
+use crate::classify;
+use crate::codec::{self, ImageCodec};
+use crate::color::{self, Rgba};
+use crate::guard;
+use crate::html;
+use crate::imagehash;
+use crate::palette::{self, Swatch};
+use crate::qr;
+use crate::setting::{Settings, SimilarImages};
+use crate::template;
+use crate::thumbnail::{self, ThumbnailJob};
 
 
+
+/// 搜索片段长度（字符数，不含省略号）
+const SNIPPET_LEN: usize = 80;
+/// 搜索片段中命中位置之前保留的上下文字符数
+const SNIPPET_CONTEXT: usize = 20;
+
+/// 列表查询统一使用的列，配合 Storage::row_to_item 使用
+///
+/// 正文只取预览用的前 100 个字符，大段文本不随列表查询读出。
+const ITEM_COLUMNS: &str = "id, type, substr(content_text, 1, 100), content_file_paths, created_at, is_pinned, tag,
+     image_format, width, height";
+
+/// 另存为文件的完整正文（超出大小上限或超过大文本阈值）在 blob_refs 中的 role
+const BODY_ROLE: &str = "body";
+/// 压缩保存的正文的命名空间后缀（也是文件扩展名的结尾）
+const BODY_COMPRESSED_SUFFIX: &str = "+zst";
+
+/// 批量清理（清空、保留期限等）时允许删除的记录：未置顶且不在任何看板中
+const REMOVABLE_CONDITION: &str =
+    "is_pinned = 0 AND id NOT IN (SELECT record_id FROM board_items)";
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum ClipType {
     Text,
@@ -52,6 +89,127 @@ pub struct ClipItem {
     pub tags: Vec<String>,  // 标签数组：["color", "favorite"], ["image", "work"] 等
 }
 
+/// 列表游标：记录上一页最后一条的排序键
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageCursor {
+    pub is_pinned: bool,
+    pub created_at: i64,
+    pub id: i64,
+}
+
+impl PageCursor {
+    /// 编码为不透明字符串（base64url）
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}:{}", self.is_pinned as u8, self.created_at, self.id);
+        general_purpose::URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("Invalid cursor: {}", cursor);
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let mut parts = raw.split(':');
+        let (Some(pinned), Some(created_at), Some(id), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            is_pinned: match pinned {
+                "0" => false,
+                "1" => true,
+                _ => return Err(invalid()),
+            },
+            created_at: created_at.parse().map_err(|_| invalid())?,
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// 一页列表数据
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClipPage {
+    pub items: Vec<ClipItem>,
+    pub next_cursor: Option<String>, // 下一页游标，没有更多数据时为 None
+    pub has_more: bool,
+}
+
+/// 记录过滤条件（智能分组的查询定义），各条件之间为 AND 关系
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ClipFilter {
+    pub query: Option<String>,    // content_text 包含的文本
+    pub types: Vec<ClipType>,     // 内容类型，为空表示不限
+    pub tags: Vec<String>,        // 必须全部包含的标签
+    pub pinned: Option<bool>,     // 是否置顶
+    pub within_days: Option<u32>, // 最近 N 天内的记录（相对时间）
+}
+
+/// 保存的搜索（智能分组）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    pub filter: ClipFilter,
+    pub count: i64, // 当前匹配的记录数
+    pub created_at: i64,
+}
+
+/// 看板（手动整理的分组）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Board {
+    pub id: i64,
+    pub name: String,
+    pub item_count: i64,
+    pub created_at: i64,
+}
+
+/// 代码片段（用户编写的模板）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snippet {
+    pub id: i64,
+    pub name: String,
+    pub abbreviation: Option<String>,
+    pub body: String,
+    pub inputs: Vec<String>, // 渲染时需要填写的 {input:Name} 输入项
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 搜索片段中单个命中的位置（字符偏移，左闭右开）
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 搜索命中项：列表项 + 命中附近的片段
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub item: ClipItem,
+    pub snippet: String,             // 命中附近的文本片段
+    pub highlights: Vec<MatchRange>, // 片段内每处命中的位置
+    pub match_count: usize,          // 全文命中次数
+}
+
+/// 相似图片查询结果：列表项 + 与目标图片感知哈希的汉明距离
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarImage {
+    #[serde(flatten)]
+    pub item: ClipItem,
+    pub distance: u32,
+}
+
+/// 一页搜索结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchPage {
+    pub hits: Vec<SearchHit>,
+    pub total: i64, // 命中的记录总数（不受分页影响）
+    pub limit: usize,
+    pub offset: usize,
+}
+
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ClipData {
@@ -62,9 +220,180 @@ pub enum ClipData {
     Color(String),      // 颜色值（保存原始格式）
 }
 
+/// 缩略图查询结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Thumbnail {
+    /// 已缓存，值为相对图片目录的路径
+    Cached(String),
+    /// 尚未生成（或设置已变更），需要执行 ThumbnailJob 后调用 Storage::save_thumbnail
+    Missing(ThumbnailJob),
+}
+
+/// 图片编码参数（取自设置），锁外编码时使用
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageOptions {
+    pub codec: ImageCodec,
+    pub quality: u8,
+    pub max_dimension: u32,
+}
+
+/// 已在锁外完成编码和文件写入的图片，由 Storage::insert_image 写入数据库
+pub struct PreparedImage {
+    hash_hex: String,
+    phash: u64,
+    codec: ImageCodec,
+    width: u32,
+    height: u32,
+    preview: String,
+    metadata: Map<String, serde_json::Value>,
+    original: StagedBlob,
+    original_bytes: Vec<u8>,
+    thumbnail: StagedBlob,
+    thumbnail_bytes: Vec<u8>,
+}
+
+impl PreparedImage {
+    /// 编码原图、生成缩略图、计算感知哈希和调色板并写入文件，不访问数据库
+    pub fn prepare(
+        width: usize,
+        height: usize,
+        rgba_data: Vec<u8>,
+        hash_hex: String,
+        options: ImageOptions,
+        blobs: &BlobStore,
+    ) -> Result<Self> {
+        // 验证数据大小
+        if width * height * 4 != rgba_data.len() {
+            return Err(anyhow::anyhow!("图片数据大小不匹配: 期望 {} bytes, 实际 {} bytes", 
+                width * height * 4, rgba_data.len()));
+        }
+
+        // 从 RGBA 原始数据创建图片
+        let rgba_image = image::RgbaImage::from_raw(width as u32, height as u32, rgba_data)
+            .ok_or_else(|| anyhow::anyhow!("无法从 RGBA 数据创建图片"))?;
+        let img = image::DynamicImage::ImageRgba8(rgba_image);
+
+        // 感知哈希：内容相同但像素不完全一致的图片（改动少量像素、重新编码）哈希相近
+        let phash = imagehash::dhash(&img);
+
+        // Phase 1: 按设置编码原图，超出最大边长时先等比缩小
+        let resized = codec::fit(&img, options.max_dimension);
+        let stored = resized.as_ref().unwrap_or(&img);
+        let original_bytes = codec::encode(stored, options.codec, options.quality)?;
+
+        // Phase 2: 生成缩略图（提高分辨率和质量）
+        let thumbnail_img = img.thumbnail(800, 600);
+        
+        // 使用更高质量的 WebP 编码
+        let mut thumbnail_bytes = Vec::new();
+        let webp_encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut thumbnail_bytes);
+        thumbnail_img.write_with_encoder(webp_encoder)
+            .context("Failed to encode thumbnail")?;
+
+        // 提取主色，以 palette 为键存入元数据
+        let palette = palette::extract(&img, palette::DEFAULT_COLORS);
+        let mut metadata = Map::new();
+        metadata.insert("palette".to_string(), serde_json::to_value(&palette)?);
+        metadata.insert("image".to_string(), Storage::image_metadata(options.codec, options.quality, &img, stored));
+        // 识别二维码和条形码，没有识别到时也记录空列表，表示已经识别过
+        metadata.insert("qr".to_string(), serde_json::to_value(qr::decode(&img))?);
+
+        // 文件按内容寻址写入，登记到数据库前不会被引用
+        let original = blobs.stage(options.codec.mime(), &original_bytes)?;
+        let thumbnail = blobs.stage("image/webp", &thumbnail_bytes)?;
+        println!("✅ 原图已保存: {}", original.path);
+        println!("✅ 缩略图已生成: {}", thumbnail.path);
+
+        Ok(Self {
+            hash_hex,
+            phash,
+            codec: options.codec,
+            width: stored.width(),
+            height: stored.height(),
+            preview: Storage::image_preview(stored, options.codec),
+            metadata,
+            original,
+            original_bytes,
+            thumbnail,
+            thumbnail_bytes,
+        })
+    }
+
+    /// 把文本编码为二维码图片并完成 prepare 的全部工作，不访问数据库
+    pub fn from_qr(text: &str, options: ImageOptions, blobs: &BlobStore) -> Result<Self> {
+        let rgba = qr::encode(text)?.into_rgba8();
+        let (width, height) = (rgba.width() as usize, rgba.height() as usize);
+        let rgba_data = rgba.into_raw();
+        let hash_hex = Storage::image_hash(&rgba_data);
+        Self::prepare(width, height, rgba_data, hash_hex, options, blobs)
+    }
+}
+
+/// 重新压缩一张原图的任务，由 Storage::recompress_job 生成，run 可以在锁外执行
+pub struct RecompressJob {
+    record_id: i64,
+    image_path: String,
+    source: PathBuf,
+    options: ImageOptions,
+    previous: Option<serde_json::Value>,
+}
+
+impl RecompressJob {
+    /// 读取、解码并按设置重新编码原图，写入新文件，不访问数据库
+    pub fn run(self, blobs: &BlobStore) -> Result<RecompressedImage> {
+        let bytes = fs::read(&self.source).context("Failed to read original image")?;
+        let img = image::load_from_memory(&bytes).context("Failed to decode original image")?;
+        let ImageOptions { codec, quality, max_dimension } = self.options;
+        let resized = codec::fit(&img, max_dimension);
+        let stored = resized.as_ref().unwrap_or(&img);
+        let encoded = codec::encode(stored, codec, quality)?;
+
+        // 保留首次保存时记录的原始尺寸
+        let mut info = Storage::image_metadata(codec, quality, &img, stored);
+        if let Some(previous) = &self.previous {
+            for key in ["source_width", "source_height"] {
+                if !previous[key].is_null() {
+                    info[key] = previous[key].clone();
+                }
+            }
+        }
+
+        let staged = blobs.stage(codec.mime(), &encoded)?;
+        Ok(RecompressedImage {
+            record_id: self.record_id,
+            image_path: self.image_path,
+            codec,
+            width: stored.width(),
+            height: stored.height(),
+            preview: Storage::image_preview(stored, codec),
+            info,
+            old_size: bytes.len() as u64,
+            staged,
+            bytes: encoded,
+        })
+    }
+}
+
+/// 已在锁外完成重新编码和文件写入的原图，由 Storage::save_recompressed 写入数据库
+pub struct RecompressedImage {
+    record_id: i64,
+    image_path: String,
+    codec: ImageCodec,
+    width: u32,
+    height: u32,
+    preview: String,
+    info: serde_json::Value,
+    old_size: u64,
+    staged: StagedBlob,
+    bytes: Vec<u8>,
+}
+
 pub struct Storage {
     conn: Connection,
+    data_dir: PathBuf,
     image_dir: PathBuf,
+    blobs: BlobStore,
+    settings: Settings,
 }
 
 impl Storage {
@@ -82,10 +411,36 @@ impl Storage {
         // 性能调优
         conn.pragma_update(None, "journal_mode", "WAL")?;
         conn.pragma_update(None, "synchronous", "NORMAL")?;
+        // 看板条目依赖外键级联删除
+        conn.pragma_update(None, "foreign_keys", "ON")?;
 
         Self::migrate(&mut conn)?;
 
-        Ok(Self { conn, image_dir })
+        // 设置文件损坏时使用默认值，不影响启动
+        let settings = Settings::load(&data_dir).unwrap_or_else(|e| {
+            eprintln!("❌ 读取设置失败，使用默认设置: {:?}", e);
+            Settings::default()
+        });
+
+        let blobs = BlobStore::new(&image_dir);
+        let mut storage = Self { conn, data_dir, image_dir, blobs, settings };
+        // 把旧版按月份目录保存的图片文件迁入按内容寻址的存储
+        if let Err(e) = storage.migrate_image_files() {
+            eprintln!("❌ 迁移图片文件失败: {:?}", e);
+        }
+        // 把超过大文本阈值的旧记录正文另存为文件
+        match storage.move_large_bodies() {
+            Ok(0) => {}
+            Ok(moved) => println!("✅ 已将 {} 条大文本记录的正文另存为文件", moved),
+            Err(e) => eprintln!("❌ 另存大文本正文失败: {:?}", e),
+        }
+        // 清理写入后事务失败留下的未登记文件；此时图片流水线尚未启动，没有并发写入
+        match storage.blobs.collect_orphans(&storage.conn) {
+            Ok(0) => {}
+            Ok(removed) => println!("🧹 已清理 {} 个未登记的文件", removed),
+            Err(e) => eprintln!("❌ 清理未登记的文件失败: {:?}", e),
+        }
+        Ok(storage)
     }
 
     fn migrate(conn: &mut Connection) -> Result<()> {
@@ -94,6 +449,19 @@ impl Storage {
         
         let migrations = Migrations::new(vec![
             M::up(schema_sql),
+            M::up(include_str!("../migrations/002_keyset_index.sql")),
+            M::up(include_str!("../migrations/003_saved_searches.sql")),
+            M::up(include_str!("../migrations/004_boards.sql")),
+            M::up(include_str!("../migrations/005_snippets.sql")),
+            M::up_with_hook(include_str!("../migrations/006_metadata.sql"), |tx: &Transaction| {
+                Self::reclassify(tx, None).map(|_| ()).map_err(|e| HookError::Hook(e.to_string()))
+            }),
+            M::up_with_hook(include_str!("../migrations/007_color.sql"), |tx: &Transaction| {
+                Self::recolor(tx).map(|_| ()).map_err(|e| HookError::Hook(e.to_string()))
+            }),
+            M::up(include_str!("../migrations/008_image_phash.sql")),
+            M::up(include_str!("../migrations/009_blob_store.sql")),
+            M::up(include_str!("../migrations/010_barcode_rescan.sql")),
         ]);
         migrations.to_latest(conn)?;
         Ok(())
@@ -105,18 +473,56 @@ impl Storage {
         let text = text.trim().to_string();
         if text.is_empty() { return Ok(0); }
         let hash = Self::compute_hash(text.as_bytes());
+        let (stored, body) = self.split_body(&text, usize::MAX, self.settings.search_text_bytes);
+        self.save_text(stored, &hash, body)
+    }
+
+    /// 存超出大小上限的纯文本：记录中保存截断后的文本（不超过 limit 字节），完整内容另存为文件
+    ///
+    /// 去重按完整内容计算，get_content 返回完整内容。
+    pub fn add_spilled_text(&mut self, text: &str, limit: usize) -> Result<i64> {
+        let text = text.trim();
+        if text.is_empty() { return Ok(0); }
+        let hash = Self::compute_hash(text.as_bytes());
+        let (stored, body) = self.split_body(text, limit, self.settings.search_text_bytes);
+        self.save_text(stored, &hash, body)
+    }
+
+    /// 拆分记录中保存的正文和另存为文件的完整正文
+    ///
+    /// 超过大文本阈值时记录中只保留开头 keep 字节，超过 limit 时截断到 limit，两种情况都另存完整内容。
+    /// 记录中已经能放下完整内容时不另存。
+    fn split_body<'a>(&self, body: &'a str, limit: usize, keep: usize) -> (&'a str, Option<&'a str>) {
+        let stored = if body.len() > self.settings.large_body_bytes {
+            guard::truncate(body, keep.min(limit))
+        } else {
+            guard::truncate(body, limit)
+        };
+        if stored.len() < body.len() { (stored, Some(body)) } else { (body, None) }
+    }
 
-        // 检测是否为颜色值，设置 tags 数组
-        let (clip_type, tags) = if Self::is_color(&text) {
-            (ClipType::Color, vec!["color".to_string()])
+    fn save_text(&mut self, text: &str, hash: &str, body: Option<&str>) -> Result<i64> {
+        // 检测是否为颜色值，设置 tags 数组；分类使用完整正文，而不是记录中保存的预览
+        let full_text = body.unwrap_or(text);
+        let color = color::parse(full_text);
+        let (clip_type, tags, metadata) = if color.is_some() {
+            (ClipType::Color, vec!["color".to_string()], Map::new())
         } else {
-            (ClipType::Text, vec!["text".to_string()])
+            let (tags, metadata) = Self::classify_tags(ClipType::Text, full_text, None);
+            (ClipType::Text, tags, metadata)
         };
 
         let tx = self.conn.transaction()?;
-        let id = Self::upsert_record(&tx, clip_type, &hash, &tags, |sql, params| {
+        let id = Self::upsert_record(&tx, clip_type, hash, &tags, &metadata, |sql, params| {
              tx.execute(sql, params)
-        }, Some(&text), None, None, None)?;
+        }, Some(text), None, None, None)?;
+        if let Some(color) = color {
+            tx.execute("UPDATE records SET color_rgba = ?1 WHERE id = ?2", params![color.to_u32(), id])?;
+        }
+        if let Some(body) = body {
+            let blob = Self::put_body(&self.blobs, &tx, "text/plain", body)?;
+            Self::add_blob_ref(&tx, id, BODY_ROLE, blob.id)?;
+        }
         tx.commit()?;
         Ok(id)
     }
@@ -125,18 +531,42 @@ impl Storage {
     pub fn add_html(&mut self, text_preview: String, html_content: String) -> Result<i64> {
         // 检测 text_preview 是否为颜色值，如果是则保存为 Color 类型
         let text_trimmed = text_preview.trim();
-        if Self::is_color(text_trimmed) {
+        if color::is_color(text_trimmed) {
             // 直接保存为颜色
             return self.add_text(text_trimmed.to_string());
         }
         
         // HTML 的指纹计算：建议用 html 内容算，或者 text+html 混合算
         let hash = Self::compute_hash(html_content.as_bytes());
-        
+        let (stored, body) = self.split_body(&html_content, usize::MAX, guard::BODY_PREVIEW_BYTES);
+        self.save_html(&text_preview, stored, &hash, body)
+    }
+
+    /// 存超出大小上限的 HTML：记录中保存截断后的 HTML 及其纯文本，完整内容另存为文件
+    pub fn add_spilled_html(&mut self, html_content: &str, limit: usize) -> Result<i64> {
+        let hash = Self::compute_hash(html_content.as_bytes());
+        let (stored, body) = self.split_body(html_content, limit, guard::BODY_PREVIEW_BYTES);
+        let text = html::to_plain_text(html_content);
+        self.save_html(&text, stored, &hash, body)
+    }
+
+    /// text 为完整的纯文本，html_content 为记录中保存的 HTML，body 为另存的完整 HTML
+    fn save_html(&mut self, text: &str, html_content: &str, hash: &str, body: Option<&str>) -> Result<i64> {
+        // 分类使用完整正文，另存时记录中的纯文本按搜索文本上限截断
+        let (tags, metadata) = Self::classify_tags(ClipType::Html, text, Some(body.unwrap_or(html_content)));
+        let text_preview = match body {
+            Some(_) => guard::truncate(text, self.settings.search_text_bytes),
+            None => text,
+        };
+
         let tx = self.conn.transaction()?;
-        let id = Self::upsert_record(&tx, ClipType::Html, &hash, &vec!["html".to_string()], |sql, params| {
+        let id = Self::upsert_record(&tx, ClipType::Html, hash, &tags, &metadata, |sql, params| {
              tx.execute(sql, params)
-        }, Some(&text_preview), Some(&html_content), None, None)?;
+        }, Some(text_preview), Some(html_content), None, None)?;
+        if let Some(body) = body {
+            let blob = Self::put_body(&self.blobs, &tx, "text/html", body)?;
+            Self::add_blob_ref(&tx, id, BODY_ROLE, blob.id)?;
+        }
         tx.commit()?;
         Ok(id)
     }
@@ -156,7 +586,7 @@ impl Storage {
         let hash = Self::compute_hash(json_str.as_bytes());
 
         let tx = self.conn.transaction()?;
-        let id = Self::upsert_record(&tx, ClipType::Files, &hash, &vec!["files".to_string()], |sql, params| {
+        let id = Self::upsert_record(&tx, ClipType::Files, &hash, &vec!["files".to_string()], &Map::new(), |sql, params| {
              tx.execute(sql, params)
         }, Some(&search_text), None, None, Some(&json_str))?;
         tx.commit()?;
@@ -167,75 +597,14 @@ impl Storage {
     pub fn get_recent(&self, limit: usize, offset: usize) -> Result<Vec<ClipItem>> {
         println!("🔍 查询最近记录: limit={}, offset={}", limit, offset);
         
-        let mut stmt = self.conn.prepare(
-            "SELECT id, type, content_text, content_file_paths, created_at, is_pinned, tag,
-             image_format, width, height
-             FROM records 
-             ORDER BY is_pinned DESC, created_at DESC 
-             LIMIT ?1 OFFSET ?2"
-        )?;
-
-        let rows = stmt.query_map(params![limit, offset], |row| {
-            let id: i64 = row.get(0)?;
-            let type_str: String = row.get(1)?;
-            let text: Option<String> = row.get(2)?;
-            let files_json: Option<String> = row.get(3)?;
-            let created_at: i64 = row.get(4)?;
-            let is_pinned: bool = row.get(5)?;
-            let tags_json: Option<String> = row.get(6)?;
-            let image_format: Option<String> = row.get(7)?;
-            let width: Option<i64> = row.get(8)?;
-            let height: Option<i64> = row.get(9)?;
-
-            let content_type = ClipType::from(type_str);
-            
-            // 解析 tags JSON 数组
-            let tags = if let Some(json) = tags_json {
-                serde_json::from_str::<Vec<String>>(&json).unwrap_or_else(|_| vec!["text".to_string()])
-            } else {
-                vec!["text".to_string()]
-            };
-            
-            // 生成 UI 预览文字
-            let preview = match content_type {
-                ClipType::Text | ClipType::Html => {
-                    text.unwrap_or_default().chars().take(100).collect::<String>().replace('\n', " ")
-                },
-                ClipType::Color => {
-                    // 颜色直接显示值
-                    text.unwrap_or_default()
-                },
-                ClipType::Image => {
-                    // 显示图片信息
-                    if let (Some(w), Some(h), Some(fmt)) = (width, height, image_format) {
-                        format!("[图片] {}x{} {}", w, h, fmt.to_uppercase())
-                    } else {
-                        "[图片]".to_string()
-                    }
-                },
-                ClipType::Files => {
-                    // 尝试解析 JSON 看看有几个文件
-                    if let Some(json) = files_json {
-                        if let Ok(paths) = serde_json::from_str::<Vec<String>>(&json) {
-                            format!("[文件] {} 个项目: {}", paths.len(), paths.first().unwrap_or(&"".to_string()))
-                        } else {
-                            "[文件列表]".to_string()
-                        }
-                    } else {
-                        "[文件列表]".to_string()
-                    }
-                }
-            };
-
-            Ok(ClipItem {
-                id,
-                content_type,
-                preview,
-                created_at,
-                is_pinned,
-                tags,
-            })
-        })?;
+        let sql = format!(
+            "SELECT {} FROM records 
+             ORDER BY is_pinned DESC, created_at DESC, id DESC 
+             LIMIT ?1 OFFSET ?2",
+            ITEM_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![limit, offset], Self::row_to_item)?;
 
         let mut items = Vec::new();
         for row in rows { items.push(row?); }
@@ -243,6 +612,52 @@ impl Storage {
         Ok(items)
     }
 
+    /// 游标分页获取列表
+    /// 按 (is_pinned, created_at, id) 定位，新记录插入不会导致翻页时重复或遗漏
+    pub fn get_page(&self, limit: usize, cursor: Option<&str>) -> Result<ClipPage> {
+        let cursor = cursor.map(PageCursor::decode).transpose()?;
+
+        // 多取一条用于判断是否还有下一页
+        let fetch = limit as i64 + 1;
+        let mut items = Vec::new();
+        if let Some(c) = cursor {
+            let sql = format!(
+                "SELECT {} FROM records
+                 WHERE (is_pinned, created_at, id) < (?1, ?2, ?3)
+                 ORDER BY is_pinned DESC, created_at DESC, id DESC
+                 LIMIT ?4",
+                ITEM_COLUMNS
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![c.is_pinned, c.created_at, c.id, fetch], Self::row_to_item)?;
+            for row in rows { items.push(row?); }
+        } else {
+            let sql = format!(
+                "SELECT {} FROM records
+                 ORDER BY is_pinned DESC, created_at DESC, id DESC
+                 LIMIT ?1",
+                ITEM_COLUMNS
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![fetch], Self::row_to_item)?;
+            for row in rows { items.push(row?); }
+        }
+
+        let has_more = items.len() > limit;
+        items.truncate(limit);
+        let next_cursor = if has_more {
+            items.last().map(|item| PageCursor {
+                is_pinned: item.is_pinned,
+                created_at: item.created_at,
+                id: item.id,
+            }.encode())
+        } else {
+            None
+        };
+
+        Ok(ClipPage { items, next_cursor, has_more })
+    }
+
     /// 获取总记录数
     pub fn get_total_count(&self) -> Result<i64> {
         let count: i64 = self.conn.query_row(
@@ -255,46 +670,65 @@ impl Storage {
     }
 
     /// 搜索 (所有类型都通过 content_text 搜索)
+    /// 返回前 50 条命中，预览替换为包含命中位置的片段
     pub fn search(&self, query: &str) -> Result<Vec<ClipItem>> {
+        let page = self.search_page(query, 50, 0)?;
+        Ok(page.hits.into_iter().map(|hit| ClipItem { preview: hit.snippet, ..hit.item }).collect())
+    }
+
+    /// 分页搜索：返回命中片段、高亮位置以及命中总数
+    pub fn search_page(&self, query: &str, limit: usize, offset: usize) -> Result<SearchPage> {
         // 使用 LIKE 查询支持中文和模糊匹配
-        let like_query = format!("%{}%", query.replace('%', "\\%").replace('_', "\\_"));
+        let like_query = Self::like_pattern(query);
+
+        let total: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM records WHERE content_text LIKE ?1 ESCAPE '\\'",
+            params![like_query],
+            |row| row.get(0),
+        )?;
+
         let mut stmt = self.conn.prepare(
-            "SELECT id, type, content_text, content_file_paths, created_at, is_pinned, tag 
-             FROM records 
+            "SELECT id, type, content_text, created_at, is_pinned, tag
+             FROM records
              WHERE content_text LIKE ?1 ESCAPE '\\'
-             ORDER BY created_at DESC LIMIT 50"
+             ORDER BY created_at DESC LIMIT ?2 OFFSET ?3"
         )?;
-        
-        let rows = stmt.query_map(params![like_query], |row| {
-             // 复制上面的 row mapping 逻辑
-             let id: i64 = row.get(0)?;
-             let type_str: String = row.get(1)?;
-             let text: Option<String> = row.get(2)?;
-             let _files_json: Option<String> = row.get(3)?;
-             let created_at: i64 = row.get(4)?;
-             let is_pinned: bool = row.get(5)?;
-             let tags_json: Option<String> = row.get(6)?;
-             let content_type = ClipType::from(type_str);
-             
-             // 解析 tags JSON 数组
-             let tags = if let Some(json) = tags_json {
-                 serde_json::from_str::<Vec<String>>(&json).unwrap_or_else(|_| vec!["text".to_string()])
-             } else {
-                 vec!["text".to_string()]
-             };
-             
-             let preview = match content_type {
-                ClipType::Text | ClipType::Html => text.unwrap_or_default().chars().take(50).collect(),
-                ClipType::Color => text.unwrap_or_default(),
-                ClipType::Image => "[图片]".to_string(),
-                ClipType::Files => "[文件]".to_string(),
-            };
-            Ok(ClipItem { id, content_type, preview, created_at, is_pinned, tags })
+
+        let rows = stmt.query_map(params![like_query, limit, offset], |row| {
+            let id: i64 = row.get(0)?;
+            let type_str: String = row.get(1)?;
+            let text: Option<String> = row.get(2)?;
+            let created_at: i64 = row.get(3)?;
+            let is_pinned: bool = row.get(4)?;
+            let tags_json: Option<String> = row.get(5)?;
+            Ok((id, type_str, text.unwrap_or_default(), created_at, is_pinned, tags_json))
         })?;
 
-        let mut items = Vec::new();
-        for row in rows { items.push(row?); }
-        Ok(items)
+        let mut hits = Vec::new();
+        for row in rows {
+            let (id, type_str, text, created_at, is_pinned, tags_json) = row?;
+            let content_type = ClipType::from(type_str);
+            let preview = match content_type {
+                ClipType::Color => text.clone(),
+                _ => text.chars().take(100).collect::<String>().replace('\n', " "),
+            };
+            let (snippet, highlights, match_count) = Self::build_snippet(&text, query);
+            hits.push(SearchHit {
+                item: ClipItem {
+                    id,
+                    content_type,
+                    preview,
+                    created_at,
+                    is_pinned,
+                    tags: Self::parse_tags(tags_json),
+                },
+                snippet,
+                highlights,
+                match_count,
+            });
+        }
+
+        Ok(SearchPage { hits, total, limit, offset })
     }
 
     /// 获取详情 (用于粘贴)
@@ -320,12 +754,18 @@ impl Storage {
         let (t_str, text, html, img_path_old, file_paths, image_path) = item;
 
         match ClipType::from(t_str) {
-            ClipType::Text => Ok(ClipData::Text(text.unwrap_or_default())),
+            ClipType::Text => match self.spilled_body(id)? {
+                Some(body) => Ok(ClipData::Text(body)),
+                None => Ok(ClipData::Text(text.unwrap_or_default())),
+            },
             ClipType::Color => Ok(ClipData::Color(text.unwrap_or_default())),
-            ClipType::Html => Ok(ClipData::Html {
-                text: text.unwrap_or_default(),
-                html: html.unwrap_or_default(),
-            }),
+            ClipType::Html => match self.spilled_body(id)? {
+                Some(body) => Ok(ClipData::Html { text: html::to_plain_text(&body), html: body }),
+                None => Ok(ClipData::Html {
+                    text: text.unwrap_or_default(),
+                    html: html.unwrap_or_default(),
+                }),
+            },
             ClipType::Image => {
                 // 优先使用新字段 image_path，兼容旧数据
                 let path = image_path.or(img_path_old)
@@ -345,6 +785,83 @@ impl Storage {
         }
     }
 
+    /// 超出大小上限时另存的完整正文
+    fn spilled_body(&self, id: i64) -> Result<Option<String>> {
+        let path: Option<String> = self.conn.query_row(
+            "SELECT b.path FROM blob_refs r JOIN blobs b ON b.id = r.blob_id
+             WHERE r.record_id = ?1 AND r.role = ?2",
+            params![id, BODY_ROLE],
+            |row| row.get(0),
+        ).optional()?;
+        path.map(|path| Self::read_body(&self.blobs, &path)).transpose()
+    }
+
+    /// 用 zstd 压缩正文后保存，命名空间为 {mime}+zst（如 text/plain+zst）
+    fn put_body(blobs: &BlobStore, conn: &Connection, mime: &str, body: &str) -> Result<Blob> {
+        let compressed = zstd::encode_all(body.as_bytes(), zstd::DEFAULT_COMPRESSION_LEVEL)
+            .context("Failed to compress body")?;
+        blobs.put(conn, &format!("{}{}", mime, BODY_COMPRESSED_SUFFIX), &compressed)
+    }
+
+    /// 读取另存为文件的正文（兼容未压缩的旧文件）
+    fn read_body(blobs: &BlobStore, path: &str) -> Result<String> {
+        let bytes = fs::read(blobs.full_path(path)).with_context(|| format!("Failed to read body {}", path))?;
+        let bytes = if path.ends_with(BODY_COMPRESSED_SUFFIX) {
+            zstd::decode_all(bytes.as_slice()).with_context(|| format!("Failed to decompress body {}", path))?
+        } else {
+            bytes
+        };
+        String::from_utf8(bytes).with_context(|| format!("Body {} is not valid UTF-8", path))
+    }
+
+    /// 把正文超过大文本阈值、尚未另存的文本和 HTML 记录改为另存完整正文，返回处理的记录数
+    ///
+    /// 每条记录单独提交，记录中的 HTML 只保留开头一段，纯文本按搜索文本上限截断，
+    /// get_content 仍返回完整内容。纯文本没有超出搜索文本上限的记录不需要截断，保持原样。
+    pub fn move_large_bodies(&mut self) -> Result<usize> {
+        let threshold = self.settings.large_body_bytes as i64;
+        let text_threshold = threshold.max(self.settings.search_text_bytes as i64);
+        let rows = self.conn.prepare(
+            "SELECT id, type, content_text, content_html FROM records
+             WHERE ((type = 'text' AND length(CAST(content_text AS BLOB)) > ?3)
+                 OR (type = 'html' AND length(CAST(content_html AS BLOB)) > ?1))
+               AND id NOT IN (SELECT record_id FROM blob_refs WHERE role = ?2)"
+        )?
+        .query_map(params![threshold, BODY_ROLE, text_threshold], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                ClipType::from(row.get::<_, String>(1)?),
+                row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut moved = 0;
+        for (id, ctype, text, html) in rows {
+            let tx = self.conn.transaction()?;
+            let text_preview = guard::truncate(&text, self.settings.search_text_bytes);
+            match (ctype, html) {
+                (ClipType::Html, Some(html)) => {
+                    let blob = Self::put_body(&self.blobs, &tx, "text/html", &html)?;
+                    Self::add_blob_ref(&tx, id, BODY_ROLE, blob.id)?;
+                    tx.execute(
+                        "UPDATE records SET content_text = ?1, content_html = ?2 WHERE id = ?3",
+                        params![text_preview, guard::truncate(&html, guard::BODY_PREVIEW_BYTES), id],
+                    )?;
+                }
+                _ => {
+                    let blob = Self::put_body(&self.blobs, &tx, "text/plain", &text)?;
+                    Self::add_blob_ref(&tx, id, BODY_ROLE, blob.id)?;
+                    tx.execute("UPDATE records SET content_text = ?1 WHERE id = ?2", params![text_preview, id])?;
+                }
+            }
+            tx.commit()?;
+            moved += 1;
+        }
+        Ok(moved)
+    }
+
     /// 获取图片的缩略图路径（用于前端展示）
     pub fn get_image_paths(&self, id: i64) -> Result<(String, String)> {
         self.conn.query_row(
@@ -369,6 +886,7 @@ impl Storage {
         ctype: ClipType,
         hash: &str,
         tags: &[String],
+        metadata: &Map<String, serde_json::Value>,
         executor: F, // 闭包，用于执行具体的 SQL
         
         // 各种可选字段
@@ -380,15 +898,17 @@ impl Storage {
     where
         F: FnOnce(&str, &[&dyn rusqlite::ToSql]) -> rusqlite::Result<usize>,
     {
-        // 1. 将 tags 数组序列化为 JSON
+        // 1. 将 tags 数组和元数据序列化为 JSON
         let tags_json = serde_json::to_string(tags)?;
+        let metadata_json = Self::metadata_json(metadata)?;
         
         // 2. 构造 SQL
-        let sql = "INSERT INTO records (type, hash, created_at, content_text, content_html, content_image_path, content_file_paths, tag)
-                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        let sql = "INSERT INTO records (type, hash, created_at, content_text, content_html, content_image_path, content_file_paths, tag, metadata)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
                    ON CONFLICT(hash) DO UPDATE SET
                       created_at = excluded.created_at,
-                      tag = excluded.tag";
+                      tag = excluded.tag,
+                      metadata = excluded.metadata";
         
         // 3. 执行
         executor(sql, params![
@@ -399,7 +919,8 @@ impl Storage {
             html,
             img_path,
             file_paths,
-            tags_json
+            tags_json,
+            metadata_json
         ])?;
 
         // 4. 获取 ID
@@ -428,215 +949,1061 @@ impl Storage {
         Ok(())
     }
 
-    fn compute_hash(data: &[u8]) -> String {
-        let hash = blake3::hash(data);
-        hex::encode(hash.as_bytes())
-    }
+    /// 将 ITEM_COLUMNS 查询出的行转换为列表项
+    fn row_to_item(row: &rusqlite::Row) -> rusqlite::Result<ClipItem> {
+        let id: i64 = row.get(0)?;
+        let type_str: String = row.get(1)?;
+        let text: Option<String> = row.get(2)?;
+        let files_json: Option<String> = row.get(3)?;
+        let created_at: i64 = row.get(4)?;
+        let is_pinned: bool = row.get(5)?;
+        let tags_json: Option<String> = row.get(6)?;
+        let image_format: Option<String> = row.get(7)?;
+        let width: Option<i64> = row.get(8)?;
+        let height: Option<i64> = row.get(9)?;
 
-    /// 检测字符串是否为颜色值
-    /// 支持格式：
-    /// - HEX: #RGB, #RRGGBB, #RRGGBBAA
-    /// - RGB: rgb(r, g, b)
-    /// - RGBA: rgba(r, g, b, a)
-    /// - HSL: hsl(h, s%, l%)
-    /// - HSLA: hsla(h, s%, l%, a)
-    fn is_color(text: &str) -> bool {
-        let text = text.trim();
+        let content_type = ClipType::from(type_str);
         
-        // HEX 格式: #RGB, #RRGGBB, #RRGGBBAA
-        if text.starts_with('#') {
-            let hex_part = &text[1..];
-            let len = hex_part.len();
-            // 验证长度和字符
-            if (len == 3 || len == 6 || len == 8) && hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
-                return true;
-            }
-        }
+        let tags = Self::parse_tags(tags_json);
         
-        // RGB/RGBA 格式
-        let lower = text.to_lowercase();
-        if lower.starts_with("rgb(") || lower.starts_with("rgba(") {
-            if let (Some(start), Some(end)) = (lower.find('('), lower.rfind(')')) {
-                let content = &lower[start+1..end];
-                let parts: Vec<&str> = content.split(',').map(|s| s.trim()).collect();
-                // rgb 需要 3 个参数，rgba 需要 4 个参数
-                if (lower.starts_with("rgb(") && parts.len() == 3) || 
-                   (lower.starts_with("rgba(") && parts.len() == 4) {
-                    return true;
+        // 生成 UI 预览文字
+        let preview = match content_type {
+            ClipType::Text | ClipType::Html => {
+                text.unwrap_or_default().chars().take(100).collect::<String>().replace('\n', " ")
+            },
+            ClipType::Color => {
+                // 颜色直接显示值
+                text.unwrap_or_default()
+            },
+            ClipType::Image => {
+                // 显示图片信息
+                if let (Some(w), Some(h), Some(fmt)) = (width, height, image_format) {
+                    format!("[图片] {}x{} {}", w, h, fmt.to_uppercase())
+                } else {
+                    "[图片]".to_string()
+                }
+            },
+            ClipType::Files => {
+                // 尝试解析 JSON 看看有几个文件
+                if let Some(json) = files_json {
+                    if let Ok(paths) = serde_json::from_str::<Vec<String>>(&json) {
+                        format!("[文件] {} 个项目: {}", paths.len(), paths.first().unwrap_or(&"".to_string()))
+                    } else {
+                        "[文件列表]".to_string()
+                    }
+                } else {
+                    "[文件列表]".to_string()
                 }
             }
+        };
+
+        Ok(ClipItem {
+            id,
+            content_type,
+            preview,
+            created_at,
+            is_pinned,
+            tags,
+        })
+    }
+
+    /// 构造 LIKE 子串匹配模式（配合 ESCAPE '\\' 使用）
+    fn like_pattern(query: &str) -> String {
+        format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+    }
+
+    /// 将过滤条件转换为 WHERE 子句及其参数
+    fn filter_clause(filter: &ClipFilter) -> (String, Vec<Value>) {
+        let mut conditions = vec!["1 = 1".to_string()];
+        let mut values = Vec::new();
+
+        if let Some(query) = filter.query.as_deref().filter(|q| !q.is_empty()) {
+            values.push(Value::Text(Self::like_pattern(query)));
+            conditions.push(format!("content_text LIKE ?{} ESCAPE '\\'", values.len()));
         }
-        
-        // HSL/HSLA 格式
-        if lower.starts_with("hsl(") || lower.starts_with("hsla(") {
-            if let (Some(start), Some(end)) = (lower.find('('), lower.rfind(')')) {
-                let content = &lower[start+1..end];
-                let parts: Vec<&str> = content.split(',').map(|s| s.trim()).collect();
-                // hsl 需要 3 个参数，hsla 需要 4 个参数
-                if (lower.starts_with("hsl(") && parts.len() == 3) || 
-                   (lower.starts_with("hsla(") && parts.len() == 4) {
-                    return true;
-                }
+        if !filter.types.is_empty() {
+            let mut placeholders = Vec::new();
+            for ctype in &filter.types {
+                values.push(Value::Text(ctype.to_string()));
+                placeholders.push(format!("?{}", values.len()));
             }
+            conditions.push(format!("type IN ({})", placeholders.join(", ")));
+        }
+        for tag in &filter.tags {
+            values.push(Value::Text(tag.clone()));
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM json_each(records.tag) WHERE json_each.value = ?{})",
+                values.len()
+            ));
+        }
+        if let Some(pinned) = filter.pinned {
+            values.push(Value::Integer(pinned as i64));
+            conditions.push(format!("is_pinned = ?{}", values.len()));
+        }
+        if let Some(days) = filter.within_days {
+            let since = Utc::now().timestamp_micros() - days as i64 * 86_400_000_000;
+            values.push(Value::Integer(since));
+            conditions.push(format!("created_at >= ?{}", values.len()));
         }
-        
-        false
-    }
 
-    /// 切换记录的置顶状态
-    pub fn toggle_pin(&self, id: i64) -> Result<bool> {
-        let new_state: bool = self.conn.query_row(
-            "SELECT is_pinned FROM records WHERE id = ?1",
-            params![id],
-            |row| row.get(0),
-        )?;
-        
-        self.conn.execute(
-            "UPDATE records SET is_pinned = ?1 WHERE id = ?2",
-            params![!new_state, id],
-        )?;
-        
-        Ok(!new_state)
+        (conditions.join(" AND "), values)
     }
 
-    /// 删除指定记录
-    pub fn delete_record(&self, id: i64) -> Result<()> {
-        self.conn.execute(
-            "DELETE FROM records WHERE id = ?1",
-            params![id],
-        )?;
-        Ok(())
+    /// 文本 / HTML 记录的标签：类型标签在前，其后为分类器标签
+    fn classify_tags(ctype: ClipType, text: &str, html: Option<&str>) -> (Vec<String>, Map<String, serde_json::Value>) {
+        let classification = match html {
+            Some(html) => classify::classify_html(text, html),
+            None => classify::classify(text),
+        };
+        let mut tags = vec![ctype.to_string()];
+        tags.extend(classification.tags);
+        (tags, classification.metadata)
     }
 
-    /// 清空所有未置顶的记录
-    pub fn clear_unpinned(&mut self) -> Result<i64> {
-        let deleted = self.conn.execute("DELETE FROM records WHERE is_pinned = 0", [])?;
-        println!("🗑️ 已清空 {} 条未置顶记录", deleted);
-        Ok(deleted as i64)
+    /// 元数据序列化为 JSON，为空时存 NULL
+    fn metadata_json(metadata: &Map<String, serde_json::Value>) -> Result<Option<String>> {
+        if metadata.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_string(metadata)?))
     }
 
-    /// 添加图片记录（Phase 1-3 实现）
-    pub fn add_image(&mut self, width: usize, height: usize, rgba_data: Vec<u8>) -> Result<(i64, Vec<u8>)> {
-        use image::{ImageFormat, RgbaImage};
-        use std::time::{SystemTime, UNIX_EPOCH};
+    /// 解析 tags JSON 数组
+    fn parse_tags(tags_json: Option<String>) -> Vec<String> {
+        tags_json
+            .and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok())
+            .unwrap_or_else(|| vec!["text".to_string()])
+    }
 
-        println!("📸 开始处理图片: {}x{}, {} bytes", width, height, rgba_data.len());
+    /// 生成搜索片段：截取第一处命中附近的文本，并计算片段内各命中的位置
+    /// 与 SQLite LIKE 一致，仅对 ASCII 字母忽略大小写
+    /// 返回 (片段, 片段内命中位置, 全文命中次数)
+    fn build_snippet(text: &str, query: &str) -> (String, Vec<MatchRange>, usize) {
+        let chars: Vec<char> = text.chars().collect();
+        let needle: Vec<char> = query.chars().map(|c| c.to_ascii_lowercase()).collect();
 
-        // 验证数据大小
-        if width * height * 4 != rgba_data.len() {
-            return Err(anyhow::anyhow!("图片数据大小不匹配: 期望 {} bytes, 实际 {} bytes", 
-                width * height * 4, rgba_data.len()));
+        // 查找所有不重叠的命中起点
+        let mut matches = Vec::new();
+        if !needle.is_empty() {
+            let mut i = 0;
+            while i + needle.len() <= chars.len() {
+                let hit = chars[i..i + needle.len()]
+                    .iter()
+                    .zip(&needle)
+                    .all(|(c, n)| c.to_ascii_lowercase() == *n);
+                if hit {
+                    matches.push(i);
+                    i += needle.len();
+                } else {
+                    i += 1;
+                }
+            }
         }
 
-        // 计算图片 hash（用于去重）
-        let hash = blake3::hash(&rgba_data);
-        let hash_hex = hex::encode(&hash.as_bytes()[..8]); // 取前8字节
-        println!("📸 图片hash: {}", hash_hex);
-
-        // Phase 3: 去重检查
-        println!("📸 检查是否已存在...");
-        match self.find_image_by_hash(&hash_hex) {
-            Ok(Some(existing_id)) => {
-                println!("📸 图片已存在，使用已有记录 ID: {}", existing_id);
-                // 读取已存在的缩略图数据返回
-                let (_, thumbnail_path) = self.get_image_paths(existing_id)?;
-                let full_thumb_path = self.image_dir.join(&thumbnail_path);
-                let thumbnail_data = fs::read(full_thumb_path)?;
-                return Ok((existing_id, thumbnail_data));
-            }
-            Ok(None) => {
-                println!("📸 图片不存在，继续保存");
+        // 以第一处命中为中心截取窗口，保证命中完整落在片段内
+        let window_start = matches.first().map_or(0, |&m| m.saturating_sub(SNIPPET_CONTEXT));
+        let mut window_end = (window_start + SNIPPET_LEN).min(chars.len());
+        if let Some(&m) = matches.first() {
+            window_end = window_end.max(m + needle.len());
+        }
+
+        let mut snippet = String::new();
+        let mut prefix_len = 0;
+        if window_start > 0 {
+            snippet.push('…');
+            prefix_len = 1;
+        }
+        // 换行替换为空格，保持字符偏移不变
+        snippet.extend(chars[window_start..window_end].iter().map(|&c| {
+            if c == '\n' || c == '\r' || c == '\t' { ' ' } else { c }
+        }));
+        if window_end < chars.len() {
+            snippet.push('…');
+        }
+
+        let highlights = matches
+            .iter()
+            .filter(|&&m| m >= window_start && m + needle.len() <= window_end)
+            .map(|&m| MatchRange {
+                start: m - window_start + prefix_len,
+                end: m - window_start + prefix_len + needle.len(),
+            })
+            .collect();
+
+        (snippet, highlights, matches.len())
+    }
+
+    fn compute_hash(data: &[u8]) -> String {
+        let hash = blake3::hash(data);
+        hex::encode(hash.as_bytes())
+    }
+
+    /// 获取记录的分类元数据（以标签为键）
+    pub fn get_metadata(&self, id: i64) -> Result<Map<String, serde_json::Value>> {
+        let metadata: Option<String> = self.conn.query_row(
+            "SELECT metadata FROM records WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Ok(metadata.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default())
+    }
+
+    /// 用当前的分类器重新计算所有文本 / HTML 记录的标签和元数据，返回有变化的记录数
+    pub fn reclassify_all(&mut self) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let changed = Self::reclassify(&tx, Some(&self.blobs))?;
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// 另存了正文的记录按完整正文分类；blobs 为 None 时（blob 表建立之前的迁移）只读记录中的内容
+    fn reclassify(conn: &Connection, blobs: Option<&BlobStore>) -> Result<usize> {
+        let body_column = match blobs {
+            Some(_) => format!(
+                "(SELECT b.path FROM blob_refs r JOIN blobs b ON b.id = r.blob_id
+                  WHERE r.record_id = records.id AND r.role = '{}')", BODY_ROLE
+            ),
+            None => "NULL".to_string(),
+        };
+        let rows = conn.prepare(&format!(
+            "SELECT id, type, content_text, content_html, tag, metadata, {} FROM records WHERE type IN ('text', 'html')",
+            body_column
+        ))?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut changed = 0;
+        for (id, type_str, mut text, mut html, old_tags, old_metadata, body_path) in rows {
+            let ctype = ClipType::from(type_str);
+            if let (Some(blobs), Some(path)) = (blobs, body_path) {
+                let body = Self::read_body(blobs, &path)?;
+                match ctype {
+                    ClipType::Html => {
+                        text = Some(html::to_plain_text(&body));
+                        html = Some(body);
+                    }
+                    _ => text = Some(body),
+                }
             }
-            Err(e) => {
-                eprintln!("❌ 去重检查失败: {:?}", e);
-                return Err(e);
+            let (tags, metadata) = Self::classify_tags(ctype, text.as_deref().unwrap_or_default(), html.as_deref());
+            let tags_json = serde_json::to_string(&tags)?;
+            let metadata_json = Self::metadata_json(&metadata)?;
+            // 未变化的记录不写入，避免无谓地重建 FTS 索引
+            if old_tags.as_ref() == Some(&tags_json) && old_metadata == metadata_json {
+                continue;
             }
+            conn.execute(
+                "UPDATE records SET tag = ?1, metadata = ?2 WHERE id = ?3",
+                params![tags_json, metadata_json, id],
+            )?;
+            changed += 1;
         }
+        Ok(changed)
+    }
 
-        // 从 RGBA 原始数据创建图片
-        let rgba_image = RgbaImage::from_raw(width as u32, height as u32, rgba_data.clone())
-            .ok_or_else(|| anyhow::anyhow!("无法从 RGBA 数据创建图片"))?;
-        let img = image::DynamicImage::ImageRgba8(rgba_image);
+    /// 颜色记录的规范化颜色值
+    pub fn get_color(&self, id: i64) -> Result<Rgba> {
+        let value: Option<u32> = self.conn.query_row(
+            "SELECT color_rgba FROM records WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        value.map(Rgba::from_u32).ok_or_else(|| anyhow::anyhow!("Clip {} is not a color", id))
+    }
 
-        let format = ImageFormat::Png; // 统一保存为 PNG
-        let ext = "png";
+    /// 按当前颜色解析器重新判定文本 / 颜色记录：能解析的文本转为颜色，
+    /// 不再能解析的颜色（如 rgb(999, a, b)）转回文本并重新分类，返回有变化的记录数
+    fn recolor(conn: &Connection) -> Result<usize> {
+        let rows = conn.prepare(
+            "SELECT id, type, content_text, color_rgba FROM records WHERE type IN ('text', 'color')"
+        )?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                row.get::<_, Option<u32>>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut changed = 0;
+        for (id, type_str, text, old_color) in rows {
+            let color = color::parse(&text).map(Rgba::to_u32);
+            if color == old_color && (ClipType::from(type_str) == ClipType::Color) == color.is_some() {
+                continue;
+            }
+            let (clip_type, tags, metadata) = match color {
+                Some(_) => (ClipType::Color, vec!["color".to_string()], Map::new()),
+                None => {
+                    let (tags, metadata) = Self::classify_tags(ClipType::Text, &text, None);
+                    (ClipType::Text, tags, metadata)
+                }
+            };
+            conn.execute(
+                "UPDATE records SET type = ?1, tag = ?2, metadata = ?3, color_rgba = ?4 WHERE id = ?5",
+                params![clip_type.to_string(), serde_json::to_string(&tags)?, Self::metadata_json(&metadata)?, color, id],
+            )?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
 
-        // 生成文件路径
-        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros();
-        let year_month = chrono::Local::now().format("%Y%m").to_string();
+    /// 切换记录的置顶状态
+    pub fn toggle_pin(&self, id: i64) -> Result<bool> {
+        let new_state: bool = self.conn.query_row(
+            "SELECT is_pinned FROM records WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
         
-        // 创建目录: images/202601/original 和 images/202601/thumbnail
-        let month_dir = self.image_dir.join(&year_month);
-        let original_dir = month_dir.join("original");
-        let thumbnail_dir = month_dir.join("thumbnail");
+        self.conn.execute(
+            "UPDATE records SET is_pinned = ?1 WHERE id = ?2",
+            params![!new_state, id],
+        )?;
         
-        fs::create_dir_all(&original_dir)?;
-        fs::create_dir_all(&thumbnail_dir)?;
+        Ok(!new_state)
+    }
 
-        // 文件名: {timestamp}_{hash}.{ext}
-        let filename = format!("{}_{}.{}", now, hash_hex, ext);
-        let thumb_filename = format!("{}_{}.webp", now, hash_hex);
-        
-        // 相对路径（存储到DB）
-        let relative_path = format!("{}/original/{}", year_month, filename);
-        let relative_thumb_path = format!("{}/thumbnail/{}", year_month, thumb_filename);
+    /// 删除指定记录（不再被引用的图片文件一并删除）
+    pub fn delete_record(&self, id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM records WHERE id = ?1",
+            params![id],
+        )?;
+        self.blobs.collect_garbage(&self.conn)?;
+        Ok(())
+    }
+
+    /// 清空所有未置顶的记录（看板中的记录同样保留）
+    pub fn clear_unpinned(&mut self) -> Result<i64> {
+        let sql = format!("DELETE FROM records WHERE {}", REMOVABLE_CONDITION);
+        let deleted = self.conn.execute(&sql, [])?;
+        self.blobs.collect_garbage(&self.conn)?;
+        println!("🗑️ 已清空 {} 条未置顶记录", deleted);
+        Ok(deleted as i64)
+    }
 
-        // 绝对路径（文件系统操作）
-        let original_path = original_dir.join(&filename);
-        let thumbnail_path = thumbnail_dir.join(&thumb_filename);
+    /// 添加图片记录（Phase 1-3 实现）
+    ///
+    /// 依次执行去重查询、编码和写入数据库。剪贴板捕获走 ImagePipeline，
+    /// 把编码放在锁外执行；这里是同一流程的同步版本。
+    pub fn add_image(&mut self, width: usize, height: usize, rgba_data: Vec<u8>) -> Result<(i64, Vec<u8>)> {
+        println!("📸 开始处理图片: {}x{}, {} bytes", width, height, rgba_data.len());
 
-        // Phase 1: 保存原图（PNG格式）
-        img.save_with_format(&original_path, format)
-            .context("Failed to write original image")?;
-        println!("✅ 原图已保存: {}", relative_path);
+        let hash_hex = Self::image_hash(&rgba_data);
+        if let Some(existing) = self.find_image(&hash_hex)? {
+            return Ok(existing);
+        }
+        let prepared = PreparedImage::prepare(width, height, rgba_data, hash_hex, self.image_options(), &self.blobs)?;
+        self.insert_image(prepared)
+    }
 
-        // 获取保存后的文件大小
-        let file_size = fs::metadata(&original_path)?.len();
+    /// 图片去重用的哈希：原始 RGBA 数据的完整 BLAKE3 哈希
+    pub fn image_hash(rgba_data: &[u8]) -> String {
+        blake3::hash(rgba_data).to_hex().to_string()
+    }
 
-        // Phase 2: 生成缩略图（同步，提高分辨率和质量）
-        let thumbnail_img = img.thumbnail(800, 600);
-        
-        // 使用更高质量的 WebP 编码
-        let mut webp_buffer = Vec::new();
-        let webp_encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut webp_buffer);
-        thumbnail_img.write_with_encoder(webp_encoder)
-            .context("Failed to encode thumbnail")?;
-        
-        // 保存到文件
-        fs::write(&thumbnail_path, &webp_buffer)
-            .context("Failed to write thumbnail")?;
-        println!("✅ 缩略图已生成: {}", relative_thumb_path);
+    /// Phase 3: 去重检查，图片已存在时返回记录 ID 和缩略图数据
+    pub fn find_image(&self, hash_hex: &str) -> Result<Option<(i64, Vec<u8>)>> {
+        let Some(existing_id) = self.find_image_by_hash(hash_hex)? else {
+            return Ok(None);
+        };
+        println!("📸 图片已存在，使用已有记录 ID: {}", existing_id);
+        // 读取已存在的缩略图数据返回
+        let (_, thumbnail_path) = self.get_image_paths(existing_id)?;
+        let thumbnail_data = fs::read(self.image_dir.join(&thumbnail_path))?;
+        Ok(Some((existing_id, thumbnail_data)))
+    }
+
+    /// 当前设置下的图片编码参数
+    pub fn image_options(&self) -> ImageOptions {
+        ImageOptions {
+            codec: self.settings.image_codec,
+            quality: self.settings.image_quality,
+            max_dimension: self.settings.max_image_dimension,
+        }
+    }
+
+    /// 图片文件存储（只包含根目录，可以复制到锁外写文件）
+    pub fn blob_store(&self) -> BlobStore {
+        self.blobs.clone()
+    }
+
+    /// 把锁外准备好的图片写入数据库，返回记录 ID 和缩略图数据
+    ///
+    /// 相同图片在准备期间已被保存时（并发捕获），直接返回已有的记录。
+    pub fn insert_image(&mut self, prepared: PreparedImage) -> Result<(i64, Vec<u8>)> {
+        if let Some(existing) = self.find_image(&prepared.hash_hex)? {
+            return Ok(existing);
+        }
+
+        let replaced: Vec<i64> = if self.settings.similar_images == SimilarImages::Replace {
+            let threshold = self.settings.similar_image_threshold;
+            self.image_phashes()?
+                .into_iter()
+                .filter(|&(_, other)| imagehash::distance(prepared.phash, other) <= threshold)
+                .map(|(id, _)| id)
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-        // 插入数据库记录
+        // 插入数据库记录，文件已按内容寻址写入，这里只登记
         let timestamp_micros = Utc::now().timestamp_micros();
+        let mut tags = vec!["image".to_string()];
+        if !Self::qr_codes(&prepared.metadata).is_empty() {
+            tags.push("qr".to_string());
+        }
+        let tx = self.conn.transaction()?;
+        let original = self.blobs.commit(&tx, &prepared.original, &prepared.original_bytes)?;
+        let thumbnail = self.blobs.commit(&tx, &prepared.thumbnail, &prepared.thumbnail_bytes)?;
         
-        self.conn.execute(
+        tx.execute(
             "INSERT INTO records (
                 type, hash, created_at, content_text,
                 image_path, thumbnail_path, image_format, image_size,
-                image_hash, width, height, tag
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                image_hash, width, height, tag, metadata, image_phash
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
             params![
                 ClipType::Image.to_string(),
-                hash_hex, // hash字段用于通用去重
+                format!("image:{}", prepared.hash_hex), // hash字段用于通用去重，加前缀与文本哈希区分
                 timestamp_micros,
-                format!("[图片] {}x{} {}", width, height, ext.to_uppercase()), // content_text用于预览
-                relative_path,
-                relative_thumb_path,
-                ext,
-                file_size as i64,
-                hash_hex, // image_hash用于图片去重
-                width as i64,
-                height as i64,
-                r#"["image"]"#, // tag标签
+                Self::image_text(&prepared.preview, &prepared.metadata), // content_text用于预览和搜索二维码内容
+                original.path,
+                thumbnail.path,
+                prepared.codec.extension(),
+                original.size as i64,
+                prepared.hash_hex, // image_hash用于图片去重
+                prepared.width as i64,
+                prepared.height as i64,
+                serde_json::to_string(&tags)?, // tag标签
+                Self::metadata_json(&prepared.metadata)?,
+                prepared.phash as i64, // SQLite 没有无符号整数，按位存为 i64
             ],
         )?;
+        let id = tx.last_insert_rowid();
+        Self::add_blob_ref(&tx, id, "original", original.id)?;
+        Self::add_blob_ref(&tx, id, "thumbnail", thumbnail.id)?;
+        tx.commit()?;
 
-        let id = self.conn.last_insert_rowid();
         println!("📸 图片记录已创建 ID: {}", id);
+
+        // 替换相似的旧图片，置顶或在看板中的图片保留
+        let sql = format!("DELETE FROM records WHERE id = ?1 AND {}", REMOVABLE_CONDITION);
+        for old_id in replaced {
+            if self.conn.execute(&sql, params![old_id])? > 0 {
+                println!("📸 已替换相似图片 ID: {}", old_id);
+            }
+        }
+        self.blobs.collect_garbage(&self.conn)?;
         
         // 返回 ID 和缩略图数据
-        Ok((id, webp_buffer))
+        Ok((id, prepared.thumbnail_bytes))
+    }
+
+    /// 图片预览文字
+    fn image_preview(image: &image::DynamicImage, codec: ImageCodec) -> String {
+        format!("[图片] {}x{} {}", image.width(), image.height(), codec.extension().to_uppercase())
+    }
+
+    /// 图片记录的 content_text：预览文字，其后每行一条识别出的二维码内容
+    fn image_text(preview: &str, metadata: &Map<String, serde_json::Value>) -> String {
+        std::iter::once(preview.to_string())
+            .chain(Self::qr_codes(metadata))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 元数据中以 qr 为键的二维码和条形码内容
+    fn qr_codes(metadata: &Map<String, serde_json::Value>) -> Vec<String> {
+        metadata.get("qr")
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// 以 image 为键的元数据：原图的编码，以及缩小保存时的原始尺寸
+    fn image_metadata(codec: ImageCodec, quality: u8, source: &image::DynamicImage, stored: &image::DynamicImage) -> serde_json::Value {
+        let mut value = serde_json::json!({ "codec": codec });
+        if codec.is_lossy() {
+            value["quality"] = quality.into();
+        }
+        if source.dimensions() != stored.dimensions() {
+            value["source_width"] = source.width().into();
+            value["source_height"] = source.height().into();
+        }
+        value
+    }
+
+    /// 原图编码与当前设置不一致（或超出最大边长）的图片记录，按 ID 从新到旧
+    pub fn images_to_recompress(&self) -> Result<Vec<i64>> {
+        let rows = self.conn.prepare(
+            "SELECT id, width, height, metadata FROM records WHERE type = 'image' ORDER BY id DESC"
+        )?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<i64>>(1)?.unwrap_or_default(),
+                row.get::<_, Option<i64>>(2)?.unwrap_or_default(),
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(rows.into_iter()
+            .filter(|(_, width, height, metadata)| {
+                let metadata: Map<String, serde_json::Value> = metadata.as_deref()
+                    .and_then(|json| serde_json::from_str(json).ok())
+                    .unwrap_or_default();
+                self.needs_recompress(&metadata, *width as u32, *height as u32)
+            })
+            .map(|(id, ..)| id)
+            .collect())
+    }
+
+    fn needs_recompress(&self, metadata: &Map<String, serde_json::Value>, width: u32, height: u32) -> bool {
+        let info = metadata.get("image");
+        // 早于编码设置保存的图片都是 PNG
+        let codec: ImageCodec = info.and_then(|i| serde_json::from_value(i["codec"].clone()).ok()).unwrap_or_default();
+        let quality = info.and_then(|i| i["quality"].as_u64());
+        let settings = &self.settings;
+        let max = settings.max_image_dimension;
+        codec != settings.image_codec
+            || (codec.is_lossy() && quality != Some(settings.image_quality as u64))
+            || (max != 0 && (width > max || height > max))
+    }
+
+    /// 按当前设置重新编码一张图片的原图，返回（原大小, 新大小）；已符合设置时返回 None
+    ///
+    /// 记录改为引用新文件，旧文件在不再被引用时删除。缩略图不变。
+    /// 读取和编码在当前线程完成；持有 Storage 锁的调用方应改为
+    /// recompress_job → 锁外 RecompressJob::run → save_recompressed。
+    pub fn recompress_image(&mut self, id: i64) -> Result<Option<(u64, u64)>> {
+        match self.recompress_job(id)? {
+            Some(job) => {
+                let image = job.run(&self.blobs)?;
+                self.save_recompressed(image)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 重新压缩一张图片的任务；已符合设置时返回 None
+    pub fn recompress_job(&self, id: i64) -> Result<Option<RecompressJob>> {
+        let (image_path, width, height) = self.conn.query_row(
+            "SELECT image_path, width, height FROM records WHERE id = ?1 AND type = 'image'",
+            params![id],
+            |row| Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<i64>>(1)?.unwrap_or_default(),
+                row.get::<_, Option<i64>>(2)?.unwrap_or_default(),
+            )),
+        ).optional()?.ok_or_else(|| anyhow::anyhow!("Clip {} is not an image", id))?;
+        let metadata = self.get_metadata(id)?;
+        if !self.needs_recompress(&metadata, width as u32, height as u32) {
+            return Ok(None);
+        }
+        Ok(Some(RecompressJob {
+            record_id: id,
+            source: self.blobs.full_path(&image_path),
+            image_path,
+            options: self.image_options(),
+            previous: metadata.get("image").cloned(),
+        }))
+    }
+
+    /// 把锁外重新编码的原图写入数据库，返回（原大小, 新大小）
+    ///
+    /// 编码期间记录被删除或原图已被替换时放弃结果，返回 None。
+    pub fn save_recompressed(&mut self, image: RecompressedImage) -> Result<Option<(u64, u64)>> {
+        let current: Option<String> = self.conn.query_row(
+            "SELECT image_path FROM records WHERE id = ?1 AND type = 'image'",
+            params![image.record_id],
+            |row| row.get(0),
+        ).optional()?;
+        if current.as_deref() != Some(image.image_path.as_str()) {
+            return Ok(None);
+        }
+        // 元数据重新读取，编码期间写入的其他键（如识别结果）不会被覆盖
+        let mut metadata = self.get_metadata(image.record_id)?;
+        metadata.insert("image".to_string(), image.info);
+
+        let tx = self.conn.transaction()?;
+        let blob = self.blobs.commit(&tx, &image.staged, &image.bytes)?;
+        Self::add_blob_ref(&tx, image.record_id, "original", blob.id)?;
+        tx.execute(
+            "UPDATE records SET image_path = ?1, image_format = ?2, image_size = ?3, width = ?4, height = ?5,
+                    content_text = ?6, metadata = ?7
+             WHERE id = ?8",
+            params![
+                blob.path,
+                image.codec.extension(),
+                blob.size as i64,
+                image.width as i64,
+                image.height as i64,
+                Self::image_text(&image.preview, &metadata),
+                Self::metadata_json(&metadata)?,
+                image.record_id,
+            ],
+        )?;
+        tx.commit()?;
+        self.blobs.collect_garbage(&self.conn)?;
+        Ok(Some((image.old_size, blob.size)))
+    }
+
+    /// 查询指定尺寸和倍率的缩略图
+    pub fn find_thumbnail(&self, id: i64, size: &str, scale: u32) -> Result<Thumbnail> {
+        let spec = self.settings.thumbnail_sizes.iter()
+            .find(|s| s.name == size)
+            .ok_or_else(|| anyhow::anyhow!("Unknown thumbnail size: {}", size))?;
+        if !self.settings.thumbnail_scales.contains(&scale) {
+            return Err(anyhow::anyhow!("Unsupported thumbnail scale: {}x", scale));
+        }
+        let role = thumbnail::role(spec, scale);
+
+        let cached: Option<String> = self.conn.query_row(
+            "SELECT b.path FROM blob_refs r JOIN blobs b ON b.id = r.blob_id WHERE r.record_id = ?1 AND r.role = ?2",
+            params![id, role],
+            |row| row.get(0),
+        ).optional()?;
+        if let Some(path) = cached {
+            if self.blobs.full_path(&path).exists() {
+                return Ok(Thumbnail::Cached(path));
+            }
+        }
+
+        let image_path: String = self.conn.query_row(
+            "SELECT image_path FROM records WHERE id = ?1 AND type = 'image'",
+            params![id],
+            |row| row.get(0),
+        ).optional()?.ok_or_else(|| anyhow::anyhow!("Clip {} is not an image", id))?;
+        Ok(Thumbnail::Missing(ThumbnailJob {
+            record_id: id,
+            role,
+            source: self.blobs.full_path(&image_path),
+            width: spec.width * scale,
+            height: spec.height * scale,
+        }))
+    }
+
+    /// 缓存生成的缩略图，返回相对图片目录的路径
+    pub fn save_thumbnail(&mut self, job: &ThumbnailJob, bytes: &[u8]) -> Result<String> {
+        let tx = self.conn.transaction()?;
+        let blob = self.blobs.put(&tx, "image/webp", bytes)?;
+        Self::add_blob_ref(&tx, job.record_id, &job.role, blob.id)?;
+        tx.commit()?;
+        // 同一 role 的旧缩略图文件不再被引用
+        self.blobs.collect_garbage(&self.conn)?;
+        Ok(blob.path)
+    }
+
+    /// 获取缩略图，未缓存时同步生成
+    pub fn get_thumbnail(&mut self, id: i64, size: &str, scale: u32) -> Result<String> {
+        match self.find_thumbnail(id, size, scale)? {
+            Thumbnail::Cached(path) => Ok(path),
+            Thumbnail::Missing(job) => {
+                let bytes = job.render()?;
+                self.save_thumbnail(&job, &bytes)
+            }
+        }
+    }
+
+    /// 删除不再符合当前设置的缩略图缓存，返回删除的引用数
+    pub fn prune_thumbnails(&mut self) -> Result<usize> {
+        let roles: Vec<String> = self.settings.thumbnail_sizes.iter()
+            .flat_map(|size| self.settings.thumbnail_scales.iter().map(move |&scale| thumbnail::role(size, scale)))
+            .collect();
+        let placeholders = vec!["?"; roles.len()].join(", ");
+        let sql = format!(
+            "DELETE FROM blob_refs WHERE role LIKE 'thumbnail:%' AND role NOT IN ({})",
+            placeholders
+        );
+        let removed = self.conn.execute(&sql, params_from_iter(roles.iter()))?;
+        self.blobs.collect_garbage(&self.conn)?;
+        Ok(removed)
+    }
+
+    /// 所有图片记录中缺失（或文件已丢失）的缩略图
+    pub fn missing_thumbnails(&self) -> Result<Vec<ThumbnailJob>> {
+        let ids = self.conn.prepare("SELECT id FROM records WHERE type = 'image' ORDER BY id DESC")?
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut jobs = Vec::new();
+        for id in ids {
+            for size in &self.settings.thumbnail_sizes {
+                for &scale in &self.settings.thumbnail_scales {
+                    if let Thumbnail::Missing(job) = self.find_thumbnail(id, &size.name, scale)? {
+                        jobs.push(job);
+                    }
+                }
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// 图片记录的主色调色板；早于该功能保存的图片在首次读取时提取并写回元数据
+    pub fn get_palette(&self, id: i64) -> Result<Vec<Swatch>> {
+        let type_str: String = self.conn.query_row(
+            "SELECT type FROM records WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if ClipType::from(type_str) != ClipType::Image {
+            return Err(anyhow::anyhow!("Clip {} is not an image", id));
+        }
+
+        let mut metadata = self.get_metadata(id)?;
+        if let Some(palette) = metadata.get("palette") {
+            return Ok(serde_json::from_value(palette.clone())?);
+        }
+
+        let (image_path, _) = self.get_image_paths(id)?;
+        let img = image::open(self.image_dir.join(image_path)).context("Failed to open image")?;
+        let palette = palette::extract(&img, palette::DEFAULT_COLORS);
+        metadata.insert("palette".to_string(), serde_json::to_value(&palette)?);
+        self.conn.execute(
+            "UPDATE records SET metadata = ?1 WHERE id = ?2",
+            params![Self::metadata_json(&metadata)?, id],
+        )?;
+        Ok(palette)
+    }
+
+    /// 把调色板中的第 index 种颜色保存为颜色记录，返回记录 ID
+    pub fn save_palette_color(&mut self, id: i64, index: usize) -> Result<i64> {
+        let palette = self.get_palette(id)?;
+        let swatch = palette.get(index)
+            .ok_or_else(|| anyhow::anyhow!("Palette of clip {} has no color #{}", id, index))?;
+        self.add_text(swatch.color.clone())
+    }
+
+    /// 图片中识别出的二维码和条形码内容
+    pub fn get_qr_codes(&self, id: i64) -> Result<Vec<String>> {
+        Ok(Self::qr_codes(&self.get_metadata(id)?))
+    }
+
+    /// 把图片中识别出的第 index 条二维码内容保存为文本记录，返回记录 ID
+    pub fn save_qr_text(&mut self, id: i64, index: usize) -> Result<i64> {
+        let codes = self.get_qr_codes(id)?;
+        let code = codes.get(index)
+            .ok_or_else(|| anyhow::anyhow!("Clip {} has no QR code #{}", id, index))?;
+        self.add_text(code.clone())
+    }
+
+    /// 尚未识别二维码的图片记录及其原图路径（旧版保存的图片）
+    pub fn images_to_scan(&self) -> Result<Vec<(i64, PathBuf)>> {
+        let rows = self.conn.prepare(
+            "SELECT id, image_path FROM records
+             WHERE type = 'image' AND image_path IS NOT NULL
+               AND (metadata IS NULL OR json_extract(metadata, '$.qr') IS NULL)
+             ORDER BY id"
+        )?
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows.into_iter().map(|(id, path)| (id, self.blobs.full_path(&path))).collect())
+    }
+
+    /// 保存在锁外识别出的二维码内容：写入元数据、content_text，并加上 qr 标签
+    pub fn save_qr_codes(&mut self, id: i64, codes: &[String]) -> Result<()> {
+        let (preview, tags_json) = self.conn.query_row(
+            "SELECT content_text, tag FROM records WHERE id = ?1 AND type = 'image'",
+            params![id],
+            |row| Ok((row.get::<_, Option<String>>(0)?.unwrap_or_default(), row.get::<_, Option<String>>(1)?)),
+        ).optional()?.ok_or_else(|| anyhow::anyhow!("Clip {} is not an image", id))?;
+        // content_text 的第一行是预览文字，之后是上次识别的内容
+        let preview = preview.lines().next().unwrap_or_default().to_string();
+
+        let mut metadata = self.get_metadata(id)?;
+        metadata.insert("qr".to_string(), serde_json::to_value(codes)?);
+        let mut tags = Self::parse_tags(tags_json);
+        tags.retain(|tag| tag != "qr");
+        if !codes.is_empty() {
+            tags.push("qr".to_string());
+        }
+        self.conn.execute(
+            "UPDATE records SET content_text = ?1, tag = ?2, metadata = ?3 WHERE id = ?4",
+            params![
+                Self::image_text(&preview, &metadata),
+                serde_json::to_string(&tags)?,
+                Self::metadata_json(&metadata)?,
+                id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 生成二维码用的文本：文本类记录的内容，图片记录返回错误
+    pub fn qr_source_text(&self, id: i64) -> Result<String> {
+        match self.get_content(id)? {
+            ClipData::Text(text) | ClipData::Color(text) | ClipData::Html { text, .. } => Ok(text),
+            ClipData::Files(paths) => Ok(paths.join("\n")),
+            ClipData::Image(_) => Err(anyhow::anyhow!("Clip {} is an image", id)),
+        }
+    }
+
+    /// 把文本类记录的内容生成为二维码图片记录，返回新记录的 ID 和缩略图数据
+    ///
+    /// 同 add_image，编码在当前线程完成；持有 Storage 锁的调用方应改为
+    /// qr_source_text → 锁外 PreparedImage::from_qr → insert_image。
+    pub fn add_qr_image(&mut self, id: i64) -> Result<(i64, Vec<u8>)> {
+        let text = self.qr_source_text(id)?;
+        let prepared = PreparedImage::from_qr(&text, self.image_options(), &self.blobs)?;
+        self.insert_image(prepared)
+    }
+
+    /// 与指定图片相似的图片，按汉明距离从近到远排列；threshold 缺省时使用设置中的阈值
+    pub fn find_similar_images(&self, id: i64, threshold: Option<u32>) -> Result<Vec<SimilarImage>> {
+        let target: i64 = self.conn
+            .query_row(
+                "SELECT image_phash FROM records WHERE id = ?1 AND type = 'image'",
+                params![id],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .optional()?
+            .flatten()
+            .ok_or_else(|| anyhow::anyhow!("Clip {} is not an image", id))?;
+        let threshold = threshold.unwrap_or(self.settings.similar_image_threshold);
+
+        let sql = format!(
+            "SELECT {}, image_phash FROM records
+             WHERE type = 'image' AND image_phash IS NOT NULL AND id != ?1
+             ORDER BY created_at DESC, id DESC",
+            ITEM_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![id], |row| {
+            Ok((Self::row_to_item(row)?, row.get::<_, i64>(10)?))
+        })?;
+
+        let mut similar = Vec::new();
+        for row in rows {
+            let (item, phash) = row?;
+            let distance = imagehash::distance(target as u64, phash as u64);
+            if distance <= threshold {
+                similar.push(SimilarImage { item, distance });
+            }
+        }
+        // 稳定排序：距离相同时保持新记录在前
+        similar.sort_by_key(|s| s.distance);
+        Ok(similar)
+    }
+
+    /// 按设置中的阈值把相似图片归组（相似关系可传递），只返回至少两张图片的组；
+    /// 组内和组之间都按记录 ID 从新到旧排列
+    pub fn similar_image_groups(&self) -> Result<Vec<Vec<i64>>> {
+        let images = self.image_phashes()?;
+        let threshold = self.settings.similar_image_threshold;
+
+        // 并查集
+        let mut parent: Vec<usize> = (0..images.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for i in 0..images.len() {
+            for j in (i + 1)..images.len() {
+                if imagehash::distance(images[i].1, images[j].1) <= threshold {
+                    let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[a] = b;
+                }
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<i64>> = HashMap::new();
+        for (i, &(id, _)) in images.iter().enumerate() {
+            groups.entry(root(&mut parent, i)).or_default().push(id);
+        }
+        let mut groups: Vec<Vec<i64>> = groups.into_values().filter(|g| g.len() > 1).collect();
+        for group in &mut groups {
+            group.sort_unstable_by(|a, b| b.cmp(a));
+        }
+        groups.sort_unstable_by(|a, b| b[0].cmp(&a[0]));
+        Ok(groups)
+    }
+
+    /// 所有已有感知哈希的图片记录（ID, 哈希）
+    fn image_phashes(&self) -> Result<Vec<(i64, u64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, image_phash FROM records WHERE type = 'image' AND image_phash IS NOT NULL"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)? as u64)))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// 尚未计算感知哈希的图片记录（迁移前保存的图片）及其缩略图、原图路径（优先读取缩略图）
+    ///
+    /// 读取失败过的图片记有 phash_failed，不再返回。
+    pub fn images_to_hash(&self) -> Result<Vec<(i64, Vec<PathBuf>)>> {
+        let rows = self.conn.prepare(
+            "SELECT id, thumbnail_path, image_path FROM records
+             WHERE type = 'image' AND image_phash IS NULL
+               AND (metadata IS NULL OR json_extract(metadata, '$.phash_failed') IS NULL)
+             ORDER BY id"
+        )?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows.into_iter()
+            .map(|(id, thumbnail_path, image_path)| {
+                let paths = [thumbnail_path, image_path].into_iter().flatten().map(|path| self.blobs.full_path(&path));
+                (id, paths.collect())
+            })
+            .collect())
+    }
+
+    /// 保存在锁外计算的感知哈希；None 表示图片无法读取，在元数据中记为 phash_failed，之后不再重试
+    pub fn save_image_phash(&mut self, id: i64, phash: Option<u64>) -> Result<()> {
+        match phash {
+            Some(phash) => {
+                self.conn.execute(
+                    "UPDATE records SET image_phash = ?1 WHERE id = ?2 AND type = 'image'",
+                    params![phash as i64, id],
+                )?;
+            }
+            None => {
+                let mut metadata = self.get_metadata(id)?;
+                metadata.insert("phash_failed".to_string(), serde_json::Value::Bool(true));
+                self.conn.execute(
+                    "UPDATE records SET metadata = ?1 WHERE id = ?2 AND type = 'image'",
+                    params![Self::metadata_json(&metadata)?, id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 补齐迁移前保存的图片的感知哈希，返回补齐的记录数
+    ///
+    /// 读取和计算在当前线程完成；持有 Storage 锁的调用方应改为
+    /// images_to_hash → 锁外 imagehash::dhash_files → save_image_phash。
+    pub fn backfill_image_phashes(&mut self) -> Result<usize> {
+        let mut filled = 0;
+        for (id, paths) in self.images_to_hash()? {
+            let phash = imagehash::dhash_files(&paths);
+            if phash.is_some() {
+                filled += 1;
+            }
+            self.save_image_phash(id, phash)?;
+        }
+        Ok(filled)
+    }
+
+    /// 记录引用存储中的文件（role 为 original / thumbnail），引用计数由触发器维护
+    fn add_blob_ref(conn: &Connection, record_id: i64, role: &str, blob_id: i64) -> Result<()> {
+        conn.execute(
+            "INSERT INTO blob_refs (record_id, blob_id, role) VALUES (?1, ?2, ?3)
+             ON CONFLICT(record_id, role) DO UPDATE SET blob_id = excluded.blob_id",
+            params![record_id, blob_id, role],
+        )?;
+        Ok(())
+    }
+
+    /// 把旧版保存在 YYYYMM/original、YYYYMM/thumbnail 下的图片迁入按内容寻址的存储，
+    /// 同时把记录的哈希换成完整的 BLAKE3 哈希，返回迁移的记录数
+    ///
+    /// 每条记录单独提交，中途失败时下次启动继续迁移剩余的记录。
+    pub fn migrate_image_files(&self) -> Result<usize> {
+        let rows = self.conn.prepare(
+            "SELECT id, COALESCE(image_path, content_image_path), thumbnail_path, image_format FROM records
+             WHERE type = 'image' AND id NOT IN (SELECT record_id FROM blob_refs)"
+        )?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut migrated = 0;
+        for (id, image_path, thumbnail_path, image_format) in rows {
+            let Some(image_path) = image_path else { continue };
+            let source = self.image_dir.join(&image_path);
+            if !source.exists() {
+                eprintln!("❌ 图片文件不存在，跳过迁移 ID {}: {}", id, image_path);
+                continue;
+            }
+            // 重新计算原始 RGBA 数据的完整哈希，与新保存的图片一致，便于去重
+            let hash_hex = image::open(&source)
+                .map(|img| blake3::hash(img.to_rgba8().as_raw()).to_hex().to_string())
+                .ok();
+
+            // 文件先复制进存储，事务提交后才删除源文件：中途失败时记录仍指向完整的源文件，
+            // 复制出的文件没有登记，下次启动时由 collect_orphans 清理
+            let tx = self.conn.unchecked_transaction()?;
+            let format = image_format.unwrap_or_else(|| "png".to_string()).to_lowercase();
+            let original = self.blobs.put_file(&tx, &format!("image/{}", format), &source)?;
+            Self::add_blob_ref(&tx, id, "original", original.id)?;
+            let mut sources = vec![source];
+            let thumbnail = match thumbnail_path {
+                Some(path) if self.image_dir.join(&path).exists() => {
+                    let thumbnail_source = self.image_dir.join(&path);
+                    let blob = self.blobs.put_file(&tx, "image/webp", &thumbnail_source)?;
+                    Self::add_blob_ref(&tx, id, "thumbnail", blob.id)?;
+                    sources.push(thumbnail_source);
+                    Some(blob.path)
+                }
+                _ => None,
+            };
+            tx.execute(
+                "UPDATE records SET image_path = ?1, thumbnail_path = COALESCE(?2, thumbnail_path) WHERE id = ?3",
+                params![original.path, thumbnail, id],
+            )?;
+            if let Some(hash_hex) = hash_hex {
+                // 哈希已被其他记录占用时保留原值
+                tx.execute(
+                    "UPDATE records SET hash = ?1, image_hash = ?2 WHERE id = ?3
+                     AND NOT EXISTS (SELECT 1 FROM records WHERE hash = ?1)",
+                    params![format!("image:{}", hash_hex), hash_hex, id],
+                )?;
+            }
+            tx.commit()?;
+            for source in sources {
+                if let Err(e) = fs::remove_file(&source) {
+                    eprintln!("❌ 删除已迁移的文件失败 {}: {:?}", source.display(), e);
+                }
+            }
+            migrated += 1;
+        }
+
+        if migrated > 0 {
+            Self::remove_empty_dirs(&self.image_dir);
+            println!("📦 已迁移 {} 条图片记录的文件", migrated);
+        }
+        Ok(migrated)
+    }
+
+    /// 删除旧的 YYYYMM/original、YYYYMM/thumbnail 目录中已经清空的目录
+    fn remove_empty_dirs(image_dir: &Path) {
+        let Ok(entries) = fs::read_dir(image_dir) else { return };
+        for month_dir in entries.flatten().map(|e| e.path()) {
+            if month_dir.file_name().is_some_and(|name| name == "blobs") || !month_dir.is_dir() {
+                continue;
+            }
+            for sub in ["original", "thumbnail"] {
+                let _ = fs::remove_dir(month_dir.join(sub)); // 非空时失败，保留
+            }
+            let _ = fs::remove_dir(&month_dir);
+        }
     }
 
     /// 根据 hash 查找已存在的图片
@@ -658,4 +2025,351 @@ impl Storage {
         }
     }
 
+    // ==========================================
+    // 智能分组（保存的搜索）
+    // ==========================================
+
+    /// 按过滤条件查询记录
+    pub fn query_clips(&self, filter: &ClipFilter, limit: usize, offset: usize) -> Result<Vec<ClipItem>> {
+        let (clause, mut values) = Self::filter_clause(filter);
+        values.push(Value::Integer(limit as i64));
+        values.push(Value::Integer(offset as i64));
+        let sql = format!(
+            "SELECT {} FROM records WHERE {}
+             ORDER BY is_pinned DESC, created_at DESC, id DESC
+             LIMIT ?{} OFFSET ?{}",
+            ITEM_COLUMNS, clause, values.len() - 1, values.len()
+        );
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), Self::row_to_item)?;
+        let mut items = Vec::new();
+        for row in rows { items.push(row?); }
+        Ok(items)
+    }
+
+    /// 统计符合过滤条件的记录数
+    pub fn count_clips(&self, filter: &ClipFilter) -> Result<i64> {
+        let (clause, values) = Self::filter_clause(filter);
+        let sql = format!("SELECT COUNT(*) FROM records WHERE {}", clause);
+        let count = self.conn.query_row(&sql, params_from_iter(values), |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// 新建保存的搜索
+    pub fn create_saved_search(&self, name: &str, filter: &ClipFilter) -> Result<i64> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Saved search name cannot be empty"));
+        }
+        let now = Utc::now().timestamp_micros();
+        self.conn.execute(
+            "INSERT INTO saved_searches (name, filter, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+            params![name, serde_json::to_string(filter)?, now],
+        ).with_context(|| format!("Failed to create saved search '{}'", name))?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 修改保存的搜索的名称和条件
+    pub fn update_saved_search(&self, id: i64, name: &str, filter: &ClipFilter) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Saved search name cannot be empty"));
+        }
+        let updated = self.conn.execute(
+            "UPDATE saved_searches SET name = ?1, filter = ?2, updated_at = ?3 WHERE id = ?4",
+            params![name, serde_json::to_string(filter)?, Utc::now().timestamp_micros(), id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Saved search not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// 删除保存的搜索（不影响记录本身）
+    pub fn delete_saved_search(&self, id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM saved_searches WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// 列出所有保存的搜索，附带实时计数
+    pub fn list_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, filter, created_at FROM saved_searches ORDER BY name COLLATE NOCASE"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+
+        let mut searches = Vec::new();
+        for row in rows {
+            let (id, name, filter_json, created_at) = row?;
+            let filter: ClipFilter = serde_json::from_str(&filter_json).unwrap_or_default();
+            let count = self.count_clips(&filter)?;
+            searches.push(SavedSearch { id, name, filter, count, created_at });
+        }
+        Ok(searches)
+    }
+
+    /// 获取保存的搜索的过滤条件
+    pub fn get_saved_search(&self, id: i64) -> Result<ClipFilter> {
+        let filter_json: String = self.conn.query_row(
+            "SELECT filter FROM saved_searches WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        ).with_context(|| format!("Saved search not found: {}", id))?;
+        Ok(serde_json::from_str(&filter_json)?)
+    }
+
+    /// 执行保存的搜索
+    pub fn get_saved_search_clips(&self, id: i64, limit: usize, offset: usize) -> Result<Vec<ClipItem>> {
+        let filter = self.get_saved_search(id)?;
+        self.query_clips(&filter, limit, offset)
+    }
+
+    // ==========================================
+    // 看板
+    // ==========================================
+
+    /// 新建看板
+    pub fn create_board(&self, name: &str) -> Result<i64> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Board name cannot be empty"));
+        }
+        self.conn.execute(
+            "INSERT INTO boards (name, created_at) VALUES (?1, ?2)",
+            params![name, Utc::now().timestamp_micros()],
+        ).with_context(|| format!("Failed to create board '{}'", name))?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 重命名看板
+    pub fn rename_board(&self, id: i64, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Board name cannot be empty"));
+        }
+        let updated = self.conn.execute(
+            "UPDATE boards SET name = ?1 WHERE id = ?2",
+            params![name, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Board not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// 删除看板（记录本身保留）
+    pub fn delete_board(&self, id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM boards WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// 列出所有看板及其条目数
+    pub fn list_boards(&self) -> Result<Vec<Board>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT b.id, b.name, b.created_at,
+                    (SELECT COUNT(*) FROM board_items i WHERE i.board_id = b.id)
+             FROM boards b ORDER BY b.name COLLATE NOCASE"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Board {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                item_count: row.get(3)?,
+            })
+        })?;
+
+        let mut boards = Vec::new();
+        for row in rows { boards.push(row?); }
+        Ok(boards)
+    }
+
+    /// 将记录添加到看板末尾，已在看板中时不做改动
+    pub fn add_to_board(&self, board_id: i64, record_id: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO board_items (board_id, record_id, sort_order, added_at)
+             VALUES (?1, ?2,
+                     (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM board_items WHERE board_id = ?1),
+                     ?3)",
+            params![board_id, record_id, Utc::now().timestamp_micros()],
+        ).with_context(|| format!("Failed to add record {} to board {}", record_id, board_id))?;
+        Ok(())
+    }
+
+    /// 从看板移除记录（记录本身保留）
+    pub fn remove_from_board(&self, board_id: i64, record_id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM board_items WHERE board_id = ?1 AND record_id = ?2",
+            params![board_id, record_id],
+        )?;
+        Ok(())
+    }
+
+    /// 调整看板内顺序
+    /// record_ids 中的记录按给定顺序排在前面，未列出的记录保持原有相对顺序排在其后
+    pub fn reorder_board(&mut self, board_id: i64, record_ids: &[i64]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        let current: Vec<i64> = {
+            let mut stmt = tx.prepare(
+                "SELECT record_id FROM board_items WHERE board_id = ?1 ORDER BY sort_order"
+            )?;
+            let rows = stmt.query_map(params![board_id], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut order: Vec<i64> = Vec::with_capacity(current.len());
+        for id in record_ids.iter().chain(&current) {
+            if current.contains(id) && !order.contains(id) {
+                order.push(*id);
+            }
+        }
+
+        for (position, record_id) in order.iter().enumerate() {
+            tx.execute(
+                "UPDATE board_items SET sort_order = ?1 WHERE board_id = ?2 AND record_id = ?3",
+                params![position as i64, board_id, record_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 获取看板中的记录（按看板顺序）
+    pub fn get_board_clips(&self, board_id: i64) -> Result<Vec<ClipItem>> {
+        let sql = format!(
+            "SELECT {} FROM board_items JOIN records ON records.id = board_items.record_id
+             WHERE board_items.board_id = ?1
+             ORDER BY board_items.sort_order",
+            ITEM_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![board_id], Self::row_to_item)?;
+        let mut items = Vec::new();
+        for row in rows { items.push(row?); }
+        Ok(items)
+    }
+
+    /// 获取记录所属的看板 ID
+    pub fn get_clip_boards(&self, record_id: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT board_id FROM board_items WHERE record_id = ?1 ORDER BY board_id"
+        )?;
+        let rows = stmt.query_map(params![record_id], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // ==========================================
+    // 代码片段
+    // ==========================================
+
+    /// 新建代码片段，保存前校验模板语法
+    pub fn create_snippet(&self, name: &str, abbreviation: Option<&str>, body: &str) -> Result<i64> {
+        let (name, abbreviation) = Self::check_snippet(name, abbreviation, body)?;
+        let now = Utc::now().timestamp_micros();
+        self.conn.execute(
+            "INSERT INTO snippets (name, abbreviation, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![name, abbreviation, body, now],
+        ).with_context(|| format!("Failed to create snippet '{}'", name))?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 修改代码片段
+    pub fn update_snippet(&self, id: i64, name: &str, abbreviation: Option<&str>, body: &str) -> Result<()> {
+        let (name, abbreviation) = Self::check_snippet(name, abbreviation, body)?;
+        let updated = self.conn.execute(
+            "UPDATE snippets SET name = ?1, abbreviation = ?2, body = ?3, updated_at = ?4 WHERE id = ?5",
+            params![name, abbreviation, body, Utc::now().timestamp_micros(), id],
+        ).with_context(|| format!("Failed to update snippet '{}'", name))?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Snippet not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// 删除代码片段
+    pub fn delete_snippet(&self, id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM snippets WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// 列出所有代码片段
+    pub fn list_snippets(&self) -> Result<Vec<Snippet>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, abbreviation, body, created_at, updated_at
+             FROM snippets ORDER BY name COLLATE NOCASE"
+        )?;
+        let rows = stmt.query_map([], Self::row_to_snippet)?;
+        let mut snippets = Vec::new();
+        for row in rows { snippets.push(row?); }
+        Ok(snippets)
+    }
+
+    /// 获取单个代码片段
+    pub fn get_snippet(&self, id: i64) -> Result<Snippet> {
+        self.conn.query_row(
+            "SELECT id, name, abbreviation, body, created_at, updated_at FROM snippets WHERE id = ?1",
+            params![id],
+            Self::row_to_snippet,
+        ).with_context(|| format!("Snippet not found: {}", id))
+    }
+
+    /// 按缩写查找代码片段
+    pub fn find_snippet_by_abbreviation(&self, abbreviation: &str) -> Result<Option<Snippet>> {
+        self.conn.query_row(
+            "SELECT id, name, abbreviation, body, created_at, updated_at FROM snippets WHERE abbreviation = ?1",
+            params![abbreviation.trim()],
+            Self::row_to_snippet,
+        ).optional().map_err(Into::into)
+    }
+
+    fn row_to_snippet(row: &rusqlite::Row) -> rusqlite::Result<Snippet> {
+        let body: String = row.get(3)?;
+        Ok(Snippet {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            abbreviation: row.get(2)?,
+            inputs: template::input_names(&body).unwrap_or_default(),
+            body,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+        })
+    }
+
+    /// 校验片段字段，返回规范化后的 (名称, 缩写)
+    fn check_snippet<'a>(name: &'a str, abbreviation: Option<&'a str>, body: &str) -> Result<(&'a str, Option<&'a str>)> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Snippet name cannot be empty"));
+        }
+        template::validate(body).context("Invalid snippet template")?;
+        let abbreviation = abbreviation.map(str::trim).filter(|a| !a.is_empty());
+        Ok((name, abbreviation))
+    }
+
+    // ==========================================
+    // 设置
+    // ==========================================
+
+    /// 当前设置
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// 校验并保存设置
+    pub fn update_settings(&mut self, settings: Settings) -> Result<()> {
+        settings.validate()?;
+        settings.save(&self.data_dir)?;
+        self.settings = settings;
+        Ok(())
+    }
 }
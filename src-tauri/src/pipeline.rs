@@ -0,0 +1,72 @@
+use anyhow::{anyhow, Result};
+use crossbeam_channel::{bounded, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::persist::{PreparedImage, Storage};
+
+// 图片保存流水线
+//
+// 捕获到的图片交给固定数量的 worker 处理。哈希、编码、缩略图和文件写入都在
+// 数据库锁外完成，只有去重查询和最后的插入短暂持有 Storage 的锁，
+// 大截图不会阻塞 IPC 命令和文本捕获。
+// 队列有上限：待处理的图片都以原始 RGBA 保存在内存中，队列满时 submit 阻塞。
+
+/// 处理完成后的回调，参数为记录 ID 和缩略图数据
+pub type ImageCallback = Box<dyn FnOnce(Result<(i64, Vec<u8>)>) + Send>;
+
+struct ImageJob {
+    width: usize,
+    height: usize,
+    rgba_data: Vec<u8>,
+    done: ImageCallback,
+}
+
+pub struct ImagePipeline {
+    sender: Sender<ImageJob>,
+}
+
+impl ImagePipeline {
+    /// 启动 workers 个处理线程，最多排队 capacity 张图片
+    pub fn new(storage: Arc<Mutex<Storage>>, workers: usize, capacity: usize) -> Self {
+        let (sender, receiver) = bounded::<ImageJob>(capacity);
+        for index in 0..workers.max(1) {
+            let receiver = receiver.clone();
+            let storage = Arc::clone(&storage);
+            thread::Builder::new()
+                .name(format!("image-worker-{}", index))
+                .spawn(move || {
+                    // 发送端全部释放后 recv 返回错误，线程退出
+                    while let Ok(job) = receiver.recv() {
+                        let result = process(&storage, job.width, job.height, job.rgba_data);
+                        (job.done)(result);
+                    }
+                })
+                .expect("Failed to spawn image worker");
+        }
+        Self { sender }
+    }
+
+    /// 提交一张图片，队列已满时阻塞直到有空位
+    pub fn submit(&self, width: usize, height: usize, rgba_data: Vec<u8>, done: ImageCallback) -> Result<()> {
+        self.sender
+            .send(ImageJob { width, height, rgba_data, done })
+            .map_err(|_| anyhow!("Image workers have stopped"))
+    }
+}
+
+fn process(storage: &Mutex<Storage>, width: usize, height: usize, rgba_data: Vec<u8>) -> Result<(i64, Vec<u8>)> {
+    let lock = || storage.lock().map_err(|_| anyhow!("Storage lock poisoned"));
+
+    let hash_hex = Storage::image_hash(&rgba_data);
+    let (options, blobs) = {
+        let storage = lock()?;
+        if let Some(existing) = storage.find_image(&hash_hex)? {
+            return Ok(existing);
+        }
+        (storage.image_options(), storage.blob_store())
+    };
+
+    let prepared = PreparedImage::prepare(width, height, rgba_data, hash_hex, options, &blobs)?;
+    lock()?.insert_image(prepared)
+}
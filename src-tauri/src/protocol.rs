@@ -0,0 +1,194 @@
+use anyhow::{anyhow, bail, Context, Result};
+use http::{header, Method, Request, Response, StatusCode, Uri};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::codec::{self, ImageCodec};
+use crate::persist::{Storage, Thumbnail};
+use crate::transform;
+
+// pastee:// 自定义协议
+//
+// 前端不再通过 asset 协议直接读文件，只能按记录 ID 取图片：
+//   /image/{id}                    原图
+//   /thumbnail/{id}                默认缩略图
+//   /thumbnail/{id}/{尺寸}@{倍率}x  设置中的命名缩略图（未缓存时生成）
+// 以上路径都可以带 ?w=&h= 按比例缩小到指定框内（不放大，不缓存到磁盘）。
+// macOS/Linux 上的 URL 为 pastee://localhost/...，Windows 上为 http://pastee.localhost/...，
+// 前端用 convertFileSrc(path, "pastee") 生成，路径会被整体百分号编码。
+// 文件按内容寻址，ETag 取文件名中的哈希，内容不变时浏览器用 304 复用缓存。
+
+/// 协议名
+pub const SCHEME: &str = "pastee";
+
+/// 即时缩放的最大边长
+pub const MAX_RESIZE: u32 = 4096;
+
+/// 原图或默认缩略图的路径
+pub fn image_path(id: i64, thumbnail: bool) -> String {
+    format!("{}/{}", if thumbnail { "thumbnail" } else { "image" }, id)
+}
+
+/// 命名尺寸缩略图的路径
+pub fn thumbnail_path(id: i64, size: &str, scale: u32) -> String {
+    format!("thumbnail/{}/{}@{}x", id, size, scale)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Original(i64),
+    Thumbnail(i64),
+    Sized { id: i64, size: String, scale: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Route {
+    target: Target,
+    /// 即时缩放的目标框
+    resize: Option<(u32, u32)>,
+}
+
+/// 处理一个请求，不依赖 Tauri，读文件和缩放都在 Storage 锁外完成
+pub fn handle(storage: &Mutex<Storage>, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return error(StatusCode::METHOD_NOT_ALLOWED, "Only GET is supported");
+    }
+    let route = match parse(request.uri()) {
+        Ok(route) => route,
+        Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+    };
+    match serve(storage, &route, request) {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("❌ pastee:// 请求失败 {}: {:?}", request.uri(), e);
+            error(StatusCode::NOT_FOUND, &e.to_string())
+        }
+    }
+}
+
+fn serve(storage: &Mutex<Storage>, route: &Route, request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>> {
+    let lock = || storage.lock().map_err(|_| anyhow!("Storage lock poisoned"));
+
+    let (path, blobs) = {
+        let storage = lock()?;
+        let blobs = storage.blob_store();
+        let path = match &route.target {
+            Target::Original(id) => Some(storage.get_image_paths(*id)?.0),
+            Target::Thumbnail(id) => Some(storage.get_image_paths(*id)?.1),
+            Target::Sized { id, size, scale } => match storage.find_thumbnail(*id, size, *scale)? {
+                Thumbnail::Cached(path) => Some(path),
+                Thumbnail::Missing(job) => {
+                    drop(storage);
+                    let bytes = job.render()?;
+                    Some(lock()?.save_thumbnail(&job, &bytes)?)
+                }
+            },
+        };
+        (path.context("Image not found")?, blobs)
+    };
+    if path.is_empty() {
+        bail!("Record has no image");
+    }
+
+    let full_path = blobs.full_path(&path);
+    let stem = full_path.file_stem().and_then(|s| s.to_str()).context("Invalid image path")?;
+    let etag = match route.resize {
+        Some((width, height)) => format!("\"{}-{}x{}\"", stem, width, height),
+        None => format!("\"{}\"", stem),
+    };
+    let builder = Response::builder()
+        .header(header::CACHE_CONTROL, "private, no-cache")
+        .header(header::ETAG, &etag)
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff");
+
+    let not_modified = request.headers().get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+    if not_modified {
+        return Ok(builder.status(StatusCode::NOT_MODIFIED).body(Vec::new())?);
+    }
+
+    let bytes = fs::read(&full_path).with_context(|| format!("Failed to read {}", path))?;
+    let (body, content_type) = match route.resize {
+        Some((width, height)) => {
+            let img = image::load_from_memory(&bytes).context("Failed to decode image")?;
+            let img = if img.width() > width || img.height() > height {
+                img.thumbnail(width, height)
+            } else {
+                img
+            };
+            (codec::encode(&img, ImageCodec::WebpLossless, 100)?, ImageCodec::WebpLossless.mime())
+        }
+        None => (bytes, content_type(&full_path)),
+    };
+
+    let length = body.len();
+    let body = if request.method() == Method::HEAD { Vec::new() } else { body };
+    Ok(builder
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_LENGTH, length)
+        .body(body)?)
+}
+
+fn parse(uri: &Uri) -> Result<Route> {
+    let path = String::from_utf8(transform::percent_decode(uri.path())?).context("Invalid UTF-8 in path")?;
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let id = |segment: &str| segment.parse::<i64>().map_err(|_| anyhow!("Invalid record id: {}", segment));
+
+    let target = match segments.as_slice() {
+        ["image", id_part] => Target::Original(id(id_part)?),
+        ["thumbnail", id_part] => Target::Thumbnail(id(id_part)?),
+        ["thumbnail", id_part, spec] => {
+            let (size, scale) = match spec.split_once('@') {
+                Some((size, scale)) => {
+                    let scale = scale.strip_suffix('x').unwrap_or(scale);
+                    (size, scale.parse::<u32>().map_err(|_| anyhow!("Invalid scale: {}", scale))?)
+                }
+                None => (*spec, 1),
+            };
+            Target::Sized { id: id(id_part)?, size: size.to_string(), scale }
+        }
+        _ => bail!("Unknown path: {}", path),
+    };
+
+    let mut width = None;
+    let mut height = None;
+    for (key, value) in url::form_urlencoded::parse(uri.query().unwrap_or("").as_bytes()) {
+        let value = value.parse::<u32>()
+            .ok()
+            .filter(|v| (1..=MAX_RESIZE).contains(v))
+            .ok_or_else(|| anyhow!("{} must be between 1 and {}", key, MAX_RESIZE))?;
+        match key.as_ref() {
+            "w" => width = Some(value),
+            "h" => height = Some(value),
+            _ => bail!("Unknown parameter: {}", key),
+        }
+    }
+    // 只给一边时另一边不限制
+    let resize = match (width, height) {
+        (None, None) => None,
+        (width, height) => Some((width.unwrap_or(MAX_RESIZE), height.unwrap_or(MAX_RESIZE))),
+    };
+
+    Ok(Route { target, resize })
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.as_bytes().to_vec())
+        .expect("Static response parts are valid")
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use image::{DynamicImage, GenericImageView, GrayImage, Luma};
+use qrcode::QrCode;
+use rxing::{BarcodeFormat, DecodeHints, Exceptions};
+use std::collections::HashSet;
+
+// 二维码、条形码识别与二维码生成
+//
+// 图片保存时在流水线的 worker 中识别其中的二维码和条形码，识别出的内容写入记录的
+// content_text（可搜索）和以 qr 为键的元数据，可以另存为文本记录。
+// QR 码用 rqrr 识别，EAN、UPC、Code 128 等一维条形码用 rxing 识别。
+// 生成时把文本编码为黑白 PNG 图片记录，便于用手机扫码。
+
+/// 识别前把大图缩小到此边长以内，截图中的二维码远大于识别所需的分辨率
+pub const SCAN_MAX_DIMENSION: u32 = 2048;
+
+/// 生成的二维码图片的最小边长
+pub const RENDER_MIN_SIZE: u32 = 512;
+
+/// 识别的一维条形码格式
+const BARCODE_FORMATS: [BarcodeFormat; 9] = [
+    BarcodeFormat::EAN_13,
+    BarcodeFormat::EAN_8,
+    BarcodeFormat::UPC_A,
+    BarcodeFormat::UPC_E,
+    BarcodeFormat::CODE_128,
+    BarcodeFormat::CODE_39,
+    BarcodeFormat::CODE_93,
+    BarcodeFormat::ITF,
+    BarcodeFormat::CODABAR,
+];
+
+/// 识别图片中的二维码和条形码，返回去重后的内容（二维码在前，各自按识别顺序）
+pub fn decode(img: &DynamicImage) -> Vec<String> {
+    let scaled;
+    let img = if img.width() > SCAN_MAX_DIMENSION || img.height() > SCAN_MAX_DIMENSION {
+        scaled = img.thumbnail(SCAN_MAX_DIMENSION, SCAN_MAX_DIMENSION);
+        &scaled
+    } else {
+        img
+    };
+
+    // 透明像素按白色处理：透明底的二维码图片很常见，直接取灰度会变成全黑
+    let luma = GrayImage::from_fn(img.width(), img.height(), |x, y| {
+        let [r, g, b, a] = img.get_pixel(x, y).0;
+        let luma = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+        Luma([((luma * a as u32 + 255 * (255 - a as u32)) / 255) as u8])
+    });
+
+    let mut codes: Vec<String> = Vec::new();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        luma.width() as usize,
+        luma.height() as usize,
+        |x, y| luma.get_pixel(x as u32, y as u32).0[0],
+    );
+    for grid in prepared.detect_grids() {
+        match grid.decode() {
+            Ok((_, content)) => push_code(&mut codes, content),
+            Err(e) => eprintln!("⚠️ 二维码解码失败: {:?}", e),
+        }
+    }
+
+    let (width, height) = luma.dimensions();
+    let mut hints = DecodeHints {
+        PossibleFormats: Some(HashSet::from(BARCODE_FORMATS)),
+        ..Default::default()
+    };
+    match rxing::helpers::detect_multiple_in_luma_with_hints(luma.into_raw(), width, height, &mut hints) {
+        Ok(results) => {
+            for result in results {
+                push_code(&mut codes, result.getText().to_string());
+            }
+        }
+        Err(Exceptions::NotFoundException(_)) => {}
+        Err(e) => eprintln!("⚠️ 条形码解码失败: {}", e),
+    }
+    codes
+}
+
+fn push_code(codes: &mut Vec<String>, content: String) {
+    if !content.is_empty() && !codes.contains(&content) {
+        codes.push(content);
+    }
+}
+
+/// 把文本编码为二维码图片（带静区，边长不小于 RENDER_MIN_SIZE）
+pub fn encode(text: &str) -> Result<DynamicImage> {
+    let code = QrCode::new(text.as_bytes()).context("Text is too long for a QR code")?;
+    let image = code.render::<Luma<u8>>()
+        .quiet_zone(true)
+        .min_dimensions(RENDER_MIN_SIZE, RENDER_MIN_SIZE)
+        .build();
+    Ok(DynamicImage::ImageLuma8(image))
+}
@@ -0,0 +1,147 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::codec::ImageCodec;
+use crate::guard::{self, CaptureLimits, OversizedContent};
+use crate::highlight;
+use crate::imagehash;
+use crate::thumbnail::{self, ThumbnailSize};
+
+// 设置管理
+//
+// 设置以 JSON 保存在数据目录下的 settings.json，
+// 缺失的字段使用默认值，便于新版本增加设置项。
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// 用户设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// 代码高亮主题（syntect 内置主题名）
+    pub highlight_theme: String,
+    /// 相似图片的判定阈值：感知哈希的汉明距离（0–64），0 表示只匹配画面一致的图片
+    pub similar_image_threshold: u32,
+    /// 保存与已有图片相似的新图片时的处理方式
+    pub similar_images: SimilarImages,
+    /// 图片原图的存储编码（PNG、无损或有损 WebP，不提供 AVIF，见 codec 模块）
+    pub image_codec: ImageCodec,
+    /// 有损编码的质量（1–100）
+    pub image_quality: u8,
+    /// 原图的最大边长（像素），超出时等比缩小；0 表示不限制
+    pub max_image_dimension: u32,
+    /// 缩略图尺寸，按名称请求
+    pub thumbnail_sizes: Vec<ThumbnailSize>,
+    /// 每种缩略图尺寸生成的倍率（1x、2x 等）
+    pub thumbnail_scales: Vec<u32>,
+    /// 捕获图片的像素数上限，超出时丢弃
+    pub max_image_pixels: u64,
+    /// 捕获文本的字节数上限
+    pub max_text_bytes: usize,
+    /// 捕获 HTML 的字节数上限
+    pub max_html_bytes: usize,
+    /// 文本或 HTML 超出上限时的处理方式
+    pub oversized_content: OversizedContent,
+    /// 超过该字节数的文本和 HTML 正文另存为文件，记录中只保留开头一段
+    pub large_body_bytes: usize,
+    /// 正文另存为文件时记录中保留的纯文本字节数，超出部分不参与搜索
+    pub search_text_bytes: usize,
+}
+
+/// 原图最大边长的下限，避免误设过小的值把图片缩得无法使用
+pub const MIN_IMAGE_DIMENSION: u32 = 64;
+
+/// 相似图片的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SimilarImages {
+    /// 都保留，通过相似图片查询归为一组
+    #[default]
+    Group,
+    /// 新图片替换未置顶、不在看板中的相似图片
+    Replace,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            highlight_theme: highlight::DEFAULT_THEME.to_string(),
+            similar_image_threshold: 5,
+            similar_images: SimilarImages::default(),
+            image_codec: ImageCodec::default(),
+            image_quality: 80,
+            max_image_dimension: 0,
+            thumbnail_sizes: thumbnail::default_sizes(),
+            thumbnail_scales: thumbnail::default_scales(),
+            max_image_pixels: guard::DEFAULT_MAX_IMAGE_PIXELS,
+            max_text_bytes: guard::DEFAULT_MAX_TEXT_BYTES,
+            max_html_bytes: guard::DEFAULT_MAX_HTML_BYTES,
+            oversized_content: OversizedContent::default(),
+            large_body_bytes: guard::DEFAULT_LARGE_BODY_BYTES,
+            search_text_bytes: guard::DEFAULT_SEARCH_TEXT_BYTES,
+        }
+    }
+}
+
+impl Settings {
+    /// 从数据目录读取设置，文件不存在时返回默认值
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let path = data_dir.join(SETTINGS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(&path).context("Failed to read settings")?;
+        serde_json::from_str(&json).context("Failed to parse settings")
+    }
+
+    /// 写入数据目录（先写临时文件再替换，避免写到一半时损坏）
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let path = data_dir.join(SETTINGS_FILE);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?).context("Failed to write settings")?;
+        fs::rename(&tmp_path, &path).context("Failed to replace settings")?;
+        Ok(())
+    }
+
+    /// 检查设置值是否有效
+    pub fn validate(&self) -> Result<()> {
+        if !highlight::theme_names().contains(&self.highlight_theme.as_str()) {
+            bail!("Unknown highlight theme: {}", self.highlight_theme);
+        }
+        if self.similar_image_threshold > imagehash::HASH_BITS {
+            bail!("Similar image threshold must be at most {}", imagehash::HASH_BITS);
+        }
+        if !(1..=100).contains(&self.image_quality) {
+            bail!("Image quality must be between 1 and 100");
+        }
+        if self.max_image_dimension != 0 && self.max_image_dimension < MIN_IMAGE_DIMENSION {
+            bail!("Maximum image dimension must be 0 (unlimited) or at least {}", MIN_IMAGE_DIMENSION);
+        }
+        thumbnail::validate(&self.thumbnail_sizes, &self.thumbnail_scales)?;
+        if self.max_image_pixels < guard::MIN_IMAGE_PIXELS {
+            bail!("Maximum image pixels must be at least {}", guard::MIN_IMAGE_PIXELS);
+        }
+        if self.max_text_bytes < guard::MIN_CONTENT_BYTES || self.max_html_bytes < guard::MIN_CONTENT_BYTES {
+            bail!("Text and HTML limits must be at least {} bytes", guard::MIN_CONTENT_BYTES);
+        }
+        if self.large_body_bytes < guard::BODY_PREVIEW_BYTES {
+            bail!("Large body threshold must be at least {} bytes", guard::BODY_PREVIEW_BYTES);
+        }
+        if self.search_text_bytes < guard::BODY_PREVIEW_BYTES {
+            bail!("Searchable text must be at least {} bytes", guard::BODY_PREVIEW_BYTES);
+        }
+        Ok(())
+    }
+
+    /// 捕获内容的大小上限
+    pub fn capture_limits(&self) -> CaptureLimits {
+        CaptureLimits {
+            max_image_pixels: self.max_image_pixels,
+            max_text_bytes: self.max_text_bytes,
+            max_html_bytes: self.max_html_bytes,
+            oversized: self.oversized_content,
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use anyhow::{anyhow, Result};
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use std::sync::LazyLock;
+
+// 表格识别
+//
+// 从电子表格复制的内容通常是制表符分隔的文本加 HTML <table>。
+// 这里把 TSV、CSV 和 HTML 表格解析为统一的行列结构，
+// 并支持转换为 Markdown、CSV、JSON 和 HTML 表格。
+
+static TABLE_SELECTOR: LazyLock<Selector> = LazyLock::new(|| Selector::parse("table").unwrap());
+
+/// 表格来源格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    Tsv,
+    Csv,
+    Html,
+}
+
+/// 解析后的表格，所有行等宽，第一行视为表头
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Table {
+    pub format: TableFormat,
+    pub rows: Vec<Vec<String>>,
+    pub columns: usize,
+}
+
+/// 从内容中识别表格：HTML 优先，其次 TSV、CSV
+pub fn detect(content: &str) -> Option<Table> {
+    if content.contains("<table") || content.contains("<TABLE") {
+        if let Some(table) = parse_html(content) {
+            return Some(table);
+        }
+    }
+    parse_tsv(content).or_else(|| parse_csv(content))
+}
+
+/// 同 detect，未识别到表格时返回错误
+pub fn parse(content: &str) -> Result<Table> {
+    detect(content).ok_or_else(|| anyhow!("Clip does not contain a table"))
+}
+
+/// 解析制表符分隔文本：至少两行两列，每行列数一致且首列非空
+///
+/// 首列非空用于排除以制表符缩进的代码。
+pub fn parse_tsv(text: &str) -> Option<Table> {
+    let rows: Vec<Vec<String>> = content_lines(text)
+        .map(|line| line.split('\t').map(str::to_string).collect())
+        .collect();
+    Table::from_uniform_rows(TableFormat::Tsv, rows)
+}
+
+/// 解析 CSV（RFC 4180，支持引号、转义引号和引号内换行）
+///
+/// 为避免把带逗号的普通句子当成表格，逗号后普遍跟空格的文本不视为 CSV；
+/// 每行都是千位分隔数字（如 1,000）的文本同样不视为 CSV。
+pub fn parse_csv(text: &str) -> Option<Table> {
+    let rows = split_csv(text.trim_end_matches(['\r', '\n']))?;
+    let separators: usize = rows.iter().map(|r| r.len() - 1).sum();
+    let spaced: usize = rows.iter().flat_map(|r| r.iter().skip(1)).filter(|f| f.starts_with(' ')).count();
+    if separators == 0 || spaced * 2 > separators || rows.iter().all(|row| is_grouped_number(row)) {
+        return None;
+    }
+    Table::from_uniform_rows(TableFormat::Csv, rows)
+}
+
+/// 按逗号拆开的一行是否为千位分隔的数字：首段 1-3 位数字，其余各段恰好 3 位数字
+fn is_grouped_number(fields: &[String]) -> bool {
+    let digits = |field: &String, len: std::ops::RangeInclusive<usize>| {
+        len.contains(&field.len()) && field.bytes().all(|b| b.is_ascii_digit())
+    };
+    digits(&fields[0], 1..=3) && fields[1..].iter().all(|field| digits(field, 3..=3))
+}
+
+/// 解析第一个 HTML <table>，colspan 以空单元格补齐
+pub fn parse_html(html: &str) -> Option<Table> {
+    let document = Html::parse_document(html);
+    let table = document.select(&TABLE_SELECTOR).next()?;
+
+    let mut rows = Vec::new();
+    collect_rows(table, &mut rows);
+    let table = Table::padded(TableFormat::Html, rows)?;
+    (table.rows.len() * table.columns >= 2).then_some(table)
+}
+
+impl Table {
+    /// 行列数必须一致且每行首列非空（TSV/CSV 的识别依据）
+    fn from_uniform_rows(format: TableFormat, rows: Vec<Vec<String>>) -> Option<Self> {
+        let columns = rows.first()?.len();
+        if rows.len() < 2 || columns < 2 || rows.iter().any(|r| r.len() != columns || r[0].trim().is_empty()) {
+            return None;
+        }
+        Some(Self { format, rows, columns })
+    }
+
+    /// 行列数可以不一致，较短的行补空单元格
+    fn padded(format: TableFormat, mut rows: Vec<Vec<String>>) -> Option<Self> {
+        let columns = rows.iter().map(Vec::len).max().filter(|&c| c > 0)?;
+        for row in &mut rows {
+            row.resize(columns, String::new());
+        }
+        Some(Self { format, rows, columns })
+    }
+
+    /// 表头（第一行）
+    pub fn header(&self) -> &[String] {
+        &self.rows[0]
+    }
+
+    /// GitHub 风格 Markdown 表格
+    pub fn to_markdown(&self) -> String {
+        let format_row = |row: &[String]| {
+            let cells: Vec<String> = row.iter()
+                .map(|cell| cell.replace('|', "\\|").replace(['\r', '\n'], " "))
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = vec![format_row(self.header()), format_row(&vec!["---".to_string(); self.columns])];
+        lines.extend(self.rows[1..].iter().map(|row| format_row(row)));
+        lines.join("\n")
+    }
+
+    /// CSV，必要时为字段加引号
+    pub fn to_csv(&self) -> String {
+        self.rows.iter()
+            .map(|row| row.iter().map(|cell| csv_field(cell)).collect::<Vec<_>>().join(","))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// JSON 对象数组，以表头为键（空白或重复的表头自动编号）
+    pub fn to_json(&self) -> Result<String> {
+        let mut keys: Vec<String> = Vec::with_capacity(self.columns);
+        for (i, name) in self.header().iter().enumerate() {
+            let name = name.trim();
+            let key = if name.is_empty() || keys.iter().any(|k| k == name) {
+                format!("column{}", i + 1)
+            } else {
+                name.to_string()
+            };
+            keys.push(key);
+        }
+
+        let records: Vec<serde_json::Value> = self.rows[1..].iter()
+            .map(|row| {
+                let object = keys.iter().cloned()
+                    .zip(row.iter().map(|cell| serde_json::Value::String(cell.clone())))
+                    .collect();
+                serde_json::Value::Object(object)
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&records)?)
+    }
+
+    /// HTML 表格，第一行为 <thead>
+    pub fn to_html(&self) -> String {
+        let render_row = |row: &[String], tag: &str| {
+            let cells: String = row.iter()
+                .map(|cell| format!("<{tag}>{}</{tag}>", escape_html(cell)))
+                .collect();
+            format!("<tr>{}</tr>", cells)
+        };
+        let body: String = self.rows[1..].iter().map(|row| render_row(row, "td")).collect();
+        format!(
+            "<table><thead>{}</thead><tbody>{}</tbody></table>",
+            render_row(self.header(), "th"),
+            body
+        )
+    }
+}
+
+/// 去掉末尾空行后的各行（兼容 \r\n）
+fn content_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.trim_end_matches(['\r', '\n']).split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line))
+}
+
+/// 按 RFC 4180 拆分 CSV，引号未闭合时返回 None
+fn split_csv(text: &str) -> Option<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    row.push(field);
+    rows.push(row);
+    Some(rows)
+}
+
+fn csv_field(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) || cell.starts_with(' ') || cell.ends_with(' ') {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// 收集表格行（含 thead/tbody/tfoot，不进入嵌套表格）
+fn collect_rows(element: ElementRef, rows: &mut Vec<Vec<String>>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        match child.value().name() {
+            "thead" | "tbody" | "tfoot" => collect_rows(child, rows),
+            "tr" => {
+                let mut cells = Vec::new();
+                for cell in child.children().filter_map(ElementRef::wrap) {
+                    if !matches!(cell.value().name(), "td" | "th") {
+                        continue;
+                    }
+                    let text = cell.text().collect::<String>();
+                    cells.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+                    let span: usize = cell.value().attr("colspan").and_then(|s| s.trim().parse().ok()).unwrap_or(1);
+                    for _ in 1..span.min(100) {
+                        cells.push(String::new());
+                    }
+                }
+                if !cells.is_empty() {
+                    rows.push(cells);
+                }
+            }
+            _ => {}
+        }
+    }
+}
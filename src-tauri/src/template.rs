@@ -0,0 +1,143 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+
+// 代码片段模板引擎
+//
+// 支持的占位符：
+// - {date} / {date:%Y-%m-%d}   当前日期，可指定 strftime 格式
+// - {time} / {time:%H:%M}      当前时间，可指定 strftime 格式
+// - {clipboard}                当前剪贴板文本
+// - {uuid}                     随机 UUID (v4)
+// - {input:Name}               渲染时由用户填写的输入项
+// 使用 {{ 和 }} 输出字面量花括号。
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+/// 渲染上下文
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    pub now: DateTime<Local>,
+    pub clipboard: Option<String>,
+    pub inputs: HashMap<String, String>,
+}
+
+impl TemplateContext {
+    pub fn new(clipboard: Option<String>, inputs: HashMap<String, String>) -> Self {
+        Self { now: Local::now(), clipboard, inputs }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Literal(String),
+    Placeholder { name: &'a str, arg: Option<&'a str> },
+}
+
+/// 渲染模板
+pub fn render(template: &str, ctx: &TemplateContext) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    for token in parse(template)? {
+        match token {
+            Token::Literal(text) => output.push_str(&text),
+            Token::Placeholder { name, arg } => match (name, arg) {
+                ("date", fmt) => output.push_str(&format_time(ctx, fmt.unwrap_or(DEFAULT_DATE_FORMAT))),
+                ("time", fmt) => output.push_str(&format_time(ctx, fmt.unwrap_or(DEFAULT_TIME_FORMAT))),
+                ("clipboard", None) => output.push_str(ctx.clipboard.as_deref().unwrap_or_default()),
+                ("uuid", None) => output.push_str(&uuid::Uuid::new_v4().to_string()),
+                ("input", Some(field)) => {
+                    let value = ctx.inputs.get(field)
+                        .ok_or_else(|| anyhow!("Missing input: {}", field))?;
+                    output.push_str(value);
+                }
+                _ => unreachable!("placeholders are validated by parse"),
+            },
+        }
+    }
+    Ok(output)
+}
+
+/// 列出模板中需要用户填写的输入项（按首次出现顺序，去重）
+pub fn input_names(template: &str) -> Result<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for token in parse(template)? {
+        if let Token::Placeholder { name: "input", arg: Some(field) } = token {
+            if !names.iter().any(|n| n == field) {
+                names.push(field.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// 检查模板语法
+pub fn validate(template: &str) -> Result<()> {
+    parse(template).map(|_| ())
+}
+
+fn parse(template: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        literal.push_str(&rest[..pos]);
+        let brace = &rest[pos..];
+
+        if let Some(after) = brace.strip_prefix("{{") {
+            literal.push('{');
+            rest = after;
+        } else if let Some(after) = brace.strip_prefix("}}") {
+            literal.push('}');
+            rest = after;
+        } else if brace.starts_with('}') {
+            bail!("Unmatched '}}' at byte {}", template.len() - brace.len());
+        } else {
+            let end = brace.find('}')
+                .ok_or_else(|| anyhow!("Unclosed placeholder at byte {}", template.len() - brace.len()))?;
+            let body = &brace[1..end];
+            let (name, arg) = match body.split_once(':') {
+                // 输入项名称忽略首尾空白，日期格式原样保留
+                Some((name, arg)) if name.trim() == "input" => ("input", Some(arg.trim())),
+                Some((name, arg)) => (name.trim(), Some(arg)),
+                None => (body.trim(), None),
+            };
+            check_placeholder(name, arg)?;
+
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+            tokens.push(Token::Placeholder { name, arg });
+            rest = &brace[end + 1..];
+        }
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    Ok(tokens)
+}
+
+fn check_placeholder(name: &str, arg: Option<&str>) -> Result<()> {
+    match (name, arg) {
+        ("date" | "time", Some(fmt)) => {
+            if StrftimeItems::new(fmt).any(|item| matches!(item, Item::Error)) {
+                bail!("Invalid {} format: {}", name, fmt);
+            }
+            Ok(())
+        }
+        ("date" | "time" | "clipboard" | "uuid", None) => Ok(()),
+        ("input", Some(field)) if !field.trim().is_empty() => Ok(()),
+        ("input", _) => bail!("Input placeholder needs a name, e.g. {{input:Name}}"),
+        (_, Some(_)) if ["clipboard", "uuid"].contains(&name) => {
+            bail!("Placeholder {{{}}} does not take an argument", name)
+        }
+        _ => bail!("Unknown placeholder: {{{}}}", name),
+    }
+}
+
+fn format_time(ctx: &TemplateContext, fmt: &str) -> String {
+    ctx.now.format_with_items(StrftimeItems::new(fmt)).to_string()
+}
@@ -0,0 +1,89 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::codec::{self, ImageCodec};
+
+// 多尺寸缩略图
+//
+// 设置中列出若干命名尺寸（如列表用的 list、预览用的 preview），每种尺寸按
+// 1x / 2x 等倍率分别生成，适配高分屏。缩略图在首次请求时从原图生成，
+// 作为 blob 缓存，记录通过 role 为 thumbnail:{名称}@{倍率}x:{宽}x{高} 的引用指向它。
+// role 中带有像素尺寸，修改设置后旧规格的缓存不再命中，由 regenerate 清理并重建。
+
+/// 倍率上限
+pub const MAX_SCALE: u32 = 4;
+
+/// 命名的缩略图尺寸（1x 下的最大宽高，按比例缩放，不放大）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThumbnailSize {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ThumbnailSize {
+    pub fn new(name: &str, width: u32, height: u32) -> Self {
+        Self { name: name.to_string(), width, height }
+    }
+}
+
+/// 默认尺寸：列表项和预览
+pub fn default_sizes() -> Vec<ThumbnailSize> {
+    vec![ThumbnailSize::new("list", 240, 180), ThumbnailSize::new("preview", 800, 600)]
+}
+
+/// 默认倍率：普通屏和 2 倍高分屏
+pub fn default_scales() -> Vec<u32> {
+    vec![1, 2]
+}
+
+/// 检查尺寸和倍率设置
+pub fn validate(sizes: &[ThumbnailSize], scales: &[u32]) -> Result<()> {
+    for (index, size) in sizes.iter().enumerate() {
+        let valid_name = !size.name.is_empty()
+            && size.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            bail!("Invalid thumbnail size name: {:?}", size.name);
+        }
+        if sizes[..index].iter().any(|other| other.name == size.name) {
+            bail!("Duplicate thumbnail size: {}", size.name);
+        }
+        if !(16..=4096).contains(&size.width) || !(16..=4096).contains(&size.height) {
+            bail!("Thumbnail size {} must be between 16 and 4096 pixels", size.name);
+        }
+    }
+    if scales.is_empty() || scales.iter().any(|scale| !(1..=MAX_SCALE).contains(scale)) {
+        bail!("Thumbnail scales must be between 1 and {}", MAX_SCALE);
+    }
+    Ok(())
+}
+
+/// 缩略图在 blob_refs 中的 role
+pub fn role(size: &ThumbnailSize, scale: u32) -> String {
+    format!("thumbnail:{}@{}x:{}x{}", size.name, scale, size.width * scale, size.height * scale)
+}
+
+/// 需要生成的缩略图：从原图文件读取、缩放、编码，不访问数据库
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThumbnailJob {
+    pub record_id: i64,
+    pub role: String,
+    pub source: PathBuf,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ThumbnailJob {
+    /// 生成无损 WebP 缩略图；原图小于目标尺寸时保持原尺寸
+    pub fn render(&self) -> Result<Vec<u8>> {
+        let img = image::open(&self.source)
+            .with_context(|| format!("Failed to open {}", self.source.display()))?;
+        let img = if img.width() > self.width || img.height() > self.height {
+            img.thumbnail(self.width, self.height)
+        } else {
+            img
+        };
+        codec::encode(&img, ImageCodec::WebpLossless, 100)
+    }
+}
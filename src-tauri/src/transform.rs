@@ -0,0 +1,340 @@
+use anyhow::{anyhow, bail, Result};
+use base64::{Engine as _, engine::general_purpose};
+use serde::Serialize;
+
+use crate::highlight;
+use crate::html;
+use crate::json;
+use crate::markdown;
+use crate::persist::ClipData;
+use crate::setting::Settings;
+use crate::table;
+
+// "粘贴为" 文本转换
+//
+// 每个转换由 id 标识，前端通过 list_transforms 获取列表，
+// 再以 id 调用 preview_transform / paste_transformed。
+
+/// 转换的输入来源
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    /// 记录的纯文本内容
+    Text,
+    /// HTML 记录的原始标记；其他类型的记录原样输出其文本
+    Html,
+    /// HTML 记录的原始标记，其他类型的记录取其文本，均交给转换处理
+    Raw,
+}
+
+/// 转换的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    /// 纯文本
+    Text,
+    /// HTML 富文本，粘贴时以输入文本作为纯文本后备
+    Html,
+}
+
+/// 已注册的文本转换
+pub struct Transform {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub source: Source,
+    pub output: Output,
+    apply: ApplyFn,
+}
+
+/// 转换函数：大多数转换只依赖输入，少数需要读取设置（如高亮主题）
+#[derive(Clone, Copy)]
+enum ApplyFn {
+    Plain(fn(&str) -> Result<String>),
+    Configured(fn(&str, &Settings) -> Result<String>),
+}
+
+impl Transform {
+    /// 文本 → 文本
+    const fn text(id: &'static str, label: &'static str, apply: fn(&str) -> Result<String>) -> Self {
+        Self { id, label, source: Source::Text, output: Output::Text, apply: ApplyFn::Plain(apply) }
+    }
+
+    /// HTML 标记 → 文本
+    const fn markup(id: &'static str, label: &'static str, apply: fn(&str) -> Result<String>) -> Self {
+        Self { id, label, source: Source::Html, output: Output::Text, apply: ApplyFn::Plain(apply) }
+    }
+
+    /// 文本 → HTML 富文本
+    const fn rich(id: &'static str, label: &'static str, apply: fn(&str) -> Result<String>) -> Self {
+        Self { id, label, source: Source::Text, output: Output::Html, apply: ApplyFn::Plain(apply) }
+    }
+
+    /// 文本 → HTML 富文本，转换时读取设置
+    const fn rich_configured(
+        id: &'static str,
+        label: &'static str,
+        apply: fn(&str, &Settings) -> Result<String>,
+    ) -> Self {
+        Self { id, label, source: Source::Text, output: Output::Html, apply: ApplyFn::Configured(apply) }
+    }
+
+    /// 表格转换：输入为 HTML 标记或 TSV/CSV 文本
+    const fn table(id: &'static str, label: &'static str, output: Output, apply: fn(&str) -> Result<String>) -> Self {
+        Self { id, label, source: Source::Raw, output, apply: ApplyFn::Plain(apply) }
+    }
+
+    fn call(&self, input: &str, settings: &Settings) -> Result<String> {
+        match self.apply {
+            ApplyFn::Plain(apply) => apply(input),
+            ApplyFn::Configured(apply) => apply(input, settings),
+        }
+    }
+}
+
+/// 转换列表项（用于前端展示）
+#[derive(Debug, Serialize)]
+pub struct TransformInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub output: Output,
+}
+
+static REGISTRY: &[Transform] = &[
+    Transform::markup("plain_text", "纯文本", plain_text),
+    Transform::markup("markdown", "Markdown", markdown),
+    Transform::rich("markdown_html", "Markdown 富文本", markdown_html),
+    Transform::rich_configured("highlight", "代码高亮", highlight_code),
+    Transform::table("table_markdown", "表格 → Markdown", Output::Text, table_markdown),
+    Transform::table("table_csv", "表格 → CSV", Output::Text, table_csv),
+    Transform::table("table_json", "表格 → JSON", Output::Text, table_json),
+    Transform::table("table_html", "表格 → HTML 表格", Output::Html, table_html),
+    Transform::text("upper", "大写", upper),
+    Transform::text("lower", "小写", lower),
+    Transform::text("title", "首字母大写", title_case),
+    Transform::text("trim_lines", "去除行首尾空白", trim_lines),
+    Transform::text("sort_lines", "按行排序", sort_lines),
+    Transform::text("dedupe_lines", "按行去重", dedupe_lines),
+    Transform::text("json_pretty", "JSON 格式化", json_pretty),
+    Transform::text("json_minify", "JSON 压缩", json_minify),
+    Transform::text("base64_encode", "Base64 编码", base64_encode),
+    Transform::text("base64_decode", "Base64 解码", base64_decode),
+    Transform::text("url_encode", "URL 编码", url_encode),
+    Transform::text("url_decode", "URL 解码", url_decode),
+    Transform::text("shell_escape", "Shell 转义", shell_escape),
+    Transform::text("json_escape", "JSON 字符串", json_escape),
+];
+
+/// 所有已注册的转换
+pub fn registry() -> &'static [Transform] {
+    REGISTRY
+}
+
+/// 转换列表（用于前端展示）
+pub fn list() -> Vec<TransformInfo> {
+    REGISTRY.iter().map(|t| TransformInfo { id: t.id, label: t.label, output: t.output }).collect()
+}
+
+/// 按 id 查找转换
+pub fn find(id: &str) -> Option<&'static Transform> {
+    REGISTRY.iter().find(|t| t.id == id)
+}
+
+/// 使用默认设置应用转换
+pub fn apply(id: &str, data: &ClipData) -> Result<ClipData> {
+    apply_with(id, data, &Settings::default())
+}
+
+/// 对记录内容应用转换，富文本转换的结果为 HTML（附带原文本作为后备）
+pub fn apply_with(id: &str, data: &ClipData, settings: &Settings) -> Result<ClipData> {
+    let transform = find(id).ok_or_else(|| anyhow!("Unknown transform: {}", id))?;
+    let output = run(transform, data, settings)?;
+    Ok(match transform.output {
+        Output::Text => ClipData::Text(output),
+        Output::Html => ClipData::Html { text: source_text(data)?, html: output },
+    })
+}
+
+/// 同 apply，直接返回转换结果（富文本转换返回 HTML 标记）
+pub fn apply_text(id: &str, data: &ClipData) -> Result<String> {
+    let transform = find(id).ok_or_else(|| anyhow!("Unknown transform: {}", id))?;
+    run(transform, data, &Settings::default())
+}
+
+fn run(transform: &Transform, data: &ClipData, settings: &Settings) -> Result<String> {
+    match (transform.source, data) {
+        (Source::Html | Source::Raw, ClipData::Html { html, .. }) => transform.call(html, settings),
+        (Source::Html, _) => source_text(data),
+        (Source::Text | Source::Raw, _) => transform.call(&source_text(data)?, settings),
+    }
+}
+
+/// 取出记录中可供转换的文本（HTML 取其纯文本部分）
+fn source_text(data: &ClipData) -> Result<String> {
+    match data {
+        ClipData::Text(text) | ClipData::Color(text) => Ok(text.clone()),
+        ClipData::Html { text, .. } => Ok(text.clone()),
+        ClipData::Files(paths) => Ok(paths.join("\n")),
+        ClipData::Image(_) => bail!("Image clips cannot be transformed as text"),
+    }
+}
+
+// ==========================================
+// 转换实现
+// ==========================================
+
+fn plain_text(input: &str) -> Result<String> {
+    Ok(html::to_plain_text(input))
+}
+
+fn markdown(input: &str) -> Result<String> {
+    Ok(html::to_markdown(input))
+}
+
+fn markdown_html(input: &str) -> Result<String> {
+    Ok(markdown::to_html(input))
+}
+
+fn table_markdown(input: &str) -> Result<String> {
+    Ok(table::parse(input)?.to_markdown())
+}
+
+fn table_csv(input: &str) -> Result<String> {
+    Ok(table::parse(input)?.to_csv())
+}
+
+fn table_json(input: &str) -> Result<String> {
+    table::parse(input)?.to_json()
+}
+
+fn table_html(input: &str) -> Result<String> {
+    Ok(table::parse(input)?.to_html())
+}
+
+/// 自动识别语言，按设置中的主题输出内联样式 HTML
+fn highlight_code(input: &str, settings: &Settings) -> Result<String> {
+    highlight::to_html(input, None, &settings.highlight_theme)
+}
+
+fn upper(input: &str) -> Result<String> {
+    Ok(input.to_uppercase())
+}
+
+fn lower(input: &str) -> Result<String> {
+    Ok(input.to_lowercase())
+}
+
+/// 每个单词首字母大写，其余小写
+fn title_case(input: &str) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut at_word_start = true;
+    for c in input.chars() {
+        if c.is_alphanumeric() || c == '\'' {
+            if at_word_start {
+                output.extend(c.to_uppercase());
+            } else {
+                output.extend(c.to_lowercase());
+            }
+            at_word_start = false;
+        } else {
+            output.push(c);
+            at_word_start = true;
+        }
+    }
+    Ok(output)
+}
+
+fn trim_lines(input: &str) -> Result<String> {
+    Ok(input.lines().map(str::trim).collect::<Vec<_>>().join("\n"))
+}
+
+fn sort_lines(input: &str) -> Result<String> {
+    let mut lines: Vec<&str> = input.lines().collect();
+    lines.sort_unstable();
+    Ok(lines.join("\n"))
+}
+
+/// 去除重复行，保留首次出现的顺序
+fn dedupe_lines(input: &str) -> Result<String> {
+    let mut seen = std::collections::HashSet::new();
+    let lines: Vec<&str> = input.lines().filter(|line| seen.insert(*line)).collect();
+    Ok(lines.join("\n"))
+}
+
+fn json_pretty(input: &str) -> Result<String> {
+    json::pretty(input)
+}
+
+fn json_minify(input: &str) -> Result<String> {
+    json::minify(input)
+}
+
+fn base64_encode(input: &str) -> Result<String> {
+    Ok(general_purpose::STANDARD.encode(input))
+}
+
+/// 同时接受标准和 URL-safe 字母表，可省略填充，忽略空白
+fn base64_decode(input: &str) -> Result<String> {
+    let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let unpadded = compact.trim_end_matches('=');
+    let bytes = general_purpose::STANDARD_NO_PAD
+        .decode(unpadded)
+        .or_else(|_| general_purpose::URL_SAFE_NO_PAD.decode(unpadded))
+        .map_err(|e| anyhow!("Invalid Base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| anyhow!("Decoded Base64 is not valid UTF-8 text"))
+}
+
+/// 百分号编码，仅保留 RFC 3986 非保留字符
+fn url_encode(input: &str) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => output.push(byte as char),
+            _ => output.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    Ok(output)
+}
+
+fn url_decode(input: &str) -> Result<String> {
+    let decoded = percent_decode(input)?;
+    String::from_utf8(decoded).map_err(|_| anyhow!("Decoded URL is not valid UTF-8 text"))
+}
+
+/// 百分号解码为字节，% 后必须紧跟两个十六进制数字
+///
+/// 不用 u8::from_str_radix 直接解析：它接受前导符号，会把 %+F 解成 0x0F。
+pub(crate) fn percent_decode(input: &str) -> Result<Vec<u8>> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3)
+                .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+                .ok_or_else(|| anyhow!("Invalid percent escape at byte {}", i))?;
+            decoded.push((hex_value(hex[0]) << 4) | hex_value(hex[1]));
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(decoded)
+}
+
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+/// POSIX shell 单引号转义
+fn shell_escape(input: &str) -> Result<String> {
+    Ok(format!("'{}'", input.replace('\'', "'\\''")))
+}
+
+/// 转换为 JSON 字符串字面量（含引号）
+fn json_escape(input: &str) -> Result<String> {
+    Ok(serde_json::to_string(input)?)
+}
@@ -0,0 +1,57 @@
+/// 剪贴内容动作测试
+/// 验证各类记录可用的动作以及按 id 解析出的执行结果
+
+mod common;
+
+use pastee_lib::action::{available, resolve, Effect};
+use pastee_lib::persist::ClipData;
+use common::{create_test_dir, get_test_data_dir};
+
+fn ids(data: &ClipData) -> Vec<&'static str> {
+    available(data).iter().map(|a| a.id).collect()
+}
+
+#[test]
+fn test_url_and_email_actions() {
+    let text = ClipData::Text("Docs: https://tauri.app/start. Questions: team@example.com".to_string());
+    assert_eq!(ids(&text), vec!["open_url", "compose_mail"]);
+    assert_eq!(resolve("open_url", &text).unwrap(), Effect::OpenUrl("https://tauri.app/start".to_string()));
+    assert_eq!(resolve("compose_mail", &text).unwrap(), Effect::OpenUrl("mailto:team@example.com".to_string()));
+
+    let html = ClipData::Html { text: "https://example.com".to_string(), html: "<a>x</a>".to_string() };
+    assert_eq!(ids(&html), vec!["open_url"]);
+
+    assert!(ids(&ClipData::Text("nothing to do".to_string())).is_empty());
+}
+
+#[test]
+fn test_reveal_path_action() {
+    let temp_dir = create_test_dir();
+    let dir = get_test_data_dir(&temp_dir);
+    let file = dir.join("notes.txt");
+    std::fs::write(&file, "x").unwrap();
+
+    let text = ClipData::Text(file.display().to_string());
+    assert_eq!(resolve("reveal_path", &text).unwrap(), Effect::Reveal(file.clone()));
+
+    let files = ClipData::Files(vec![file.display().to_string()]);
+    assert_eq!(ids(&files), vec!["reveal_path"]);
+
+    let missing = ClipData::Text(dir.join("missing.txt").display().to_string());
+    assert!(resolve("reveal_path", &missing).is_err(), "Paths that do not exist cannot be revealed");
+}
+
+#[test]
+fn test_color_actions() {
+    let data = ClipData::Color("#FF8000".to_string());
+    assert_eq!(ids(&data), vec!["copy_hex", "copy_rgb", "copy_hsl"]);
+    assert_eq!(resolve("copy_rgb", &data).unwrap(), Effect::Copy("rgb(255, 128, 0)".to_string()));
+    assert_eq!(resolve("copy_hsl", &data).unwrap(), Effect::Copy("hsl(30, 100%, 50%)".to_string()));
+
+    let translucent = ClipData::Color("hsla(120, 100%, 25%, 0.5)".to_string());
+    assert_eq!(resolve("copy_hex", &translucent).unwrap(), Effect::Copy("#00800080".to_string()));
+    assert_eq!(resolve("copy_rgb", &translucent).unwrap(), Effect::Copy("rgba(0, 128, 0, 0.5)".to_string()));
+
+    assert!(resolve("copy_hex", &ClipData::Text("#FF8000".to_string())).is_err(), "Only color clips");
+    assert!(resolve("nope", &data).is_err());
+}
@@ -0,0 +1,144 @@
+/// 按内容寻址的图片存储测试
+/// 验证相同内容只保存一份、引用计数与文件清理、未登记文件的清理，以及旧版图片文件的迁移
+
+mod common;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use pastee_lib::persist::{ClipData, Storage};
+use rusqlite::{params, Connection};
+use std::fs;
+use std::path::Path;
+use common::{create_test_dir, get_test_data_dir};
+
+fn pixels(seed: u8) -> RgbaImage {
+    RgbaImage::from_fn(16, 8, |x, y| Rgba([seed, x as u8 * 10, y as u8 * 20, 255]))
+}
+
+/// 按旧版布局写入一条图片记录：images/YYYYMM/original 和 images/YYYYMM/thumbnail
+fn add_legacy_image(data_dir: &Path, name: &str, image: &RgbaImage) -> i64 {
+    let month_dir = data_dir.join("images").join("202601");
+    fs::create_dir_all(month_dir.join("original")).unwrap();
+    fs::create_dir_all(month_dir.join("thumbnail")).unwrap();
+    image.save_with_format(month_dir.join("original").join(format!("{}.png", name)), ImageFormat::Png).unwrap();
+    image.save_with_format(month_dir.join("thumbnail").join(format!("{}.webp", name)), ImageFormat::WebP).unwrap();
+
+    let conn = Connection::open(data_dir.join("clippy.db")).unwrap();
+    conn.execute(
+        "INSERT INTO records (type, hash, created_at, content_text, image_path, thumbnail_path,
+                              image_format, image_size, image_hash, width, height, tag)
+         VALUES ('image', ?1, 1, '[图片]', ?2, ?3, 'png', 0, ?1, 16, 8, '[\"image\"]')",
+        params![name, format!("202601/original/{}.png", name), format!("202601/thumbnail/{}.webp", name)],
+    ).unwrap();
+    conn.last_insert_rowid()
+}
+
+fn blob_files(data_dir: &Path) -> usize {
+    fn count(dir: &Path) -> usize {
+        fs::read_dir(dir).map(|entries| {
+            entries.flatten().map(|e| if e.path().is_dir() { count(&e.path()) } else { 1 }).sum()
+        }).unwrap_or(0)
+    }
+    count(&data_dir.join("images").join("blobs"))
+}
+
+#[test]
+fn test_delete_removes_unreferenced_blobs() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let id = storage.add_image(16, 8, pixels(1).into_raw()).unwrap().0;
+    let (original, thumbnail) = storage.get_image_paths(id).unwrap();
+    assert!(original.starts_with("blobs/image/png/") && original.ends_with(".png"));
+    assert!(thumbnail.starts_with("blobs/image/webp/"));
+    assert_eq!(blob_files(&data_dir), 2);
+    assert_eq!(storage.add_image(16, 8, pixels(1).into_raw()).unwrap().0, id, "Identical images are one record");
+
+    let pinned = storage.add_image(16, 8, pixels(2).into_raw()).unwrap().0;
+    storage.toggle_pin(pinned).unwrap();
+    storage.delete_record(id).unwrap();
+    assert_eq!(blob_files(&data_dir), 2, "Only the deleted image's files are removed");
+
+    storage.clear_unpinned().unwrap();
+    assert_eq!(blob_files(&data_dir), 2, "Pinned images keep their files");
+    storage.delete_record(pinned).unwrap();
+    assert_eq!(blob_files(&data_dir), 0);
+}
+
+#[test]
+fn test_migrates_legacy_files_and_shares_identical_content() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    drop(Storage::new(&data_dir).unwrap());
+
+    let first = add_legacy_image(&data_dir, "100_aaaa", &pixels(3));
+    let second = add_legacy_image(&data_dir, "200_bbbb", &pixels(3));
+    let other = add_legacy_image(&data_dir, "300_cccc", &pixels(4));
+
+    let mut storage = Storage::new(&data_dir).unwrap();
+    assert!(!data_dir.join("images").join("202601").exists(), "Old month folders are emptied and removed");
+    assert_eq!(blob_files(&data_dir), 4, "Identical files are stored once");
+    assert_eq!(storage.get_image_paths(first).unwrap(), storage.get_image_paths(second).unwrap());
+    assert_ne!(storage.get_image_paths(first).unwrap(), storage.get_image_paths(other).unwrap());
+    assert!(matches!(storage.get_content(first).unwrap(), ClipData::Image(bytes) if !bytes.is_empty()));
+
+    // 迁移时换成完整哈希，再次复制同一张图片会命中旧记录
+    assert_eq!(storage.add_image(16, 8, pixels(4).into_raw()).unwrap().0, other);
+
+    storage.delete_record(first).unwrap();
+    let (original, _) = storage.get_image_paths(second).unwrap();
+    assert!(data_dir.join("images").join(&original).exists(), "Blobs still referenced are kept");
+    storage.delete_record(second).unwrap();
+    assert!(!data_dir.join("images").join(&original).exists());
+    assert_eq!(storage.migrate_image_files().unwrap(), 0, "Migration runs once");
+}
+
+#[test]
+fn test_failed_migration_keeps_legacy_files() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    drop(Storage::new(&data_dir).unwrap());
+
+    // 缩略图无法读取，迁移在原图复制进存储之后失败
+    let id = add_legacy_image(&data_dir, "100_aaaa", &pixels(5));
+    let thumbnail = data_dir.join("images").join("202601").join("thumbnail").join("100_aaaa.webp");
+    fs::remove_file(&thumbnail).unwrap();
+    fs::create_dir(&thumbnail).unwrap();
+
+    let storage = Storage::new(&data_dir).unwrap();
+    assert!(storage.migrate_image_files().is_err());
+    let (original, _) = storage.get_image_paths(id).unwrap();
+    assert_eq!(original, "202601/original/100_aaaa.png", "The record still points at the legacy file");
+    assert!(matches!(storage.get_content(id).unwrap(), ClipData::Image(bytes) if !bytes.is_empty()));
+    assert_eq!(blob_files(&data_dir), 1, "The copied original is not registered");
+
+    // 重启时清理未登记的文件，修复缩略图后迁移完成
+    drop(storage);
+    fs::remove_dir(&thumbnail).unwrap();
+    pixels(5).save_with_format(&thumbnail, ImageFormat::WebP).unwrap();
+    let storage = Storage::new(&data_dir).unwrap();
+    assert!(storage.get_image_paths(id).unwrap().0.starts_with("blobs/image/png/"));
+    assert_eq!(blob_files(&data_dir), 2);
+    assert!(!data_dir.join("images").join("202601").exists());
+}
+
+#[test]
+fn test_unregistered_files_are_removed_on_startup() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let id = storage.add_image(16, 8, pixels(6).into_raw()).unwrap().0;
+    drop(storage);
+
+    // 模拟写入文件后事务失败
+    let orphan = data_dir.join("images").join("blobs").join("text").join("plain+zst").join("ab").join("abcd.plain+zst");
+    fs::create_dir_all(orphan.parent().unwrap()).unwrap();
+    fs::write(&orphan, b"orphan").unwrap();
+    assert_eq!(blob_files(&data_dir), 3);
+
+    let storage = Storage::new(&data_dir).unwrap();
+    assert!(!orphan.exists());
+    assert!(!data_dir.join("images").join("blobs").join("text").exists(), "Emptied folders are removed");
+    assert_eq!(blob_files(&data_dir), 2, "Registered files are kept");
+    assert!(matches!(storage.get_content(id).unwrap(), ClipData::Image(_)));
+}
@@ -0,0 +1,106 @@
+/// 看板测试
+/// 验证看板 CRUD、排序、多看板归属以及清理保护
+
+mod common;
+
+use pastee_lib::persist::Storage;
+use common::{create_test_dir, get_test_data_dir};
+
+#[test]
+fn test_board_crud() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+
+    let id = storage.create_board("Release notes").unwrap();
+    assert!(storage.create_board("Release notes").is_err(), "Board names should be unique");
+    assert!(storage.create_board("  ").is_err(), "Empty names should be rejected");
+
+    storage.rename_board(id, "SQL snippets").unwrap();
+    let boards = storage.list_boards().unwrap();
+    assert_eq!(boards.len(), 1);
+    assert_eq!(boards[0].name, "SQL snippets");
+    assert_eq!(boards[0].item_count, 0);
+
+    storage.delete_board(id).unwrap();
+    assert!(storage.list_boards().unwrap().is_empty());
+    assert!(storage.rename_board(id, "Gone").is_err());
+}
+
+#[test]
+fn test_add_remove_and_order() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let board = storage.create_board("SQL").unwrap();
+    let a = storage.add_text("SELECT 1".to_string()).unwrap();
+    let b = storage.add_text("SELECT 2".to_string()).unwrap();
+    let c = storage.add_text("SELECT 3".to_string()).unwrap();
+
+    storage.add_to_board(board, a).unwrap();
+    storage.add_to_board(board, b).unwrap();
+    storage.add_to_board(board, c).unwrap();
+    // 重复添加不改变顺序
+    storage.add_to_board(board, a).unwrap();
+
+    let ids: Vec<i64> = storage.get_board_clips(board).unwrap().iter().map(|i| i.id).collect();
+    assert_eq!(ids, vec![a, b, c], "Clips should keep insertion order");
+
+    storage.reorder_board(board, &[c, a]).unwrap();
+    let ids: Vec<i64> = storage.get_board_clips(board).unwrap().iter().map(|i| i.id).collect();
+    assert_eq!(ids, vec![c, a, b], "Listed clips first, the rest keep their order");
+
+    storage.remove_from_board(board, a).unwrap();
+    let ids: Vec<i64> = storage.get_board_clips(board).unwrap().iter().map(|i| i.id).collect();
+    assert_eq!(ids, vec![c, b]);
+    assert!(storage.get_content(a).is_ok(), "Removing from a board should keep the clip");
+}
+
+#[test]
+fn test_clip_in_multiple_boards() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let first = storage.create_board("First").unwrap();
+    let second = storage.create_board("Second").unwrap();
+    let clip = storage.add_text("shared".to_string()).unwrap();
+
+    storage.add_to_board(first, clip).unwrap();
+    storage.add_to_board(second, clip).unwrap();
+    assert_eq!(storage.get_clip_boards(clip).unwrap(), vec![first, second]);
+
+    // 删除记录时从所有看板中移除
+    storage.delete_record(clip).unwrap();
+    assert!(storage.get_board_clips(first).unwrap().is_empty());
+    assert!(storage.list_boards().unwrap().iter().all(|b| b.item_count == 0));
+}
+
+#[test]
+fn test_board_clips_survive_clear_unpinned() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let board = storage.create_board("Keep").unwrap();
+    let kept = storage.add_text("on a board".to_string()).unwrap();
+    storage.add_text("loose clip".to_string()).unwrap();
+    storage.add_to_board(board, kept).unwrap();
+
+    let deleted = storage.clear_unpinned().unwrap();
+    assert_eq!(deleted, 1, "Only the loose clip should be cleared");
+    let items = storage.get_recent(10, 0).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, kept);
+}
+
+#[test]
+fn test_add_missing_record_fails() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+
+    let board = storage.create_board("Board").unwrap();
+    assert!(storage.add_to_board(board, 99999).is_err(), "Foreign key should reject unknown clips");
+}
@@ -0,0 +1,161 @@
+/// 内容分类测试
+/// 验证各分类器的标签和元数据，以及已有记录的迁移回填
+
+mod common;
+
+use pastee_lib::classify::{classifiers, classify, classify_html};
+use pastee_lib::persist::Storage;
+use rusqlite::Connection;
+use serde_json::json;
+use common::{create_test_dir, get_test_data_dir};
+
+fn tags(text: &str) -> Vec<String> {
+    classify(text).tags
+}
+
+#[test]
+fn test_registry() {
+    let mut ids: Vec<&str> = classifiers().iter().map(|c| c.tag).collect();
+    let total = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), total, "Classifier tags should be unique");
+    assert!(classify("   ").tags.is_empty());
+    assert!(classify("just some words").tags.is_empty());
+}
+
+#[test]
+fn test_url_and_email() {
+    let result = classify("See https://docs.rs/regex and https://docs.rs/url, or mail dev@Example.org.");
+    assert_eq!(result.tags, vec!["url", "email"]);
+    assert_eq!(result.metadata["url"], json!({ "count": 2, "domains": ["docs.rs"] }));
+    assert_eq!(result.metadata["email"], json!({ "count": 1, "domains": ["example.org"] }));
+
+    assert_eq!(tags("https://user@example.com/x"), vec!["url"], "Credentials in URLs are not emails");
+    assert!(tags("user at example dot com").is_empty());
+}
+
+#[test]
+fn test_phone() {
+    let result = classify("+1 (555) 123-4567");
+    assert_eq!(result.tags, vec!["phone"]);
+    assert_eq!(result.metadata["phone"], json!({ "number": "+15551234567", "international": true }));
+
+    assert_eq!(tags("010-1234-5678"), vec!["phone"]);
+    assert_eq!(tags("12345678"), vec!["number"], "Bare digits are numbers, not phones");
+    assert!(!tags("2024-01-15").contains(&"phone".to_string()));
+}
+
+#[test]
+fn test_path() {
+    let result = classify("/usr/local/bin/rustc");
+    assert_eq!(result.tags, vec!["path"]);
+    assert_eq!(result.metadata["path"], json!({ "style": "unix", "name": "rustc" }));
+
+    let result = classify(r"C:\Users\Ada\Documents\report.PDF");
+    assert_eq!(result.metadata["path"], json!({ "style": "windows", "name": "report.PDF", "extension": "pdf" }));
+
+    assert_eq!(tags("~/projects/pastee/")[0], "path");
+    assert!(!tags("// just a comment").contains(&"path".to_string()));
+    assert!(!tags("/ is the root of a very long sentence here").contains(&"path".to_string()));
+}
+
+#[test]
+fn test_ip() {
+    let result = classify("192.168.1.10");
+    assert_eq!(result.tags, vec!["ip"]);
+    assert_eq!(result.metadata["ip"], json!({ "version": 4, "private": true }));
+
+    let result = classify("2001:db8::1/64");
+    assert_eq!(result.metadata["ip"], json!({ "version": 6, "private": false, "prefix": 64 }));
+
+    assert!(tags("256.1.1.1").is_empty());
+    assert!(tags("10.0.0.0/33").is_empty());
+}
+
+#[test]
+fn test_uuid() {
+    let result = classify("{67e55044-10b1-426f-9247-bb680e5fe0c8}");
+    assert_eq!(result.tags, vec!["uuid"]);
+    assert_eq!(result.metadata["uuid"], json!({ "version": 4 }));
+    assert!(!tags("67e5504410b1426f9247bb680e5fe0c8").contains(&"uuid".to_string()), "Only hyphenated UUIDs");
+}
+
+#[test]
+fn test_number_and_date() {
+    assert_eq!(classify("1,234,567").metadata["number"], json!({ "value": 1234567, "integer": true }));
+    assert_eq!(classify("-3.5e2").metadata["number"], json!({ "value": -350.0, "integer": false }));
+    assert!(tags("12 apples").is_empty());
+
+    assert_eq!(classify("2024-01-15").metadata["date"], json!({ "iso": "2024-01-15" }));
+    assert_eq!(classify("2024-01-15 08:30:00").metadata["date"], json!({ "iso": "2024-01-15T08:30:00" }));
+    assert_eq!(classify("2024-01-15T08:30:00+08:00").metadata["date"], json!({ "iso": "2024-01-15T08:30:00+08:00" }));
+    assert_eq!(classify("March 5, 2024").metadata["date"], json!({ "iso": "2024-03-05" }));
+    assert!(tags("2024-13-45").is_empty());
+}
+
+#[test]
+fn test_code_and_structured_text() {
+    let result = classify("fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}");
+    assert_eq!(result.tags, vec!["code"]);
+    assert_eq!(result.metadata["code"], json!({ "language": "Rust" }));
+
+    assert_eq!(classify("{\"a\": [1]}").tags, vec!["json"], "JSON is not also tagged as code");
+    assert_eq!(tags("# Title\n\nSome **bold** text and a [link](https://x.io)"), vec!["markdown", "url"]);
+    assert_eq!(classify("a\tb\n1\t2").metadata["table"], json!({ "format": "tsv", "rows": 2, "columns": 2 }));
+    let indented = "\tfn main() {\n\t    println!(\"{}\", 1);\n\t}";
+    assert_eq!(tags(indented), vec!["code"], "Tab-indented code is not a table");
+}
+
+#[test]
+fn test_html_tables_use_markup() {
+    let result = classify_html("k v", "<table><tr><td>k</td><td>v</td></tr><tr><td>1</td><td>2</td></tr></table>");
+    assert_eq!(result.tags, vec!["table"]);
+    assert_eq!(result.metadata["table"]["format"], json!("html"));
+}
+
+#[test]
+fn test_clips_store_tags_and_metadata() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let id = storage.add_text("https://github.com/Hewenz/pastee".to_string()).unwrap();
+    let recent = storage.get_recent(1, 0).unwrap();
+    assert_eq!(recent[0].tags, vec!["text".to_string(), "url".to_string()]);
+    assert_eq!(storage.get_metadata(id).unwrap()["url"]["domains"], json!(["github.com"]));
+
+    let color = storage.add_text("#FF00FF".to_string()).unwrap();
+    assert!(storage.get_metadata(color).unwrap().is_empty());
+}
+
+#[test]
+fn test_migration_backfills_existing_rows() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let id = {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        storage.add_text("ping 10.0.0.1".to_string()).unwrap();
+        storage.add_text("10.0.0.1".to_string()).unwrap()
+    };
+
+    // 还原为分类功能之前的数据库：没有 metadata 列，文本记录只有 text 标签
+    {
+        let conn = Connection::open(data_dir.join("clippy.db")).unwrap();
+        conn.execute_batch(
+            "ALTER TABLE records DROP COLUMN image_phash;
+             ALTER TABLE records DROP COLUMN color_rgba;
+             ALTER TABLE records DROP COLUMN metadata;
+             UPDATE records SET tag = '[\"text\"]';
+             PRAGMA user_version = 5;",
+        ).unwrap();
+    }
+
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let recent = storage.get_recent(10, 0).unwrap();
+    assert_eq!(recent[0].tags, vec!["text".to_string(), "ip".to_string()]);
+    assert_eq!(recent[1].tags, vec!["text".to_string()]);
+    assert_eq!(storage.get_metadata(id).unwrap()["ip"]["version"], json!(4));
+
+    assert_eq!(storage.reclassify_all().unwrap(), 0, "Nothing changes when classifiers are unchanged");
+}
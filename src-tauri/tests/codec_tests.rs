@@ -0,0 +1,120 @@
+/// 图片存储编码测试
+/// 验证各编码的往返结果、最大边长缩小、粘贴时的 RGBA 还原以及已有原图的重新压缩（含锁外编码）
+
+mod common;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use pastee_lib::codec::{encode, fit, ImageCodec};
+use pastee_lib::persist::{ClipData, Storage};
+use pastee_lib::setting::Settings;
+use serde_json::json;
+use common::{create_test_dir, get_test_data_dir};
+
+fn gradient(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| Rgba([(x * 3) as u8, (y * 5) as u8, 200, 255]))
+}
+
+/// 粘贴回剪贴板时使用的 RGBA 数据
+fn pasted_rgba(storage: &Storage, id: i64) -> RgbaImage {
+    match storage.get_content(id).unwrap() {
+        ClipData::Image(bytes) => image::load_from_memory(&bytes).unwrap().into_rgba8(),
+        other => panic!("Expected image, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_encode_round_trip() {
+    let image = DynamicImage::ImageRgba8(gradient(40, 30));
+    for codec in [ImageCodec::Png, ImageCodec::WebpLossless] {
+        let bytes = encode(&image, codec, 80).unwrap();
+        assert_eq!(image::load_from_memory(&bytes).unwrap().into_rgba8(), image.to_rgba8(), "{:?} is lossless", codec);
+    }
+
+    let lossy = image::load_from_memory(&encode(&image, ImageCodec::WebpLossy, 50).unwrap()).unwrap();
+    assert_eq!((lossy.width(), lossy.height()), (40, 30));
+    let close = lossy.to_rgba8().pixels().zip(image.to_rgba8().pixels())
+        .all(|(a, b)| a.0.iter().zip(b.0.iter()).all(|(x, y)| x.abs_diff(*y) <= 24));
+    assert!(close, "Lossy WebP stays close to the source");
+
+    assert!(fit(&image, 0).is_none());
+    assert!(fit(&image, 40).is_none());
+    let small = fit(&image, 20).unwrap();
+    assert_eq!((small.width(), small.height()), (20, 15));
+}
+
+#[test]
+fn test_codec_settings_apply_to_new_images() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    storage.update_settings(Settings {
+        image_codec: ImageCodec::WebpLossy,
+        image_quality: 70,
+        max_image_dimension: 64,
+        ..Default::default()
+    }).unwrap();
+
+    let id = storage.add_image(128, 96, gradient(128, 96).into_raw()).unwrap().0;
+    assert_eq!(pasted_rgba(&storage, id).dimensions(), (64, 48));
+    assert!(storage.get_image_paths(id).unwrap().0.ends_with(".webp"));
+    assert_eq!(storage.get_recent(1, 0).unwrap()[0].preview, "[图片] 64x48 WEBP");
+    assert_eq!(storage.get_metadata(id).unwrap()["image"], json!({
+        "codec": "webp_lossy", "quality": 70, "source_width": 128, "source_height": 96
+    }));
+
+    for invalid in [
+        Settings { image_quality: 0, ..Default::default() },
+        Settings { max_image_dimension: 10, ..Default::default() },
+    ] {
+        assert!(storage.update_settings(invalid).is_err());
+    }
+}
+
+#[test]
+fn test_recompress_existing_originals() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let source = gradient(60, 40);
+    let id = storage.add_image(60, 40, source.clone().into_raw()).unwrap().0;
+    let (png_path, _) = storage.get_image_paths(id).unwrap();
+    assert!(storage.images_to_recompress().unwrap().is_empty());
+
+    storage.update_settings(Settings { image_codec: ImageCodec::WebpLossless, ..Default::default() }).unwrap();
+    assert_eq!(storage.images_to_recompress().unwrap(), vec![id]);
+    assert!(storage.recompress_image(id).unwrap().is_some());
+
+    assert_eq!(pasted_rgba(&storage, id), source, "Lossless recompression keeps the pixels");
+    assert!(storage.get_image_paths(id).unwrap().0.ends_with(".webp"));
+    assert!(!data_dir.join("images").join(png_path).exists(), "The replaced PNG is no longer referenced");
+    assert!(storage.images_to_recompress().unwrap().is_empty());
+    assert!(storage.recompress_image(id).unwrap().is_none());
+}
+
+#[test]
+fn test_recompress_outside_the_lock() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let id = storage.add_image(60, 40, gradient(60, 40).into_raw()).unwrap().0;
+    let other = storage.add_image(30, 20, gradient(30, 20).into_raw()).unwrap().0;
+    storage.update_settings(Settings { image_codec: ImageCodec::WebpLossless, ..Default::default() }).unwrap();
+
+    // 命令中的做法：取任务，锁外编码，再写入数据库；编码期间写入的元数据保留
+    let job = storage.recompress_job(id).unwrap().unwrap();
+    let image = job.run(&storage.blob_store()).unwrap();
+    storage.save_qr_codes(id, &["scanned meanwhile".to_string()]).unwrap();
+    assert!(storage.save_recompressed(image).unwrap().is_some());
+    assert!(storage.get_image_paths(id).unwrap().0.ends_with(".webp"));
+    assert_eq!(storage.get_qr_codes(id).unwrap(), vec!["scanned meanwhile".to_string()]);
+    assert_eq!(storage.get_metadata(id).unwrap()["image"]["codec"], json!("webp_lossless"));
+    assert!(storage.recompress_job(id).unwrap().is_none());
+
+    // 编码期间记录被删除时放弃结果
+    let job = storage.recompress_job(other).unwrap().unwrap();
+    let image = job.run(&storage.blob_store()).unwrap();
+    storage.delete_record(other).unwrap();
+    assert!(storage.save_recompressed(image).unwrap().is_none());
+}
@@ -174,7 +174,6 @@ fn test_invalid_hex() {
         "#GG00FF",     // 非法字符
         "#F",          // 长度错误
         "#FF",         // 长度错误
-        "#FFFF",       // 长度错误
         "#FFFFF",      // 长度错误
         "#FFFFFFF",    // 长度错误
         "FF00FF",      // 缺少 #
@@ -200,9 +199,10 @@ fn test_invalid_rgb() {
     
     let invalid_rgb = vec![
         "rgb(255, 0)",         // 参数不足
-        "rgb(255, 0, 255, 0)", // 参数过多
-        "rgb(255 0 255)",      // 缺少逗号
+        "rgb(255, 0, 255, 0, 1)", // 参数过多
+        "rgb(255 0, 255)",     // 混用逗号和空格
         "rgb 255, 0, 255",     // 缺少括号
+        "rgb(999, a, b)",      // 数值越界、非数字
     ];
     
     for invalid in invalid_rgb {
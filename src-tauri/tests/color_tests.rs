@@ -0,0 +1,153 @@
+/// 颜色模型测试
+/// 验证 CSS Color 4 解析、写法转换、WCAG 对比度、浅色 / 深色序列和颜色记录的迁移
+
+mod common;
+
+use pastee_lib::color::{contrast, parse, Notation, Rgba};
+use pastee_lib::persist::{ClipType, Storage};
+use rusqlite::Connection;
+use common::{create_test_dir, get_test_data_dir};
+
+const ALL_NOTATIONS: [Notation; 8] = [
+    Notation::Hex, Notation::Rgb, Notation::Hsl, Notation::Hwb,
+    Notation::Lab, Notation::Lch, Notation::Oklab, Notation::Oklch,
+];
+
+fn rgba(r: u8, g: u8, b: u8, a: u8) -> Rgba {
+    Rgba { r, g, b, a }
+}
+
+fn hex(text: &str) -> String {
+    parse(text).unwrap_or_else(|| panic!("Failed to parse {}", text)).format(Notation::Hex).unwrap()
+}
+
+#[test]
+fn test_parse_css4_syntax() {
+    assert_eq!(parse("rgb(255 0 0 / 50%)").unwrap(), rgba(255, 0, 0, 128));
+    assert_eq!(parse("rgba(0, 0, 255, .25)").unwrap(), rgba(0, 0, 255, 64));
+    assert_eq!(parse("rgb(255, 0, 0, 0.5)").unwrap(), rgba(255, 0, 0, 128), "rgb() accepts alpha");
+    assert_eq!(parse("#F00C").unwrap(), rgba(255, 0, 0, 204));
+    assert_eq!(hex("hsl(120deg 100% 25%)"), "#008000");
+    assert_eq!(hex("hsl(0.5turn, 100%, 50%)"), "#00FFFF");
+    assert_eq!(hex("hwb(120 0% 50%)"), "#008000");
+    assert_eq!(hex("hwb(0 60% 60%)"), "#808080", "Whiteness plus blackness over 100% is gray");
+    assert_eq!(hex("lab(100% 0 0)"), "#FFFFFF");
+    assert_eq!(hex("oklch(62.8% 0.2577 29.23)"), "#FF0000");
+    assert_eq!(hex("oklab(0 0 0)"), "#000000");
+}
+
+#[test]
+fn test_named_colors() {
+    assert_eq!(hex("RebeccaPurple"), "#663399");
+    assert_eq!(hex("tomato"), "#FF6347");
+    assert_eq!(parse("transparent").unwrap(), rgba(0, 0, 0, 0));
+    assert_eq!(rgba(255, 99, 71, 255).format(Notation::Name).unwrap(), "tomato");
+    assert!(rgba(255, 99, 72, 255).format(Notation::Name).is_none());
+    assert!(parse("reddish").is_none());
+}
+
+#[test]
+fn test_rejects_invalid_colors() {
+    for invalid in ["rgb(999, a, b)", "rgb(300, 0, 0)", "rgb(255 0 0 /)", "rgb(255, 0, 0 / 1)",
+                    "hsl(120, 150%, 50%)", "hwb(1, 2, 3)", "lab(50% 0)", "#GGG", "colour(1 2 3)"] {
+        assert!(parse(invalid).is_none(), "{} should not parse", invalid);
+    }
+}
+
+#[test]
+fn test_conversions() {
+    let orange = parse("#FF8000").unwrap();
+    assert_eq!(orange.format(Notation::Rgb).unwrap(), "rgb(255, 128, 0)");
+    assert_eq!(orange.format(Notation::Hsl).unwrap(), "hsl(30, 100%, 50%)");
+    assert_eq!(orange.format(Notation::Hwb).unwrap(), "hwb(30 0% 0%)");
+
+    let red = rgba(255, 0, 0, 255);
+    assert_eq!(red.format(Notation::Oklch).unwrap(), "oklch(62.8% 0.2577 29.23)");
+    assert_eq!(red.format(Notation::Lab).unwrap(), "lab(54.29% 80.8 69.89)");
+    assert_eq!(rgba(255, 255, 255, 255).format(Notation::Lch).unwrap(), "lch(100% 0 0)");
+
+    let translucent = rgba(0, 128, 0, 128);
+    assert_eq!(translucent.format(Notation::Hex).unwrap(), "#00800080");
+    assert_eq!(translucent.format(Notation::Hwb).unwrap(), "hwb(120 0% 50% / 0.5)");
+
+    // 任意写法都能解析回原色；hsl / hwb 的百分比取整，误差稍大
+    for color in [orange, red, translucent, rgba(18, 52, 86, 255), rgba(250, 250, 240, 255)] {
+        for notation in ALL_NOTATIONS {
+            let text = color.format(notation).unwrap();
+            let back = parse(&text).unwrap_or_else(|| panic!("Failed to parse {}", text));
+            let tolerance = if matches!(notation, Notation::Hsl | Notation::Hwb) { 3 } else { 1 };
+            let close = |a: u8, b: u8| a.abs_diff(b) <= tolerance;
+            assert!(close(back.r, color.r) && close(back.g, color.g) && close(back.b, color.b) && back.a == color.a,
+                    "{:?} -> {} -> {:?}", color, text, back);
+        }
+    }
+}
+
+#[test]
+fn test_wcag_contrast() {
+    let black = parse("black").unwrap();
+    let white = parse("white").unwrap();
+    let report = contrast(black, white);
+    assert_eq!(report.ratio, 21.0);
+    assert!(report.aa && report.aaa);
+
+    let gray = contrast(parse("#777").unwrap(), white);
+    assert_eq!(gray.ratio, 4.48);
+    assert!(!gray.aa && gray.aa_large && !gray.aaa);
+
+    assert_eq!(contrast(parse("rgb(0 0 0 / 0)").unwrap(), white).ratio, 1.0, "Transparent text has no contrast");
+}
+
+#[test]
+fn test_tints_and_shades() {
+    let variants = parse("#000").unwrap().variants(3);
+    assert_eq!(variants.tints, vec!["#BFBFBF", "#808080", "#404040"]);
+    assert_eq!(variants.shades, vec!["#000000", "#000000", "#000000"]);
+    assert_eq!(parse("#FFF").unwrap().variants(1).shades, vec!["#808080"]);
+}
+
+#[test]
+fn test_color_clips_store_canonical_rgba() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let id = storage.add_text("rgb(255 0 0 / 50%)".to_string()).unwrap();
+    assert_eq!(storage.get_recent(1, 0).unwrap()[0].content_type, ClipType::Color);
+    assert_eq!(storage.get_color(id).unwrap(), rgba(255, 0, 0, 128));
+
+    let text = storage.add_text("not a color".to_string()).unwrap();
+    assert!(storage.get_color(text).is_err());
+}
+
+#[test]
+fn test_migration_recolors_existing_rows() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let (invalid, named) = {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        let invalid = storage.add_text("rgb(999, a, b)".to_string()).unwrap();
+        let named = storage.add_text("tomato".to_string()).unwrap();
+        (invalid, named)
+    };
+
+    // 还原为旧解析器的判定结果：rgb(999, a, b) 被当作颜色，颜色名被当作文本
+    {
+        let conn = Connection::open(data_dir.join("clippy.db")).unwrap();
+        conn.execute_batch(&format!(
+            "ALTER TABLE records DROP COLUMN image_phash;
+             ALTER TABLE records DROP COLUMN color_rgba;
+             UPDATE records SET type = 'color', tag = '[\"color\"]' WHERE id = {invalid};
+             UPDATE records SET type = 'text', tag = '[\"text\"]' WHERE id = {named};
+             PRAGMA user_version = 6;"
+        )).unwrap();
+    }
+
+    let storage = Storage::new(&data_dir).unwrap();
+    let recent = storage.get_recent(10, 0).unwrap();
+    let item = |id: i64| recent.iter().find(|item| item.id == id).unwrap();
+    assert_eq!(item(invalid).content_type, ClipType::Text);
+    assert_eq!(item(invalid).tags, vec!["text".to_string()]);
+    assert_eq!(item(named).content_type, ClipType::Color);
+    assert_eq!(storage.get_color(named).unwrap(), rgba(255, 99, 71, 255));
+}
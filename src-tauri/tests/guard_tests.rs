@@ -0,0 +1,115 @@
+/// 捕获大小上限测试
+/// 验证超大图片、文本和 HTML 事件的丢弃、截断与另存，以及上限设置的校验
+
+mod common;
+
+use pastee_lib::clipboard::ClipEvent;
+use pastee_lib::guard::{self, CaptureLimits, Guarded, OversizedContent};
+use pastee_lib::persist::{ClipData, Storage};
+use pastee_lib::setting::Settings;
+use common::{create_test_dir, get_test_data_dir};
+
+fn limits(oversized: OversizedContent) -> CaptureLimits {
+    CaptureLimits { max_image_pixels: 1_000_000, max_text_bytes: 1024, max_html_bytes: 2048, oversized }
+}
+
+#[test]
+fn test_oversized_images_are_dropped() {
+    let limits = limits(OversizedContent::Spill);
+
+    // 声称的尺寸超限时不看像素数据直接丢弃
+    let bomb = ClipEvent::Image { width: 100_000, height: 100_000, rgba_data: vec![0; 16] };
+    assert!(matches!(guard::check(bomb, &limits), Guarded::Drop(_)));
+    let overflow = ClipEvent::Image { width: usize::MAX, height: usize::MAX, rgba_data: Vec::new() };
+    assert!(matches!(guard::check(overflow, &limits), Guarded::Drop(_)));
+
+    let mismatched = ClipEvent::Image { width: 100, height: 100, rgba_data: vec![0; 100] };
+    assert!(matches!(guard::check(mismatched, &limits), Guarded::Drop(_)));
+
+    let fits = ClipEvent::Image { width: 1000, height: 1000, rgba_data: vec![0; 4_000_000] };
+    assert!(matches!(guard::check(fits, &limits), Guarded::Keep(ClipEvent::Image { .. })));
+}
+
+#[test]
+fn test_oversized_text_drop_and_truncate() {
+    let small = ClipEvent::Text("short".to_string());
+    assert!(matches!(guard::check(small, &limits(OversizedContent::Drop)), Guarded::Keep(ClipEvent::Text(_))));
+
+    let huge = "日志".repeat(1000);
+    let dropped = guard::check(ClipEvent::Text(huge.clone()), &limits(OversizedContent::Drop));
+    assert!(matches!(dropped, Guarded::Drop(_)));
+
+    // 截断时不切断多字节字符
+    match guard::check(ClipEvent::Text(huge.clone()), &limits(OversizedContent::Truncate)) {
+        Guarded::Keep(ClipEvent::Text(text)) => {
+            assert!(text.len() <= 1024);
+            assert_eq!(text.len(), 1023);
+            assert!(huge.starts_with(&text));
+        }
+        other => panic!("Expected truncated text, got {:?}", other),
+    }
+
+    let html = format!("<p>{}</p>", "x".repeat(4096));
+    match guard::check(ClipEvent::Html(html), &limits(OversizedContent::Truncate)) {
+        Guarded::Keep(ClipEvent::Html(html)) => assert_eq!(html.len(), 2048),
+        other => panic!("Expected truncated HTML, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_spilled_content_is_stored_in_full() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let limits = limits(OversizedContent::Spill);
+
+    let huge = "line of a large log dump\n".repeat(200);
+    let Guarded::Spill(ClipEvent::Text(full)) = guard::check(ClipEvent::Text(huge.clone()), &limits) else {
+        panic!("Expected spill");
+    };
+    let id = storage.add_spilled_text(&full, limits.max_text_bytes).unwrap();
+    match storage.get_content(id).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, huge.trim()),
+        other => panic!("Expected text, got {:?}", other),
+    }
+    assert_eq!(storage.add_spilled_text(&full, limits.max_text_bytes).unwrap(), id, "Dedupe uses the full content");
+
+    let html = format!("<ul>{}</ul>", "<li>row</li>".repeat(500));
+    let html_id = storage.add_spilled_html(&html, limits.max_html_bytes).unwrap();
+    match storage.get_content(html_id).unwrap() {
+        ClipData::Html { html: body, .. } => assert_eq!(body, html),
+        other => panic!("Expected HTML, got {:?}", other),
+    }
+
+    // 删除记录后完整内容的文件随之清理
+    let blobs = data_dir.join("images").join("blobs").join("text");
+    storage.delete_record(id).unwrap();
+    storage.delete_record(html_id).unwrap();
+    assert_eq!(walk(&blobs), 0);
+}
+
+#[test]
+fn test_limit_settings_validation() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    assert_eq!(storage.settings().capture_limits(), CaptureLimits::default());
+    for invalid in [
+        Settings { max_image_pixels: 100, ..Default::default() },
+        Settings { max_text_bytes: 10, ..Default::default() },
+        Settings { max_html_bytes: 0, ..Default::default() },
+    ] {
+        assert!(storage.update_settings(invalid).is_err());
+    }
+    storage.update_settings(Settings { max_text_bytes: 4096, oversized_content: OversizedContent::Drop, ..Default::default() }).unwrap();
+    assert_eq!(storage.settings().capture_limits().max_text_bytes, 4096);
+}
+
+fn walk(dir: &std::path::Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else { return 0 };
+    entries.flatten().map(|entry| {
+        let path = entry.path();
+        if path.is_dir() { walk(&path) } else { 1 }
+    }).sum()
+}
@@ -0,0 +1,86 @@
+/// 代码高亮测试
+/// 验证语言识别、高亮 HTML 输出和主题设置
+
+mod common;
+
+use pastee_lib::highlight::{detect_language, theme_names, to_html, DEFAULT_THEME};
+use pastee_lib::persist::{ClipData, Storage};
+use pastee_lib::setting::Settings;
+use pastee_lib::transform::apply_with;
+use common::{create_test_dir, get_test_data_dir};
+
+#[test]
+fn test_detect_language() {
+    let cases = [
+        ("fn main() {\n    let x = vec![1, 2];\n    println!(\"{:?}\", x);\n}", "Rust"),
+        ("import os\n\ndef run(path):\n    print(path)\n", "Python"),
+        ("const add = (a, b) => a + b;\nconsole.log(add(1, 2));", "JavaScript"),
+        ("package main\n\nfunc main() {\n\tx := 1\n\tfmt.Println(x)\n}", "Go"),
+        ("SELECT id, name FROM users WHERE id = 1 ORDER BY name;", "SQL"),
+        ("#!/bin/bash\necho \"hi\" | grep h", "Bourne Again Shell (bash)"),
+        ("{\"name\": \"pastee\", \"tags\": [1, 2]}", "JSON"),
+        ("<div class=\"box\"><p>Hi</p></div>", "HTML"),
+    ];
+    for (code, expected) in cases {
+        assert_eq!(detect_language(code), Some(expected), "Failed to detect {}", expected);
+    }
+}
+
+#[test]
+fn test_plain_text_is_not_code() {
+    assert_eq!(detect_language("Let me know where you are from."), None);
+    assert_eq!(detect_language("Select a file from the list, then press OK."), None);
+    assert_eq!(detect_language(""), None);
+}
+
+#[test]
+fn test_highlighted_html_uses_inline_styles() {
+    let html = to_html("let x = 1;", Some("rs"), DEFAULT_THEME).unwrap();
+    assert!(html.starts_with("<pre style=\""), "Output should be a styled <pre>: {}", html);
+    assert!(html.contains("<span style=\"color:"));
+    assert!(!html.contains("class="), "No external CSS classes should be needed");
+
+    assert!(to_html("x", Some("no-such-language"), DEFAULT_THEME).is_err());
+    assert!(to_html("x", None, "no-such-theme").is_err());
+}
+
+#[test]
+fn test_highlight_transform_uses_theme_setting() {
+    let code = ClipData::Text("fn main() {}".to_string());
+    let light = Settings { highlight_theme: "InspiredGitHub".to_string(), ..Default::default() };
+    let dark = Settings { highlight_theme: "base16-ocean.dark".to_string(), ..Default::default() };
+
+    let light_html = match apply_with("highlight", &code, &light).unwrap() {
+        ClipData::Html { text, html } => {
+            assert_eq!(text, "fn main() {}", "Plain-text fallback should be the source code");
+            html
+        }
+        other => panic!("Expected HTML output, got {:?}", other),
+    };
+    let dark_html = match apply_with("highlight", &code, &dark).unwrap() {
+        ClipData::Html { html, .. } => html,
+        other => panic!("Expected HTML output, got {:?}", other),
+    };
+    assert_ne!(light_html, dark_html, "Different themes should produce different styles");
+}
+
+#[test]
+fn test_theme_setting_persists() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+
+    {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        assert_eq!(storage.settings().highlight_theme, DEFAULT_THEME);
+        assert!(theme_names().contains(&"Solarized (dark)"));
+
+        let invalid = Settings { highlight_theme: "Nope".to_string(), ..Default::default() };
+        assert!(storage.update_settings(invalid).is_err());
+
+        let settings = Settings { highlight_theme: "Solarized (dark)".to_string(), ..Default::default() };
+        storage.update_settings(settings).unwrap();
+    }
+
+    let storage = Storage::new(&data_dir).unwrap();
+    assert_eq!(storage.settings().highlight_theme, "Solarized (dark)");
+}
@@ -0,0 +1,109 @@
+/// HTML 解析测试
+/// 验证纯文本预览提取和 HTML 到 Markdown 的转换
+
+use pastee_lib::html::{to_markdown, to_plain_text};
+use pastee_lib::persist::ClipData;
+use pastee_lib::transform::apply_text;
+
+#[test]
+fn test_preview_skips_script_and_style() {
+    let html = "<style>p { color: red; }</style><p>Visible</p><script>alert('x < y')</script>";
+    assert_eq!(to_plain_text(html), "Visible");
+
+    let html = "<html><head><title>Page</title><meta charset=\"utf-8\"></head>\
+                <body><noscript>Enable JS</noscript><template><b>t</b></template>Body</body></html>";
+    assert_eq!(to_plain_text(html), "Body");
+}
+
+#[test]
+fn test_preview_collapses_whitespace() {
+    let html = "<span>Hello</span>\n\n   <b>World</b>\r\n  <i> again </i>";
+    assert_eq!(to_plain_text(html), "Hello World again");
+    assert_eq!(to_plain_text("plain text, no tags"), "plain text, no tags");
+    assert_eq!(to_plain_text(""), "");
+}
+
+#[test]
+fn test_preview_decodes_entities() {
+    assert_eq!(to_plain_text("Tom &amp; Jerry &lt;3 &quot;cheese&quot;"), "Tom & Jerry <3 \"cheese\"");
+    assert_eq!(to_plain_text("&copy; 2026 &#8212; &#x4E2D;&#25991;"), "© 2026 — 中文");
+    assert_eq!(to_plain_text("a&nbsp;b"), "a b");
+}
+
+#[test]
+fn test_preview_ignores_comments_and_cdata() {
+    assert_eq!(to_plain_text("a<!-- hidden <b>tag</b> -->b"), "ab");
+    assert_eq!(to_plain_text("<!--StartFragment--><b>Copied</b><!--EndFragment-->"), "Copied");
+
+    let text = to_plain_text("before<![CDATA[ raw ]]>after");
+    assert!(!text.contains("CDATA"), "CDATA markers should not leak into text: {}", text);
+    assert!(text.starts_with("before") && text.ends_with("after"));
+}
+
+#[test]
+fn test_preview_line_breaks() {
+    assert_eq!(to_plain_text("one<br>two<br/>three"), "one\ntwo\nthree");
+    assert_eq!(to_plain_text("<p>First</p><p>Second</p>"), "First\n\nSecond");
+    assert_eq!(to_plain_text("<div>a</div><div>b</div>"), "a\nb");
+    assert_eq!(to_plain_text("<ul><li>x</li><li>y</li></ul>"), "x\ny");
+    assert_eq!(to_plain_text("<h1>Title</h1>Body"), "Title\n\nBody");
+    assert_eq!(
+        to_plain_text("<table><tr><th>k</th><th>v</th></tr><tr><td>a</td><td>1</td></tr></table>"),
+        "k\tv\na\t1"
+    );
+}
+
+#[test]
+fn test_preview_keeps_preformatted_whitespace() {
+    let html = "<p>Code:</p><pre>fn main() {\n    println!(\"hi\");\n}</pre>";
+    assert_eq!(to_plain_text(html), "Code:\n\nfn main() {\n    println!(\"hi\");\n}");
+}
+
+#[test]
+fn test_markdown_inline() {
+    assert_eq!(to_markdown("<b>bold</b> and <em>italic</em>"), "**bold** and *italic*");
+    assert_eq!(to_markdown("<strong>spaced </strong>word"), "**spaced** word");
+    assert_eq!(to_markdown("Run <code>cargo test</code>"), "Run `cargo test`");
+    assert_eq!(to_markdown("<a href=\"https://example.com\">site</a>"), "[site](https://example.com)");
+    assert_eq!(to_markdown("<img src=\"cat.png\" alt=\"A cat\">"), "![A cat](cat.png)");
+    assert_eq!(to_markdown("line one<br>line two"), "line one\\\nline two");
+    assert_eq!(to_markdown("2 * 3 = 6 &amp; a_b"), "2 \\* 3 = 6 & a\\_b");
+}
+
+#[test]
+fn test_markdown_blocks() {
+    let html = "<h2>Notes</h2><p>First</p><blockquote><p>Quoted</p><p>Twice</p></blockquote><hr>";
+    assert_eq!(to_markdown(html), "## Notes\n\nFirst\n\n> Quoted\n>\n> Twice\n\n---");
+
+    let html = "<pre><code class=\"language-rust\">let x = 1;\n</code></pre>";
+    assert_eq!(to_markdown(html), "```rust\nlet x = 1;\n```");
+}
+
+#[test]
+fn test_markdown_lists() {
+    let html = "<ul><li>one</li><li>two<ul><li>nested</li></ul></li></ul>";
+    assert_eq!(to_markdown(html), "- one\n- two\n  - nested");
+
+    let html = "<ol start=\"3\"><li>c</li><li>d</li></ol>";
+    assert_eq!(to_markdown(html), "3. c\n4. d");
+}
+
+#[test]
+fn test_markdown_table() {
+    let html = "<table><thead><tr><th>Name</th><th>Note</th></tr></thead>\
+                <tbody><tr><td>a</td><td>x | y</td></tr><tr><td>b</td></tr></tbody></table>";
+    assert_eq!(
+        to_markdown(html),
+        "| Name | Note |\n| --- | --- |\n| a | x \\| y |\n| b |  |"
+    );
+}
+
+#[test]
+fn test_markdown_transform() {
+    let html = ClipData::Html { text: "Title".to_string(), html: "<h1>Title</h1>".to_string() };
+    assert_eq!(apply_text("markdown", &html).unwrap(), "# Title");
+
+    // 纯文本记录没有可转换的标记，原样输出
+    let text = ClipData::Text("*already* markdown".to_string());
+    assert_eq!(apply_text("markdown", &text).unwrap(), "*already* markdown");
+}
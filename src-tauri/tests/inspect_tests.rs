@@ -0,0 +1,120 @@
+/// 剪贴内容检查器测试
+/// 每个检测器分别验证匹配和不匹配的情况
+
+use pastee_lib::inspect::{detectors, inspect, run, Inspection};
+
+const JWT: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+                   eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+                   SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+
+fn value<'a>(inspection: &'a Inspection, name: &str) -> &'a str {
+    inspection.fields.iter()
+        .find(|f| f.name == name)
+        .map(|f| f.value.as_str())
+        .unwrap_or_else(|| panic!("Missing field {} in {:?}", name, inspection))
+}
+
+#[test]
+fn test_registry() {
+    let mut ids: Vec<&str> = detectors().iter().map(|d| d.id).collect();
+    let total = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), total, "Detector ids should be unique");
+    assert!(run("nope", "text").is_none());
+}
+
+#[test]
+fn test_epoch_detector() {
+    let seconds = run("epoch", "1700000000").unwrap();
+    assert_eq!(value(&seconds, "Unit"), "seconds");
+    assert_eq!(value(&seconds, "UTC"), "2023-11-14T22:13:20Z");
+
+    let millis = run("epoch", " 1700000000123\n").unwrap();
+    assert_eq!(value(&millis, "Unit"), "milliseconds");
+    assert_eq!(value(&millis, "UTC"), "2023-11-14T22:13:20.123Z");
+
+    assert!(run("epoch", "42").is_none(), "Small numbers are not timestamps");
+    assert!(run("epoch", "99999999999999").is_none());
+    assert!(run("epoch", "17000000x0").is_none());
+}
+
+#[test]
+fn test_jwt_detector() {
+    let jwt = run("jwt", JWT).unwrap();
+    assert!(value(&jwt, "Header").contains("\"alg\": \"HS256\""));
+    assert!(value(&jwt, "Claims").contains("\"name\": \"John Doe\""));
+    assert_eq!(value(&jwt, "Issued at"), "2018-01-18T01:30:22Z");
+    assert_eq!(value(&jwt, "Signature"), "Not verified");
+
+    assert!(run("jwt", &format!("Bearer {}", JWT)).is_some());
+    assert!(run("jwt", "a.b.c").is_none());
+    assert!(run("jwt", "www.example.com").is_none());
+}
+
+#[test]
+fn test_url_detector() {
+    let url = run("url", "https://user@example.com:8443/a/b?q=rust%20lang&page=2#top").unwrap();
+    assert_eq!(value(&url, "Scheme"), "https");
+    assert_eq!(value(&url, "Host"), "example.com");
+    assert_eq!(value(&url, "Port"), "8443");
+    assert_eq!(value(&url, "User"), "user");
+    assert_eq!(value(&url, "Path"), "/a/b");
+    assert_eq!(value(&url, "Query: q"), "rust lang");
+    assert_eq!(value(&url, "Query: page"), "2");
+    assert_eq!(value(&url, "Fragment"), "top");
+
+    assert!(run("url", "not a url").is_none());
+    assert!(run("url", "mailto:someone@example.com").is_none(), "URLs without a host are skipped");
+}
+
+#[test]
+fn test_hex_detector() {
+    let hex = run("hex", "48656c6c6f").unwrap();
+    assert_eq!(value(&hex, "ASCII"), "Hello");
+    assert_eq!(value(&hex, "Bytes"), "5");
+
+    let prefixed = run("hex", "0xff").unwrap();
+    assert_eq!(value(&prefixed, "Decimal"), "255");
+
+    let separated = run("hex", "de:ad:be:ef").unwrap();
+    assert_eq!(value(&separated, "Decimal"), "3735928559");
+
+    assert!(run("hex", "12345678").is_none(), "Plain digits are left to other detectors");
+    assert!(run("hex", "abc").is_none(), "Odd-length strings are not byte strings");
+    assert!(run("hex", "hello").is_none());
+}
+
+#[test]
+fn test_base64_detector() {
+    let standard = run("base64", "aGVsbG8gd29ybGQ=").unwrap();
+    assert_eq!(value(&standard, "Alphabet"), "Base64");
+    assert_eq!(value(&standard, "Text"), "hello world");
+
+    let url_safe = run("base64", "Pz8_Pz8-").unwrap();
+    assert_eq!(value(&url_safe, "Alphabet"), "Base64url");
+    assert_eq!(value(&url_safe, "Text"), "?????>");
+
+    assert!(run("base64", "password").is_none(), "Binary results are not reported");
+    assert!(run("base64", "hello world").is_none());
+    assert!(run("base64", "deadbeef").is_none(), "Hex strings are left to the hex detector");
+}
+
+#[test]
+fn test_digest_detector() {
+    let digest = run("digest", "abc").unwrap();
+    assert_eq!(value(&digest, "SHA-256"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    assert_eq!(value(&digest, "MD5"), "900150983cd24fb0d6963f7d28e17f72");
+    assert_eq!(value(&digest, "BLAKE3"), "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+    assert!(run("digest", "").is_none());
+}
+
+#[test]
+fn test_inspect_runs_all_matching_detectors() {
+    let results = inspect("1700000000");
+    let ids: Vec<&str> = results.iter().map(|r| r.detector).collect();
+    assert_eq!(ids, vec!["epoch", "digest"]);
+
+    let ids: Vec<&str> = inspect(JWT).iter().map(|r| r.detector).collect();
+    assert_eq!(ids, vec!["jwt", "digest"]);
+}
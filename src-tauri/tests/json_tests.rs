@@ -0,0 +1,114 @@
+/// JSON 检查器测试
+/// 验证校验错误位置、格式化以及 JSONPath / jq 子集查询
+
+mod common;
+
+use pastee_lib::json::{format_matches, looks_like_json, minify, pretty, query, validate};
+use pastee_lib::persist::Storage;
+use serde_json::{json, Value};
+use common::{create_test_dir, get_test_data_dir};
+
+fn sample() -> Value {
+    json!({
+        "store": {
+            "book": [
+                { "title": "Dune", "author": "Herbert", "price": 9 },
+                { "title": "Emma", "author": "Austen", "price": 5 },
+                { "title": "Ulysses", "author": "Joyce", "price": 12 }
+            ],
+            "owner name": "Ada"
+        },
+        "tags": ["a", "b"]
+    })
+}
+
+#[test]
+fn test_validate_reports_position() {
+    let report = validate("{\"a\": 1}");
+    assert!(report.valid);
+    assert_eq!(report.root, Some("object"));
+    assert!(report.error.is_none());
+
+    let text = "{\n  \"a\": 1,\n  \"b\": x\n}";
+    let report = validate(text);
+    assert!(!report.valid);
+    let error = report.error.unwrap();
+    assert_eq!(error.line, 3);
+    assert_eq!(error.column, 8);
+    assert_eq!(&text[error.offset..error.offset + 1], "x", "Offset should point at the bad token");
+    assert!(!error.message.contains("line"), "Position should not be repeated in message: {}", error.message);
+
+    let error = validate("[1, 2").error.unwrap();
+    assert_eq!(error.line, 1);
+    assert!(error.message.contains("EOF"));
+}
+
+#[test]
+fn test_detection() {
+    assert!(looks_like_json("{\"a\": [1, 2]}"));
+    assert!(looks_like_json("  [1, 2, 3]\n"));
+    assert!(!looks_like_json("42"), "Scalars are not tagged as JSON");
+    assert!(!looks_like_json("\"just a string\""));
+    assert!(!looks_like_json("{not json}"));
+}
+
+#[test]
+fn test_pretty_and_minify() {
+    assert_eq!(minify(" { \"b\": 1, \"a\": [true, null] } ").unwrap(), r#"{"b":1,"a":[true,null]}"#);
+    assert_eq!(pretty(r#"{"a":[1]}"#).unwrap(), "{\n  \"a\": [\n    1\n  ]\n}");
+
+    let err = pretty("{\"a\": }").unwrap_err().to_string();
+    assert!(err.contains("line 1 column 7"), "Error should carry position: {}", err);
+}
+
+#[test]
+fn test_jsonpath_queries() {
+    let data = sample();
+    assert_eq!(query(&data, "$.store.book[0].title").unwrap(), vec![json!("Dune")]);
+    assert_eq!(query(&data, "$.store.book[-1].author").unwrap(), vec![json!("Joyce")]);
+    assert_eq!(query(&data, "$.store.book[*].price").unwrap(), vec![json!(9), json!(5), json!(12)]);
+    assert_eq!(query(&data, "$..author").unwrap(), vec![json!("Herbert"), json!("Austen"), json!("Joyce")]);
+    assert_eq!(query(&data, "$.store.book[1:].title").unwrap(), vec![json!("Emma"), json!("Ulysses")]);
+    assert_eq!(query(&data, "$.store['owner name']").unwrap(), vec![json!("Ada")]);
+    assert_eq!(query(&data, "$").unwrap(), vec![data.clone()]);
+    assert!(query(&data, "$.missing.key").unwrap().is_empty());
+}
+
+#[test]
+fn test_jq_queries() {
+    let data = sample();
+    assert_eq!(query(&data, ".tags[0]").unwrap(), vec![json!("a")]);
+    assert_eq!(query(&data, ".tags[]").unwrap(), vec![json!("a"), json!("b")]);
+    assert_eq!(query(&data, ".store.book[].title").unwrap().len(), 3);
+    assert_eq!(query(&data, ".store.\"owner name\"").unwrap(), vec![json!("Ada")]);
+    assert_eq!(query(&data, ".store | .book[1] | .author").unwrap(), vec![json!("Austen")]);
+    assert_eq!(query(&data, ".nope?").unwrap(), Vec::<Value>::new());
+    assert_eq!(query(&data, ".").unwrap(), vec![data.clone()]);
+}
+
+#[test]
+fn test_invalid_expressions() {
+    let data = sample();
+    assert!(query(&data, "store.book").is_err(), "Expressions need a '$' or '.' prefix");
+    assert!(query(&data, "$.tags[0").is_err());
+    assert!(query(&data, "$.tags[x]").is_err());
+    assert!(query(&data, ".store['unterminated]").is_err());
+}
+
+#[test]
+fn test_format_matches_for_paste() {
+    assert_eq!(format_matches(&[json!("plain")]), "plain", "Strings paste without quotes");
+    assert_eq!(format_matches(&[json!(1), json!("x")]), "1\nx");
+    assert_eq!(format_matches(&[json!({"a": 1})]), "{\n  \"a\": 1\n}");
+}
+
+#[test]
+fn test_json_clips_are_tagged() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("{\"id\": 1, \"name\": \"x\"}".to_string()).unwrap();
+    let recent = storage.get_recent(1, 0).unwrap();
+    assert_eq!(recent[0].tags, vec!["text".to_string(), "json".to_string()]);
+}
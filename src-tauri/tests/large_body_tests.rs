@@ -0,0 +1,189 @@
+/// 大文本正文另存测试
+/// 验证超过阈值的文本和 HTML 正文另存为文件、列表只读取预览、搜索文本上限、读取详情时还原完整内容以及旧记录的迁移
+
+mod common;
+
+use pastee_lib::guard;
+use pastee_lib::persist::{ClipData, Storage};
+use pastee_lib::setting::Settings;
+use rusqlite::{params, Connection};
+use serde_json::json;
+use std::path::Path;
+use common::{create_test_dir, get_test_data_dir};
+
+/// 直接读取记录中保存的正文长度
+fn stored_lengths(data_dir: &Path, id: i64) -> (usize, usize) {
+    let conn = Connection::open(data_dir.join("clippy.db")).unwrap();
+    conn.query_row(
+        "SELECT length(CAST(content_text AS BLOB)), coalesce(length(CAST(content_html AS BLOB)), 0) FROM records WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize)),
+    ).unwrap()
+}
+
+/// 记录是否另存了正文
+fn has_body(data_dir: &Path, id: i64) -> bool {
+    let conn = Connection::open(data_dir.join("clippy.db")).unwrap();
+    conn.query_row(
+        "SELECT COUNT(*) FROM blob_refs WHERE record_id = ?1 AND role = 'body'",
+        params![id],
+        |row| row.get::<_, i64>(0),
+    ).unwrap() == 1
+}
+
+fn log_dump(lines: usize) -> String {
+    (0..lines).map(|i| format!("2026-10-18 12:00:{:02} INFO request {} finished", i % 60, i)).collect::<Vec<_>>().join("\n")
+}
+
+#[test]
+fn test_large_text_moves_to_blob() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let small = storage.add_text("short note".to_string()).unwrap();
+    assert_eq!(stored_lengths(&data_dir, small).0, "short note".len());
+
+    let log = log_dump(5000);
+    assert!(log.len() > guard::DEFAULT_LARGE_BODY_BYTES);
+    let id = storage.add_text(log.clone()).unwrap();
+    assert!(has_body(&data_dir, id));
+    assert!(stored_lengths(&data_dir, id).0 <= guard::DEFAULT_SEARCH_TEXT_BYTES, "Only the search text stays in the record");
+    let (namespace, size): (String, i64) = Connection::open(data_dir.join("clippy.db")).unwrap().query_row(
+        "SELECT b.namespace, b.size FROM blob_refs r JOIN blobs b ON b.id = r.blob_id WHERE r.record_id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).unwrap();
+    assert_eq!(namespace, "text/plain+zst");
+    assert!((size as usize) < log.len() / 4, "Bodies are stored compressed");
+
+    let item = storage.get_recent(10, 0).unwrap().into_iter().find(|item| item.id == id).unwrap();
+    assert!(item.preview.starts_with("2026-10-18 12:00:00 INFO request 0"));
+    assert!(item.preview.chars().count() <= 100);
+    match storage.get_content(id).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, log),
+        other => panic!("Expected text, got {:?}", other),
+    }
+
+    // 搜索文本上限以内的内容可搜索，之后的搜不到；同一内容再次复制时去重
+    let hits = storage.search_page("request 1000 finished", 10, 0).unwrap();
+    assert_eq!(hits.total, 1);
+    assert!(hits.hits[0].snippet.contains("request 1000 finished"));
+    assert!(storage.search("request 4999 finished").unwrap().is_empty());
+    assert_eq!(storage.add_text(log).unwrap(), id);
+}
+
+#[test]
+fn test_search_text_limit() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    storage.update_settings(Settings { search_text_bytes: guard::BODY_PREVIEW_BYTES, ..Default::default() }).unwrap();
+
+    // 调小搜索文本上限后记录只保留开头一段，之后的内容搜不到，但详情仍是完整内容
+    let log = log_dump(5000);
+    let id = storage.add_text(log.clone()).unwrap();
+    assert!(stored_lengths(&data_dir, id).0 <= guard::BODY_PREVIEW_BYTES);
+    assert_eq!(storage.search("request 1 finished").unwrap().len(), 1);
+    assert!(storage.search("request 1000 finished").unwrap().is_empty());
+    match storage.get_content(id).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, log),
+        other => panic!("Expected text, got {:?}", other),
+    }
+
+    // 调大到能放下完整正文时全部可搜索，也不再另存一份
+    storage.update_settings(Settings { search_text_bytes: 1024 * 1024, ..Default::default() }).unwrap();
+    let longer = log_dump(6000);
+    let id = storage.add_text(longer.clone()).unwrap();
+    assert_eq!(stored_lengths(&data_dir, id).0, longer.len());
+    assert!(!has_body(&data_dir, id));
+    assert_eq!(storage.search("request 5999 finished").unwrap().len(), 1);
+    match storage.get_content(id).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, longer),
+        other => panic!("Expected text, got {:?}", other),
+    }
+
+    assert!(storage.update_settings(Settings { search_text_bytes: 100, ..Default::default() }).is_err());
+}
+
+#[test]
+fn test_large_html_moves_to_blob() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let rows = (0..3000).map(|i| format!("<tr><td>row {}</td></tr>", i)).collect::<String>();
+    let html = format!("<table>{}</table>", rows);
+    let text = (0..3000).map(|i| format!("row {}", i)).collect::<Vec<_>>().join("\n");
+    let id = storage.add_html(text.clone(), html.clone()).unwrap();
+
+    let (text_len, html_len) = stored_lengths(&data_dir, id);
+    assert_eq!(text_len, text.len());
+    assert!(html_len <= guard::BODY_PREVIEW_BYTES);
+    assert_eq!(storage.search("row 2999").unwrap().len(), 1);
+    match storage.get_content(id).unwrap() {
+        ClipData::Html { html: body, text: plain } => {
+            assert_eq!(body, html);
+            assert!(plain.contains("row 2999"));
+        }
+        other => panic!("Expected HTML, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_large_bodies_are_classified_in_full() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    // 截断后的预览既不是合法 JSON，也会少算表格行数
+    let items = (0..4000).map(|i| format!("{{\"id\": {}, \"name\": \"item {}\"}}", i, i)).collect::<Vec<_>>();
+    let json = format!("[{}]", items.join(", "));
+    let tsv = (0..5000).map(|i| format!("row {}\t{}\t{}", i, i * 2, i * 3)).collect::<Vec<_>>().join("\n");
+    assert!(json.len() > guard::DEFAULT_LARGE_BODY_BYTES && tsv.len() > guard::DEFAULT_LARGE_BODY_BYTES);
+
+    let json_id = storage.add_text(json).unwrap();
+    let tsv_id = storage.add_text(tsv).unwrap();
+    let check = |storage: &Storage| {
+        let items = storage.get_recent(10, 0).unwrap();
+        let tags = |id: i64| items.iter().find(|item| item.id == id).unwrap().tags.clone();
+        assert_eq!(tags(json_id), vec!["text", "json"]);
+        assert_eq!(tags(tsv_id), vec!["text", "table"]);
+        assert_eq!(storage.get_metadata(tsv_id).unwrap()["table"], json!({ "format": "tsv", "rows": 5000, "columns": 3 }));
+    };
+    check(&storage);
+
+    // 重新分类时读取另存的完整正文
+    Connection::open(data_dir.join("clippy.db")).unwrap()
+        .execute_batch("UPDATE records SET tag = '[]', metadata = NULL").unwrap();
+    assert_eq!(storage.reclassify_all().unwrap(), 2);
+    check(&storage);
+}
+
+#[test]
+fn test_existing_large_bodies_are_moved_on_startup() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let log = log_dump(3000);
+    let id = {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        storage.update_settings(Settings { large_body_bytes: 1024 * 1024, ..Default::default() }).unwrap();
+        let id = storage.add_text(log.clone()).unwrap();
+        assert_eq!(stored_lengths(&data_dir, id).0, log.len(), "Below the threshold the body stays inline");
+        storage.update_settings(Settings::default()).unwrap();
+        id
+    };
+
+    let mut storage = Storage::new(&data_dir).unwrap();
+    assert!(has_body(&data_dir, id));
+    assert!(stored_lengths(&data_dir, id).0 <= guard::DEFAULT_SEARCH_TEXT_BYTES, "Moving a body trims it to the search text");
+    assert_eq!(storage.search("request 1000 finished").unwrap().len(), 1);
+    assert!(storage.search("request 2999 finished").unwrap().is_empty());
+    match storage.get_content(id).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, log),
+        other => panic!("Expected text, got {:?}", other),
+    }
+    assert_eq!(storage.move_large_bodies().unwrap(), 0, "Moved records are skipped");
+
+    assert!(storage.update_settings(Settings { large_body_bytes: 100, ..Default::default() }).is_err());
+}
@@ -0,0 +1,67 @@
+/// Markdown 识别与渲染测试
+/// 验证 Markdown 文本检测、HTML 渲染和富文本粘贴转换
+
+mod common;
+
+use pastee_lib::markdown::{looks_like_markdown, to_html};
+use pastee_lib::persist::{ClipData, Storage};
+use pastee_lib::transform::{apply, find, Output};
+use common::{create_test_dir, get_test_data_dir};
+
+#[test]
+fn test_detects_markdown() {
+    assert!(looks_like_markdown("# Release notes\n\n- Faster search\n- New boards"));
+    assert!(looks_like_markdown("See [the docs](https://example.com) for **details**."));
+    assert!(looks_like_markdown("```rust\nfn main() {}\n```"));
+    assert!(looks_like_markdown("| a | b |\n|---|---|\n| 1 | 2 |"));
+    assert!(looks_like_markdown("> quoted\n> again\n\nand `code`"));
+}
+
+#[test]
+fn test_ignores_plain_text() {
+    assert!(!looks_like_markdown("Just a normal sentence."));
+    assert!(!looks_like_markdown("- a single dash line"));
+    assert!(!looks_like_markdown("Price: 3 * 4 = 12"));
+    assert!(!looks_like_markdown("https://example.com/path_with_underscores"));
+    assert!(!looks_like_markdown(""));
+}
+
+#[test]
+fn test_renders_html() {
+    let html = to_html("# Title\n\nSome **bold** and ~~old~~ text.\n\n- [x] done\n- item");
+    assert!(html.contains("<h1>Title</h1>"));
+    assert!(html.contains("<strong>bold</strong>"));
+    assert!(html.contains("<del>old</del>"));
+    assert!(html.contains("<li><input disabled=\"\" type=\"checkbox\" checked=\"\"/>"));
+
+    let html = to_html("| a | b |\n|---|---|\n| 1 | 2 |");
+    assert!(html.contains("<table>") && html.contains("<td>2</td>"));
+}
+
+#[test]
+fn test_rich_paste_transform() {
+    assert_eq!(find("markdown_html").unwrap().output, Output::Html);
+
+    let source = "Hello **world**";
+    match apply("markdown_html", &ClipData::Text(source.to_string())).unwrap() {
+        ClipData::Html { text, html } => {
+            assert_eq!(text, source, "Plain-text fallback should be the original Markdown");
+            assert_eq!(html, "<p>Hello <strong>world</strong></p>\n");
+        }
+        other => panic!("Expected HTML output, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_markdown_clips_are_tagged() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("## Todo\n\n- write tests\n- ship".to_string()).unwrap();
+    storage.add_text("plain note".to_string()).unwrap();
+
+    let recent = storage.get_recent(10, 0).unwrap();
+    assert_eq!(recent[0].tags, vec!["text".to_string()]);
+    assert_eq!(recent[1].tags, vec!["text".to_string(), "markdown".to_string()]);
+}
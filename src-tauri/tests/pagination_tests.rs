@@ -0,0 +1,108 @@
+/// 游标分页测试
+/// 验证 get_page 翻页稳定、无重复且 has_more 正确
+
+mod common;
+
+use pastee_lib::persist::{PageCursor, Storage};
+use common::{create_test_dir, get_test_data_dir};
+
+#[test]
+fn test_cursor_pages_cover_all_records() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    for i in 0..23 {
+        storage.add_text(format!("Item {}", i)).unwrap();
+    }
+
+    let mut ids = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut pages = 0;
+    loop {
+        let page = storage.get_page(10, cursor.as_deref()).unwrap();
+        pages += 1;
+        ids.extend(page.items.iter().map(|item| item.id));
+        if !page.has_more {
+            assert!(page.next_cursor.is_none(), "Last page should not return a cursor");
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+
+    assert_eq!(pages, 3);
+    assert_eq!(ids.len(), 23);
+    let mut unique = ids.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), 23, "Pages should not overlap");
+}
+
+#[test]
+fn test_new_clip_does_not_shift_pages() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    for i in 0..10 {
+        storage.add_text(format!("Item {}", i)).unwrap();
+    }
+
+    let page1 = storage.get_page(5, None).unwrap();
+    let expected = storage.get_page(5, page1.next_cursor.as_deref()).unwrap();
+
+    // 翻页期间到达一条新记录
+    storage.add_text("Arrived later".to_string()).unwrap();
+
+    let page2 = storage.get_page(5, page1.next_cursor.as_deref()).unwrap();
+    let expected_ids: Vec<i64> = expected.items.iter().map(|item| item.id).collect();
+    let page2_ids: Vec<i64> = page2.items.iter().map(|item| item.id).collect();
+    assert_eq!(page2_ids, expected_ids, "Second page should be unaffected by new clips");
+}
+
+#[test]
+fn test_pinned_items_come_first() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let pinned_id = storage.add_text("Pinned".to_string()).unwrap();
+    for i in 0..5 {
+        storage.add_text(format!("Item {}", i)).unwrap();
+    }
+    storage.toggle_pin(pinned_id).unwrap();
+
+    let page1 = storage.get_page(2, None).unwrap();
+    assert_eq!(page1.items[0].id, pinned_id, "Pinned clip should lead the first page");
+
+    let page2 = storage.get_page(10, page1.next_cursor.as_deref()).unwrap();
+    assert_eq!(page2.items.len(), 4);
+    assert!(!page2.has_more);
+    assert!(page2.items.iter().all(|item| !item.is_pinned));
+}
+
+#[test]
+fn test_exact_page_boundary() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    for i in 0..5 {
+        storage.add_text(format!("Item {}", i)).unwrap();
+    }
+
+    let page = storage.get_page(5, None).unwrap();
+    assert_eq!(page.items.len(), 5);
+    assert!(!page.has_more, "Exactly one full page should not report more");
+}
+
+#[test]
+fn test_cursor_roundtrip_and_invalid_cursor() {
+    let cursor = PageCursor { is_pinned: true, created_at: 1_700_000_000_000_000, id: 42 };
+    assert_eq!(PageCursor::decode(&cursor.encode()).unwrap(), cursor);
+
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+    assert!(storage.get_page(10, Some("not-a-cursor")).is_err());
+}
@@ -0,0 +1,87 @@
+/// 图片主色提取测试
+/// 验证中位切分的结果、透明像素的处理以及调色板的保存
+
+mod common;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use pastee_lib::palette::{extract, DEFAULT_COLORS};
+use pastee_lib::persist::{ClipType, Storage};
+use common::{create_test_dir, get_test_data_dir};
+
+/// 左侧 3/4 为红色、右侧 1/4 为蓝色，可选地在底部加一行透明像素
+fn red_and_blue(width: u32, height: u32, transparent_row: bool) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        if transparent_row && y == height - 1 {
+            Rgba([0, 255, 0, 0])
+        } else if x < width * 3 / 4 {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 255, 255])
+        }
+    })
+}
+
+fn summary(image: RgbaImage, count: usize) -> Vec<(String, f64)> {
+    extract(&DynamicImage::ImageRgba8(image), count).into_iter().map(|s| (s.color, s.share)).collect()
+}
+
+#[test]
+fn test_extract_orders_by_share() {
+    let palette = summary(red_and_blue(40, 10, false), DEFAULT_COLORS);
+    assert_eq!(palette, vec![("#FF0000".to_string(), 0.75), ("#0000FF".to_string(), 0.25)]);
+
+    // 大图先缩小再统计，颜色保持原值
+    let palette = summary(red_and_blue(1000, 400, false), DEFAULT_COLORS);
+    assert_eq!(palette[0], ("#FF0000".to_string(), 0.75));
+}
+
+#[test]
+fn test_extract_limits_and_transparency() {
+    let palette = summary(red_and_blue(40, 10, true), DEFAULT_COLORS);
+    assert_eq!(palette.len(), 2, "Transparent pixels are ignored");
+
+    let palette = summary(red_and_blue(40, 10, false), 1);
+    assert_eq!(palette, vec![("#BF0040".to_string(), 1.0)], "A single swatch is the average color");
+
+    assert!(summary(RgbaImage::new(8, 8), DEFAULT_COLORS).is_empty());
+}
+
+#[test]
+fn test_image_clips_store_palette() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let image = red_and_blue(40, 20, false);
+    let (id, _) = storage.add_image(40, 20, image.into_raw()).unwrap();
+    assert_eq!(storage.get_metadata(id).unwrap()["palette"][0]["color"], "#FF0000");
+
+    let color = storage.save_palette_color(id, 1).unwrap();
+    let recent = storage.get_recent(1, 0).unwrap();
+    assert_eq!(recent[0].id, color);
+    assert_eq!(recent[0].content_type, ClipType::Color);
+    assert!(storage.save_palette_color(id, 5).is_err());
+
+    let text = storage.add_text("plain".to_string()).unwrap();
+    assert!(storage.get_palette(text).is_err(), "Only image clips have palettes");
+}
+
+#[test]
+fn test_palette_extracted_for_older_images() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let id = {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        storage.add_image(40, 20, red_and_blue(40, 20, false).into_raw()).unwrap().0
+    };
+
+    // 模拟提取功能之前保存的图片：没有调色板元数据
+    {
+        let conn = rusqlite::Connection::open(data_dir.join("clippy.db")).unwrap();
+        conn.execute("UPDATE records SET metadata = NULL", []).unwrap();
+    }
+
+    let storage = Storage::new(&data_dir).unwrap();
+    assert_eq!(storage.get_palette(id).unwrap().len(), 2);
+    assert_eq!(storage.get_metadata(id).unwrap()["palette"][1]["color"], "#0000FF", "Palette is written back");
+}
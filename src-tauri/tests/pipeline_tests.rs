@@ -0,0 +1,70 @@
+/// 图片保存流水线测试
+/// 验证锁外准备的图片写入、并发捕获同一图片时的去重以及 worker 池的处理结果
+
+mod common;
+
+use image::{Rgba, RgbaImage};
+use pastee_lib::persist::{PreparedImage, Storage};
+use pastee_lib::pipeline::ImagePipeline;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use common::{create_test_dir, get_test_data_dir};
+
+fn pixels(seed: u8) -> Vec<u8> {
+    RgbaImage::from_fn(24, 16, |x, y| Rgba([seed, x as u8 * 8, y as u8 * 12, 255])).into_raw()
+}
+
+fn prepare(storage: &Storage, rgba: Vec<u8>) -> PreparedImage {
+    let hash = Storage::image_hash(&rgba);
+    PreparedImage::prepare(24, 16, rgba, hash, storage.image_options(), &storage.blob_store()).unwrap()
+}
+
+#[test]
+fn test_prepared_images_insert_once() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    // 两次捕获同一张图片，都在锁外准备完成后才写入
+    let first = prepare(&storage, pixels(1));
+    let second = prepare(&storage, pixels(1));
+    let (id, thumbnail) = storage.insert_image(first).unwrap();
+    assert!(!thumbnail.is_empty());
+    assert_eq!(storage.insert_image(second).unwrap().0, id, "The later copy resolves to the saved record");
+    assert_eq!(storage.get_recent(10, 0).unwrap().len(), 1);
+
+    let wrong_size = PreparedImage::prepare(24, 16, vec![0; 10], "x".to_string(), storage.image_options(), &storage.blob_store());
+    assert!(wrong_size.is_err());
+}
+
+#[test]
+fn test_pipeline_processes_jobs() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Arc::new(Mutex::new(Storage::new(&data_dir).unwrap()));
+    let pipeline = ImagePipeline::new(Arc::clone(&storage), 2, 1);
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+    for (index, seed) in [1, 2, 1, 3].into_iter().enumerate() {
+        let tx = tx.clone();
+        pipeline.submit(24, 16, pixels(seed), Box::new(move |result| {
+            tx.send((index, result.map(|(id, _)| id).map_err(|e| e.to_string()))).unwrap();
+        })).unwrap();
+    }
+    pipeline.submit(2, 2, vec![0; 3], Box::new(move |result| {
+        tx.send((4, result.map(|(id, _)| id).map_err(|e| e.to_string()))).unwrap();
+    })).unwrap();
+
+    let mut results = vec![None; 5];
+    for _ in 0..5 {
+        let (index, result) = rx.recv_timeout(Duration::from_secs(30)).unwrap();
+        results[index] = Some(result);
+    }
+    let id = |index: usize| results[index].clone().unwrap().unwrap();
+    assert_eq!(id(0), id(2), "Duplicate captures share one record");
+    assert_ne!(id(0), id(1));
+    assert_ne!(id(1), id(3));
+    assert!(results[4].clone().unwrap().is_err(), "Invalid data is reported through the callback");
+
+    assert_eq!(storage.lock().unwrap().get_recent(10, 0).unwrap().len(), 3);
+}
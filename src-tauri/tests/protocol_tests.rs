@@ -0,0 +1,104 @@
+/// pastee:// 协议测试
+/// 验证按记录 ID 提供原图和缩略图、响应头、缓存校验、即时缩放以及非法请求的处理
+
+mod common;
+
+use http::{header, Method, Request, StatusCode};
+use image::{Rgba, RgbaImage};
+use pastee_lib::persist::Storage;
+use pastee_lib::protocol;
+use std::sync::Mutex;
+use common::{create_test_dir, get_test_data_dir};
+
+fn get(storage: &Mutex<Storage>, uri: &str) -> http::Response<Vec<u8>> {
+    protocol::handle(storage, &Request::get(uri).body(Vec::new()).unwrap())
+}
+
+fn add_image(storage: &mut Storage, width: u32, height: u32) -> i64 {
+    let image = RgbaImage::from_fn(width, height, |x, y| Rgba([(x % 256) as u8, (y % 256) as u8, 30, 255]));
+    storage.add_image(width as usize, height as usize, image.into_raw()).unwrap().0
+}
+
+#[test]
+fn test_serves_images_by_id() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let id = add_image(&mut storage, 300, 200);
+    let storage = Mutex::new(storage);
+
+    let original = get(&storage, &format!("pastee://localhost/image/{}", id));
+    assert_eq!(original.status(), StatusCode::OK);
+    assert_eq!(original.headers()[header::CONTENT_TYPE], "image/png");
+    assert!(original.headers().contains_key(header::ETAG));
+    assert!(original.headers().contains_key(header::CACHE_CONTROL));
+    assert_eq!(image::load_from_memory(original.body()).unwrap().width(), 300);
+
+    // Windows 下的 URL 形式，convertFileSrc 会把整个路径百分号编码
+    let thumbnail = get(&storage, &format!("http://pastee.localhost/thumbnail%2F{}", id));
+    assert_eq!(thumbnail.status(), StatusCode::OK);
+    assert_eq!(thumbnail.headers()[header::CONTENT_TYPE], "image/webp");
+
+    let sized = get(&storage, &format!("pastee://localhost/thumbnail%2F{}%2Flist%402x", id));
+    assert_eq!(sized.status(), StatusCode::OK);
+    let img = image::load_from_memory(sized.body()).unwrap();
+    assert_eq!((img.width(), img.height()), (300, 200), "Thumbnails never upscale");
+
+    // get_image_url / get_thumbnail_url 返回的路径
+    for path in [protocol::image_path(id, false), protocol::image_path(id, true), protocol::thumbnail_path(id, "list", 1)] {
+        assert_eq!(get(&storage, &format!("pastee://localhost/{}", path)).status(), StatusCode::OK, "{}", path);
+    }
+
+    let resized = get(&storage, &format!("pastee://localhost/image/{}?w=150", id));
+    assert_eq!(resized.status(), StatusCode::OK);
+    let img = image::load_from_memory(resized.body()).unwrap();
+    assert_eq!((img.width(), img.height()), (150, 100));
+    assert_ne!(resized.headers()[header::ETAG], original.headers()[header::ETAG]);
+}
+
+#[test]
+fn test_conditional_and_head_requests() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let id = add_image(&mut storage, 64, 64);
+    let storage = Mutex::new(storage);
+    let uri = format!("pastee://localhost/image/{}", id);
+
+    let etag = get(&storage, &uri).headers()[header::ETAG].clone();
+    let request = Request::get(&uri).header(header::IF_NONE_MATCH, etag).body(Vec::new()).unwrap();
+    let cached = protocol::handle(&storage, &request);
+    assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+    assert!(cached.body().is_empty());
+
+    let head = protocol::handle(&storage, &Request::head(&uri).body(Vec::new()).unwrap());
+    assert_eq!(head.status(), StatusCode::OK);
+    assert!(head.body().is_empty());
+    assert_ne!(head.headers()[header::CONTENT_LENGTH], "0");
+}
+
+#[test]
+fn test_rejects_invalid_requests() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let id = add_image(&mut storage, 32, 32);
+    let text = storage.add_text("plain text".to_string()).unwrap();
+    let storage = Mutex::new(storage);
+
+    let post = Request::builder().method(Method::POST).uri(format!("pastee://localhost/image/{}", id)).body(Vec::new()).unwrap();
+    assert_eq!(protocol::handle(&storage, &post).status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    for (uri, status) in [
+        ("pastee://localhost/etc/passwd".to_string(), StatusCode::BAD_REQUEST),
+        ("pastee://localhost/image/..%2F..%2Fsecret".to_string(), StatusCode::BAD_REQUEST),
+        (format!("pastee://localhost/image%+F{}", id), StatusCode::BAD_REQUEST),
+        (format!("pastee://localhost/image/{}?w=0", id), StatusCode::BAD_REQUEST),
+        (format!("pastee://localhost/image/{}?w=99999", id), StatusCode::BAD_REQUEST),
+        (format!("pastee://localhost/image/{}", id + 1000), StatusCode::NOT_FOUND),
+        (format!("pastee://localhost/image/{}", text), StatusCode::NOT_FOUND),
+        (format!("pastee://localhost/thumbnail/{}/huge", id), StatusCode::NOT_FOUND),
+    ] {
+        assert_eq!(get(&storage, &uri).status(), status, "{}", uri);
+    }
+}
@@ -0,0 +1,186 @@
+/// 二维码识别与生成测试
+/// 验证图片中二维码和条形码的识别、内容可搜索并可另存为文本记录、旧图片的补充识别以及从文本生成二维码图片
+
+mod common;
+
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use pastee_lib::persist::{ClipData, PreparedImage, Storage};
+use pastee_lib::qr;
+use rusqlite::Connection;
+use rxing::{BarcodeFormat, MultiFormatWriter, Writer};
+use common::{create_test_dir, get_test_data_dir};
+
+/// 把二维码放在一张较大的彩色截图中间
+fn screenshot_with_qr(text: &str) -> RgbaImage {
+    let code = qr::encode(text).unwrap().into_rgba8();
+    let mut canvas = RgbaImage::from_fn(900, 700, |x, y| Rgba([(x % 200) as u8 + 40, (y % 150) as u8 + 60, 200, 255]));
+    imageops::overlay(&mut canvas, &code, 150, 80);
+    canvas
+}
+
+/// 把条形码放在一张较大的彩色截图中间
+fn screenshot_with_barcode(format: BarcodeFormat, text: &str) -> RgbaImage {
+    let bits = MultiFormatWriter.encode(text, &format, 400, 120).unwrap();
+    let code = RgbaImage::from_fn(bits.getWidth(), bits.getHeight(), |x, y| {
+        if bits.get(x, y) { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) }
+    });
+    let mut canvas = RgbaImage::from_fn(900, 700, |x, y| Rgba([(x % 200) as u8 + 40, (y % 150) as u8 + 60, 200, 255]));
+    imageops::overlay(&mut canvas, &code, 150, 200);
+    canvas
+}
+
+fn add(storage: &mut Storage, image: RgbaImage) -> i64 {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    storage.add_image(width, height, image.into_raw()).unwrap().0
+}
+
+#[test]
+fn test_decode_and_encode() {
+    let url = "https://example.com/share?id=42";
+    let code = qr::encode(url).unwrap();
+    assert!(code.width() >= qr::RENDER_MIN_SIZE);
+    assert_eq!(qr::decode(&code), vec![url.to_string()]);
+    assert_eq!(qr::decode(&DynamicImage::ImageRgba8(screenshot_with_qr(url))), vec![url.to_string()]);
+
+    // 透明底的二维码按白底识别
+    let transparent = RgbaImage::from_fn(code.width(), code.height(), |x, y| {
+        let dark = code.to_luma8().get_pixel(x, y).0[0] < 128;
+        if dark { Rgba([0, 0, 0, 255]) } else { Rgba([0, 0, 0, 0]) }
+    });
+    assert_eq!(qr::decode(&DynamicImage::ImageRgba8(transparent)), vec![url.to_string()]);
+
+    let plain = RgbaImage::from_pixel(300, 200, Rgba([240, 240, 240, 255]));
+    assert!(qr::decode(&DynamicImage::ImageRgba8(plain)).is_empty());
+    assert!(qr::encode(&"x".repeat(10_000)).is_err(), "Text beyond QR capacity is rejected");
+}
+
+#[test]
+fn test_decode_barcodes() {
+    let ean = screenshot_with_barcode(BarcodeFormat::EAN_13, "4006381333931");
+    assert_eq!(qr::decode(&DynamicImage::ImageRgba8(ean)), vec!["4006381333931".to_string()]);
+    let code128 = screenshot_with_barcode(BarcodeFormat::CODE_128, "PASTEE-2026");
+    assert_eq!(qr::decode(&DynamicImage::ImageRgba8(code128)), vec!["PASTEE-2026".to_string()]);
+
+    // 同一张图中的二维码和条形码都能识别，二维码在前
+    let mut both = screenshot_with_barcode(BarcodeFormat::CODE_128, "PASTEE-2026");
+    let code = qr::encode("https://example.com/both").unwrap().into_rgba8();
+    imageops::overlay(&mut both, &imageops::thumbnail(&code, 300, 300), 580, 360);
+    assert_eq!(
+        qr::decode(&DynamicImage::ImageRgba8(both)),
+        vec!["https://example.com/both".to_string(), "PASTEE-2026".to_string()]
+    );
+}
+
+#[test]
+fn test_captured_qr_codes_are_searchable() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let url = "https://example.com/wifi-login";
+    let id = add(&mut storage, screenshot_with_qr(url));
+    assert_eq!(storage.get_qr_codes(id).unwrap(), vec![url.to_string()]);
+    let item = storage.get_recent(1, 0).unwrap().remove(0);
+    assert_eq!(item.tags, vec!["image", "qr"]);
+    assert_eq!(storage.search("wifi-login").unwrap().len(), 1);
+
+    let text_id = storage.save_qr_text(id, 0).unwrap();
+    match storage.get_content(text_id).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, url),
+        other => panic!("Expected text, got {:?}", other),
+    }
+    assert!(storage.save_qr_text(id, 1).is_err());
+
+    let plain = add(&mut storage, RgbaImage::from_pixel(64, 64, Rgba([10, 20, 30, 255])));
+    assert!(storage.get_qr_codes(plain).unwrap().is_empty());
+    assert!(storage.images_to_scan().unwrap().is_empty(), "New images are scanned when saved");
+}
+
+#[test]
+fn test_scan_existing_images() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let url = "https://example.com/older";
+    let id = add(&mut storage, screenshot_with_qr(url));
+
+    // 模拟识别功能上线前保存的图片
+    let conn = Connection::open(data_dir.join("clippy.db")).unwrap();
+    conn.execute_batch(&format!(
+        "UPDATE records SET metadata = json_remove(metadata, '$.qr'), tag = '[\"image\"]',
+                content_text = '[图片] 900x700 PNG' WHERE id = {id}"
+    )).unwrap();
+    assert_eq!(storage.search("example.com/older").unwrap().len(), 0);
+
+    let pending = storage.images_to_scan().unwrap();
+    assert_eq!(pending.len(), 1);
+    let (pending_id, path) = &pending[0];
+    let codes = qr::decode(&image::open(path).unwrap());
+    storage.save_qr_codes(*pending_id, &codes).unwrap();
+
+    assert!(storage.images_to_scan().unwrap().is_empty());
+    assert_eq!(storage.search("example.com/older").unwrap().len(), 1);
+    assert_eq!(storage.get_recent(1, 0).unwrap()[0].tags, vec!["image", "qr"]);
+
+    // 重新识别不会重复追加内容
+    storage.save_qr_codes(id, &codes).unwrap();
+    assert_eq!(storage.get_qr_codes(id).unwrap(), vec![url.to_string()]);
+}
+
+#[test]
+fn test_migration_rescans_images_for_barcodes() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let (barcode_id, qr_id) = {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        let barcode_id = add(&mut storage, screenshot_with_barcode(BarcodeFormat::EAN_13, "4006381333931"));
+        let qr_id = add(&mut storage, screenshot_with_qr("https://example.com/kept"));
+        (barcode_id, qr_id)
+    };
+
+    // 还原为只识别 QR 码时的结果：条形码图片记有空的识别结果
+    {
+        let conn = Connection::open(data_dir.join("clippy.db")).unwrap();
+        conn.execute_batch(&format!(
+            "UPDATE records SET metadata = json_set(metadata, '$.qr', json('[]')), tag = '[\"image\"]',
+                    content_text = '[图片] 900x700 PNG' WHERE id = {barcode_id};
+             PRAGMA user_version = 9;"
+        )).unwrap();
+    }
+
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let pending = storage.images_to_scan().unwrap();
+    assert_eq!(pending.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![barcode_id], "Images with QR codes are not rescanned");
+    let codes = qr::decode(&image::open(&pending[0].1).unwrap());
+    storage.save_qr_codes(barcode_id, &codes).unwrap();
+    assert_eq!(storage.get_qr_codes(barcode_id).unwrap(), vec!["4006381333931".to_string()]);
+    assert_eq!(storage.search("4006381333931").unwrap().len(), 1);
+    assert_eq!(storage.get_qr_codes(qr_id).unwrap(), vec!["https://example.com/kept".to_string()]);
+}
+
+#[test]
+fn test_generate_qr_image_from_text_clip() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let text = storage.add_text("https://example.com/to-phone".to_string()).unwrap();
+    let (image_id, _) = storage.add_qr_image(text).unwrap();
+    assert_ne!(image_id, text);
+    assert_eq!(storage.get_qr_codes(image_id).unwrap(), vec!["https://example.com/to-phone".to_string()]);
+
+    match storage.get_content(image_id).unwrap() {
+        ClipData::Image(bytes) => {
+            let img = image::load_from_memory(&bytes).unwrap();
+            assert_eq!(qr::decode(&img), vec!["https://example.com/to-phone".to_string()]);
+        }
+        other => panic!("Expected image, got {:?}", other),
+    }
+    assert!(storage.add_qr_image(image_id).is_err(), "Images cannot be turned into QR codes");
+
+    // 命令中的做法：锁外编码，再写入数据库；同一文本生成的图片去重
+    let source = storage.qr_source_text(text).unwrap();
+    let prepared = PreparedImage::from_qr(&source, storage.image_options(), &storage.blob_store()).unwrap();
+    assert_eq!(storage.insert_image(prepared).unwrap().0, image_id);
+    assert!(storage.qr_source_text(image_id).is_err());
+}
@@ -0,0 +1,105 @@
+/// 保存的搜索（智能分组）测试
+/// 验证 CRUD、过滤条件组合和实时计数
+
+mod common;
+
+use pastee_lib::persist::{ClipFilter, ClipType, Storage};
+use common::{create_test_dir, get_test_data_dir};
+
+#[test]
+fn test_saved_search_crud() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+
+    let filter = ClipFilter { query: Some("TODO".to_string()), ..Default::default() };
+    let id = storage.create_saved_search("Todos", &filter).unwrap();
+
+    let searches = storage.list_saved_searches().unwrap();
+    assert_eq!(searches.len(), 1);
+    assert_eq!(searches[0].name, "Todos");
+    assert_eq!(searches[0].filter, filter);
+
+    let colors = ClipFilter { types: vec![ClipType::Color], ..Default::default() };
+    storage.update_saved_search(id, "Colors", &colors).unwrap();
+    assert_eq!(storage.get_saved_search(id).unwrap(), colors);
+
+    storage.delete_saved_search(id).unwrap();
+    assert!(storage.list_saved_searches().unwrap().is_empty());
+    assert!(storage.update_saved_search(id, "Gone", &colors).is_err(), "Updating a deleted search should fail");
+}
+
+#[test]
+fn test_saved_search_name_rules() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+
+    assert!(storage.create_saved_search("   ", &ClipFilter::default()).is_err(), "Empty name should be rejected");
+    storage.create_saved_search("Work", &ClipFilter::default()).unwrap();
+    assert!(storage.create_saved_search("Work", &ClipFilter::default()).is_err(), "Names should be unique");
+}
+
+#[test]
+fn test_live_counts() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let filter = ClipFilter { types: vec![ClipType::Color], ..Default::default() };
+    storage.create_saved_search("Colors", &filter).unwrap();
+    assert_eq!(storage.list_saved_searches().unwrap()[0].count, 0);
+
+    storage.add_text("#FF0000".to_string()).unwrap();
+    storage.add_text("#00FF00".to_string()).unwrap();
+    storage.add_text("plain text".to_string()).unwrap();
+
+    assert_eq!(storage.list_saved_searches().unwrap()[0].count, 2, "Counts should follow new clips");
+}
+
+#[test]
+fn test_filter_by_tag_and_pinned() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let pinned_html = storage.add_html("Pinned page".to_string(), "<p>Pinned page</p>".to_string()).unwrap();
+    storage.add_html("Other page".to_string(), "<p>Other page</p>".to_string()).unwrap();
+    let pinned_text = storage.add_text("Pinned text".to_string()).unwrap();
+    storage.toggle_pin(pinned_html).unwrap();
+    storage.toggle_pin(pinned_text).unwrap();
+
+    let filter = ClipFilter {
+        tags: vec!["html".to_string()],
+        pinned: Some(true),
+        ..Default::default()
+    };
+    let id = storage.create_saved_search("Pinned pages", &filter).unwrap();
+
+    let clips = storage.get_saved_search_clips(id, 10, 0).unwrap();
+    assert_eq!(clips.len(), 1);
+    assert_eq!(clips[0].id, pinned_html);
+}
+
+#[test]
+fn test_filter_by_query_and_recent_days() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("deploy 100% done".to_string()).unwrap();
+    storage.add_text("deploy started".to_string()).unwrap();
+    storage.add_text("lunch".to_string()).unwrap();
+
+    let filter = ClipFilter {
+        query: Some("deploy".to_string()),
+        within_days: Some(7),
+        ..Default::default()
+    };
+    assert_eq!(storage.count_clips(&filter).unwrap(), 2);
+    assert_eq!(storage.query_clips(&filter, 1, 0).unwrap().len(), 1, "Limit should apply");
+
+    // 查询中的 % 按字面匹配
+    let filter = ClipFilter { query: Some("100%".to_string()), ..Default::default() };
+    assert_eq!(storage.count_clips(&filter).unwrap(), 1);
+}
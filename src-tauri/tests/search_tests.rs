@@ -0,0 +1,131 @@
+/// 搜索结果测试
+/// 验证命中片段、高亮位置、命中总数和分页
+
+mod common;
+
+use pastee_lib::persist::Storage;
+use common::{create_test_dir, get_test_data_dir};
+
+#[test]
+fn test_snippet_contains_match_in_long_text() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    // 命中位置远在前 50 个字符之后
+    let long_text = format!("{} needle {}", "a".repeat(200), "b".repeat(200));
+    storage.add_text(long_text).unwrap();
+
+    let page = storage.search_page("needle", 10, 0).unwrap();
+    assert_eq!(page.total, 1);
+
+    let hit = &page.hits[0];
+    assert!(hit.snippet.contains("needle"), "Snippet should contain the match: {}", hit.snippet);
+    assert!(hit.snippet.starts_with('…') && hit.snippet.ends_with('…'), "Snippet should be elided on both sides");
+    assert_eq!(hit.match_count, 1);
+
+    // 旧接口的预览也应包含命中
+    let items = storage.search("needle").unwrap();
+    assert!(items[0].preview.contains("needle"));
+}
+
+#[test]
+fn test_highlight_offsets() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("Rust and rust and RUST".to_string()).unwrap();
+
+    let page = storage.search_page("rust", 10, 0).unwrap();
+    let hit = &page.hits[0];
+    assert_eq!(hit.match_count, 3, "Matching should ignore ASCII case like LIKE does");
+    assert_eq!(hit.highlights.len(), 3);
+
+    let chars: Vec<char> = hit.snippet.chars().collect();
+    for range in &hit.highlights {
+        let matched: String = chars[range.start..range.end].iter().collect();
+        assert_eq!(matched.to_lowercase(), "rust");
+    }
+}
+
+#[test]
+fn test_highlight_offsets_are_char_based() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("剪贴板管理工具，管理历史".to_string()).unwrap();
+
+    let page = storage.search_page("管理", 10, 0).unwrap();
+    let hit = &page.hits[0];
+    assert_eq!(hit.match_count, 2);
+
+    let chars: Vec<char> = hit.snippet.chars().collect();
+    for range in &hit.highlights {
+        let matched: String = chars[range.start..range.end].iter().collect();
+        assert_eq!(matched, "管理");
+    }
+}
+
+#[test]
+fn test_search_total_and_paging() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    for i in 0..25 {
+        storage.add_text(format!("log line {}", i)).unwrap();
+    }
+    storage.add_text("unrelated".to_string()).unwrap();
+
+    let page1 = storage.search_page("log", 10, 0).unwrap();
+    let page2 = storage.search_page("log", 10, 10).unwrap();
+    let page3 = storage.search_page("log", 10, 20).unwrap();
+
+    assert_eq!(page1.total, 25, "Total should ignore paging");
+    assert_eq!(page3.total, 25);
+    assert_eq!(page1.hits.len(), 10);
+    assert_eq!(page2.hits.len(), 10);
+    assert_eq!(page3.hits.len(), 5);
+
+    let mut ids: Vec<i64> = page1.hits.iter()
+        .chain(&page2.hits)
+        .chain(&page3.hits)
+        .map(|hit| hit.item.id)
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 25, "Pages should not overlap");
+}
+
+#[test]
+fn test_snippet_replaces_newlines() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("first line\nsecond match\nthird line".to_string()).unwrap();
+
+    let page = storage.search_page("match", 10, 0).unwrap();
+    let hit = &page.hits[0];
+    assert!(!hit.snippet.contains('\n'), "Snippet should be single-line");
+
+    let chars: Vec<char> = hit.snippet.chars().collect();
+    let range = hit.highlights[0];
+    assert_eq!(chars[range.start..range.end].iter().collect::<String>(), "match");
+}
+
+#[test]
+fn test_search_escapes_backslash() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("C:\\Users\\demo".to_string()).unwrap();
+    storage.add_text("plain".to_string()).unwrap();
+
+    let page = storage.search_page("\\Users", 10, 0).unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.hits[0].highlights.len(), 1);
+}
@@ -0,0 +1,139 @@
+/// 相似图片测试
+/// 验证感知哈希对细微改动的容忍度、相似图片查询 / 归组、替换模式和已有图片的哈希补齐（含读取失败的图片）
+
+mod common;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use pastee_lib::imagehash::{dhash, distance};
+use pastee_lib::persist::Storage;
+use pastee_lib::setting::{Settings, SimilarImages};
+use common::{create_test_dir, get_test_data_dir};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+/// 从左到右、从上到下渐变的「截图」，changed 个像素被改成白色
+fn screenshot(changed: u32) -> RgbaImage {
+    let mut image = RgbaImage::from_fn(WIDTH, HEIGHT, |x, y| Rgba([(x * 4) as u8, (y * 5) as u8, 128, 255]));
+    for i in 0..changed {
+        image.put_pixel(i * 7 % WIDTH, i * 3 % HEIGHT, Rgba([255, 255, 255, 255]));
+    }
+    image
+}
+
+/// 方向相反的渐变，与 screenshot 完全不同
+fn other_image() -> RgbaImage {
+    RgbaImage::from_fn(WIDTH, HEIGHT, |x, y| Rgba([255 - (x * 4) as u8, 0, (y * 5) as u8, 255]))
+}
+
+fn add(storage: &mut Storage, image: RgbaImage) -> i64 {
+    storage.add_image(WIDTH as usize, HEIGHT as usize, image.into_raw()).unwrap().0
+}
+
+#[test]
+fn test_dhash_tolerates_small_changes() {
+    let original = dhash(&DynamicImage::ImageRgba8(screenshot(0)));
+    let touched = dhash(&DynamicImage::ImageRgba8(screenshot(1)));
+    let resized = dhash(&DynamicImage::ImageRgba8(screenshot(0)).thumbnail(32, 24));
+    let other = dhash(&DynamicImage::ImageRgba8(other_image()));
+
+    assert!(distance(original, touched) <= 2, "One changed pixel barely moves the hash");
+    assert!(distance(original, resized) <= 2, "Scaling keeps the hash");
+    assert!(distance(original, other) > 20);
+}
+
+#[test]
+fn test_find_and_group_similar_images() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    let first = add(&mut storage, screenshot(0));
+    let second = add(&mut storage, screenshot(1));
+    let other = add(&mut storage, other_image());
+    assert_ne!(first, second, "Near-duplicates are kept in group mode");
+
+    let similar = storage.find_similar_images(first, None).unwrap();
+    assert_eq!(similar.iter().map(|s| s.item.id).collect::<Vec<_>>(), vec![second]);
+    assert!(storage.find_similar_images(first, Some(0)).unwrap().len() <= 1);
+    assert_eq!(storage.find_similar_images(other, Some(64)).unwrap().len(), 2);
+
+    assert_eq!(storage.similar_image_groups().unwrap(), vec![vec![second, first]]);
+
+    let text = storage.add_text("not an image".to_string()).unwrap();
+    assert!(storage.find_similar_images(text, None).is_err());
+}
+
+#[test]
+fn test_replace_mode() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    storage.update_settings(Settings { similar_images: SimilarImages::Replace, ..Default::default() }).unwrap();
+
+    let pinned = add(&mut storage, screenshot(0));
+    storage.toggle_pin(pinned).unwrap();
+    let replaced = add(&mut storage, screenshot(1));
+    let newest = add(&mut storage, screenshot(2));
+    let other = add(&mut storage, other_image());
+
+    let ids: Vec<i64> = storage.get_recent(10, 0).unwrap().iter().map(|item| item.id).collect();
+    assert_eq!(ids, vec![pinned, other, newest], "Pinned images survive, unpinned near-duplicates are replaced");
+    assert!(!ids.contains(&replaced));
+
+    assert!(storage.update_settings(Settings { similar_image_threshold: 65, ..Default::default() }).is_err());
+}
+
+#[test]
+fn test_backfill_existing_images() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let (first, second) = {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        (add(&mut storage, screenshot(0)), add(&mut storage, screenshot(3)))
+    };
+
+    // 模拟迁移前保存的图片：没有感知哈希
+    {
+        let conn = rusqlite::Connection::open(data_dir.join("clippy.db")).unwrap();
+        conn.execute("UPDATE records SET image_phash = NULL", []).unwrap();
+    }
+
+    // 启动时不读取图片，由后台线程补齐
+    let mut storage = Storage::new(&data_dir).unwrap();
+    assert_eq!(storage.images_to_hash().unwrap().len(), 2);
+    assert_eq!(storage.backfill_image_phashes().unwrap(), 2);
+    assert!(storage.images_to_hash().unwrap().is_empty());
+    assert_eq!(storage.find_similar_images(first, None).unwrap()[0].item.id, second);
+}
+
+#[test]
+fn test_unreadable_images_are_not_retried() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let (broken, readable) = {
+        let mut storage = Storage::new(&data_dir).unwrap();
+        let broken = add(&mut storage, screenshot(0));
+        let readable = add(&mut storage, RgbaImage::from_pixel(WIDTH, HEIGHT, Rgba([0, 0, 0, 255])));
+
+        // 模拟迁移前保存、文件已经丢失的图片
+        let (image_path, thumbnail_path) = storage.get_image_paths(broken).unwrap();
+        std::fs::remove_file(data_dir.join("images").join(image_path)).unwrap();
+        std::fs::remove_file(data_dir.join("images").join(thumbnail_path)).unwrap();
+        (broken, readable)
+    };
+    {
+        let conn = rusqlite::Connection::open(data_dir.join("clippy.db")).unwrap();
+        conn.execute("UPDATE records SET image_phash = NULL", []).unwrap();
+    }
+
+    let mut storage = Storage::new(&data_dir).unwrap();
+    assert_eq!(storage.backfill_image_phashes().unwrap(), 1);
+    assert_eq!(storage.get_metadata(broken).unwrap()["phash_failed"], serde_json::json!(true));
+    assert!(storage.get_metadata(readable).unwrap().get("phash_failed").is_none());
+
+    // 失败记录在重启后也不再重试
+    drop(storage);
+    let storage = Storage::new(&data_dir).unwrap();
+    assert!(storage.images_to_hash().unwrap().is_empty());
+}
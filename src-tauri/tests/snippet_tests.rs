@@ -0,0 +1,62 @@
+/// 代码片段库测试
+/// 验证片段的增删改查、缩写查找和模板校验
+
+mod common;
+
+use pastee_lib::persist::Storage;
+use common::{create_test_dir, get_test_data_dir};
+
+#[test]
+fn test_snippet_crud() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+
+    let id = storage.create_snippet("Greeting", Some("hi"), "Hello {input:Name}").unwrap();
+
+    let snippet = storage.get_snippet(id).unwrap();
+    assert_eq!(snippet.name, "Greeting");
+    assert_eq!(snippet.abbreviation.as_deref(), Some("hi"));
+    assert_eq!(snippet.inputs, vec!["Name".to_string()]);
+
+    storage.update_snippet(id, "Sign-off", None, "Thanks, {input:Me}\n{date}").unwrap();
+    let snippets = storage.list_snippets().unwrap();
+    assert_eq!(snippets.len(), 1);
+    assert_eq!(snippets[0].name, "Sign-off");
+    assert_eq!(snippets[0].abbreviation, None);
+    assert_eq!(snippets[0].inputs, vec!["Me".to_string()]);
+
+    storage.delete_snippet(id).unwrap();
+    assert!(storage.get_snippet(id).is_err());
+    assert!(storage.update_snippet(id, "Gone", None, "x").is_err());
+}
+
+#[test]
+fn test_snippet_abbreviation_lookup() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+
+    storage.create_snippet("Address", Some("addr"), "1 Main St").unwrap();
+    storage.create_snippet("No abbreviation", Some("  "), "text").unwrap();
+    storage.create_snippet("Also none", None, "text").unwrap();
+
+    let found = storage.find_snippet_by_abbreviation("addr").unwrap().unwrap();
+    assert_eq!(found.body, "1 Main St");
+    assert!(storage.find_snippet_by_abbreviation("nope").unwrap().is_none());
+
+    // 缩写唯一，空白缩写视为未设置
+    assert!(storage.create_snippet("Duplicate", Some("addr"), "x").is_err());
+}
+
+#[test]
+fn test_snippet_rejects_invalid_template() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let storage = Storage::new(&data_dir).unwrap();
+
+    assert!(storage.create_snippet("Broken", None, "Hello {input:Name").is_err());
+    assert!(storage.create_snippet("Unknown", None, "{weather}").is_err());
+    assert!(storage.create_snippet("", None, "body").is_err());
+    assert!(storage.list_snippets().unwrap().is_empty());
+}
@@ -0,0 +1,109 @@
+/// 表格识别测试
+/// 验证 TSV/CSV/HTML 表格解析、格式转换和 table 标签
+
+mod common;
+
+use pastee_lib::persist::{ClipData, Storage};
+use pastee_lib::table::{detect, parse_csv, parse_html, parse_tsv, TableFormat};
+use pastee_lib::transform::apply_text;
+use common::{create_test_dir, get_test_data_dir};
+
+fn cells(rows: &[&[&str]]) -> Vec<Vec<String>> {
+    rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()).collect()
+}
+
+#[test]
+fn test_parse_tsv() {
+    let table = parse_tsv("Name\tQty\r\nApple\t3\nPear\t\n").unwrap();
+    assert_eq!(table.format, TableFormat::Tsv);
+    assert_eq!(table.columns, 2);
+    assert_eq!(table.rows, cells(&[&["Name", "Qty"], &["Apple", "3"], &["Pear", ""]]));
+
+    assert!(parse_tsv("only\tone row").is_none(), "A single row is not a table");
+    assert!(parse_tsv("a\tb\nc").is_none(), "Ragged rows are not a table");
+    assert!(parse_tsv("no tabs\nat all").is_none());
+    assert!(parse_tsv("\tlet a = 1;\n\treturn a;").is_none(), "Tab-indented code is not a table");
+}
+
+#[test]
+fn test_parse_csv() {
+    let table = parse_csv("id,note\n1,\"hello, world\"\n2,\"say \"\"hi\"\"\nbye\"").unwrap();
+    assert_eq!(table.format, TableFormat::Csv);
+    assert_eq!(table.rows, cells(&[&["id", "note"], &["1", "hello, world"], &["2", "say \"hi\"\nbye"]]));
+
+    assert!(parse_csv("Hello, world\nGoodbye, moon").is_none(), "Prose with commas is not CSV");
+    assert!(parse_csv("a,\"unterminated\nb,c").is_none());
+    assert!(parse_csv("1,000\n2,000").is_none(), "Numbers with thousands separators are not CSV");
+    assert!(parse_csv("1,250,000\n980,000").is_none());
+    assert!(parse_csv("1,2\n3,4").is_some(), "Plain numeric columns are still CSV");
+}
+
+#[test]
+fn test_parse_html_table() {
+    let html = "<meta charset=utf-8><table><thead><tr><th>Region</th><th>Q1</th><th>Q2</th></tr></thead>\
+                <tbody><tr><td>North &amp; East</td><td colspan=\"2\">n/a</td></tr>\
+                <tr><td>South</td><td>5</td></tr></tbody></table>";
+    let table = parse_html(html).unwrap();
+    assert_eq!(table.format, TableFormat::Html);
+    assert_eq!(table.columns, 3);
+    assert_eq!(table.rows, cells(&[
+        &["Region", "Q1", "Q2"],
+        &["North & East", "n/a", ""],
+        &["South", "5", ""],
+    ]));
+
+    assert!(parse_html("<p>No table here</p>").is_none());
+    assert_eq!(detect(html).unwrap().format, TableFormat::Html, "HTML tables take priority");
+}
+
+#[test]
+fn test_conversions() {
+    let table = parse_tsv("name\tnote\na|b\t\"x\", y\n").unwrap();
+
+    assert_eq!(table.to_markdown(), "| name | note |\n| --- | --- |\n| a\\|b | \"x\", y |");
+    assert_eq!(table.to_csv(), "name,note\na|b,\"\"\"x\"\", y\"");
+    assert_eq!(table.to_json().unwrap(), "[\n  {\n    \"name\": \"a|b\",\n    \"note\": \"\\\"x\\\", y\"\n  }\n]");
+    assert_eq!(
+        table.to_html(),
+        "<table><thead><tr><th>name</th><th>note</th></tr></thead>\
+         <tbody><tr><td>a|b</td><td>&quot;x&quot;, y</td></tr></tbody></table>"
+    );
+}
+
+#[test]
+fn test_json_keys_for_blank_and_duplicate_headers() {
+    let table = parse_tsv("a\t\ta\n1\t2\t3").unwrap();
+    let json: serde_json::Value = serde_json::from_str(&table.to_json().unwrap()).unwrap();
+    assert_eq!(json, serde_json::json!([{ "a": "1", "column2": "2", "column3": "3" }]));
+}
+
+#[test]
+fn test_table_transforms() {
+    let html = ClipData::Html {
+        text: "k\tv\na\t1".to_string(),
+        html: "<table><tr><td>k</td><td>v</td></tr><tr><td>a</td><td>1</td></tr></table>".to_string(),
+    };
+    assert_eq!(apply_text("table_csv", &html).unwrap(), "k,v\na,1");
+    assert_eq!(apply_text("table_markdown", &ClipData::Text("k,v\na,1".to_string())).unwrap(),
+               "| k | v |\n| --- | --- |\n| a | 1 |");
+    assert!(apply_text("table_json", &ClipData::Text("not a table".to_string())).is_err());
+}
+
+#[test]
+fn test_table_clips_are_tagged() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    storage.add_text("Name\tQty\nApple\t3".to_string()).unwrap();
+    storage.add_html(
+        "k\tv".to_string(),
+        "<table><tr><td>k</td><td>v</td></tr></table>".to_string(),
+    ).unwrap();
+    storage.add_text("just text".to_string()).unwrap();
+
+    let recent = storage.get_recent(10, 0).unwrap();
+    assert_eq!(recent[0].tags, vec!["text".to_string()]);
+    assert_eq!(recent[1].tags, vec!["html".to_string(), "table".to_string()]);
+    assert_eq!(recent[2].tags, vec!["text".to_string(), "table".to_string()]);
+}
@@ -0,0 +1,89 @@
+/// 模板引擎测试
+/// 验证代码片段占位符的解析、展开和错误处理
+
+use chrono::{Local, TimeZone};
+use pastee_lib::template::{input_names, render, validate, TemplateContext};
+use std::collections::HashMap;
+
+fn fixed_context() -> TemplateContext {
+    TemplateContext {
+        now: Local.with_ymd_and_hms(2026, 3, 9, 14, 5, 7).unwrap(),
+        clipboard: Some("copied".to_string()),
+        inputs: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_plain_text_passthrough() {
+    let ctx = fixed_context();
+    assert_eq!(render("no placeholders here", &ctx).unwrap(), "no placeholders here");
+    assert_eq!(render("", &ctx).unwrap(), "");
+}
+
+#[test]
+fn test_date_and_time() {
+    let ctx = fixed_context();
+    assert_eq!(render("{date}", &ctx).unwrap(), "2026-03-09");
+    assert_eq!(render("{time}", &ctx).unwrap(), "14:05:07");
+    assert_eq!(render("{date:%d/%m/%Y}", &ctx).unwrap(), "09/03/2026");
+    assert_eq!(render("{time:%H:%M}", &ctx).unwrap(), "14:05");
+    assert_eq!(render("Report {date:%Y-%m-%d} at {time:%H.%M}", &ctx).unwrap(), "Report 2026-03-09 at 14.05");
+}
+
+#[test]
+fn test_clipboard() {
+    let mut ctx = fixed_context();
+    assert_eq!(render("> {clipboard}", &ctx).unwrap(), "> copied");
+
+    ctx.clipboard = None;
+    assert_eq!(render("> {clipboard}", &ctx).unwrap(), "> ", "Empty clipboard renders as empty text");
+}
+
+#[test]
+fn test_uuid() {
+    let ctx = fixed_context();
+    let first = render("{uuid}", &ctx).unwrap();
+    let second = render("{uuid}", &ctx).unwrap();
+    assert_eq!(first.len(), 36);
+    assert_eq!(first.matches('-').count(), 4);
+    assert_ne!(first, second, "Each render should produce a new UUID");
+}
+
+#[test]
+fn test_inputs() {
+    let mut ctx = fixed_context();
+    ctx.inputs.insert("Name".to_string(), "Ada".to_string());
+    ctx.inputs.insert("Team".to_string(), "Core".to_string());
+
+    assert_eq!(
+        render("Hi {input:Name} from {input: Team}, bye {input:Name}", &ctx).unwrap(),
+        "Hi Ada from Core, bye Ada"
+    );
+    assert_eq!(
+        input_names("{input:Name} {input:Team} {input:Name}").unwrap(),
+        vec!["Name".to_string(), "Team".to_string()],
+        "Input names should be unique and in order"
+    );
+
+    let err = render("{input:Missing}", &ctx).unwrap_err();
+    assert!(err.to_string().contains("Missing"));
+}
+
+#[test]
+fn test_escaped_braces() {
+    let ctx = fixed_context();
+    assert_eq!(render("{{date}} is literal", &ctx).unwrap(), "{date} is literal");
+    assert_eq!(render("fn main() {{ }}", &ctx).unwrap(), "fn main() { }");
+}
+
+#[test]
+fn test_syntax_errors() {
+    assert!(validate("{unknown}").is_err(), "Unknown placeholders should be rejected");
+    assert!(validate("{date").is_err(), "Unclosed placeholders should be rejected");
+    assert!(validate("stray }").is_err(), "Unmatched closing braces should be rejected");
+    assert!(validate("{input}").is_err(), "Inputs need a name");
+    assert!(validate("{input:  }").is_err(), "Inputs need a non-blank name");
+    assert!(validate("{uuid:v7}").is_err(), "uuid takes no argument");
+    assert!(validate("{date:%Q}").is_err(), "Invalid strftime formats should be rejected");
+    assert!(validate("{date:%Y} {time} {clipboard} {uuid} {input:X}").is_ok());
+}
@@ -0,0 +1,95 @@
+/// 多尺寸缩略图测试
+/// 验证按尺寸和倍率懒生成、缓存命中、设置变更后的清理重建以及设置校验
+
+mod common;
+
+use image::{Rgba, RgbaImage};
+use pastee_lib::persist::{Storage, Thumbnail};
+use pastee_lib::setting::Settings;
+use pastee_lib::thumbnail::ThumbnailSize;
+use std::path::Path;
+use common::{create_test_dir, get_test_data_dir};
+
+fn add_image(storage: &mut Storage, width: u32, height: u32) -> i64 {
+    let image = RgbaImage::from_fn(width, height, |x, y| Rgba([(x % 256) as u8, (y % 256) as u8, 90, 255]));
+    storage.add_image(width as usize, height as usize, image.into_raw()).unwrap().0
+}
+
+fn dimensions(data_dir: &Path, path: &str) -> (u32, u32) {
+    image::image_dimensions(data_dir.join("images").join(path)).unwrap()
+}
+
+#[test]
+fn test_thumbnails_generated_lazily_and_cached() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let id = add_image(&mut storage, 600, 400);
+
+    assert!(matches!(storage.find_thumbnail(id, "list", 1).unwrap(), Thumbnail::Missing(_)));
+    let list = storage.get_thumbnail(id, "list", 1).unwrap();
+    assert_eq!(dimensions(&data_dir, &list), (240, 160));
+    assert_eq!(storage.find_thumbnail(id, "list", 1).unwrap(), Thumbnail::Cached(list.clone()));
+
+    let retina = storage.get_thumbnail(id, "list", 2).unwrap();
+    assert_eq!(dimensions(&data_dir, &retina), (480, 320));
+    let preview = storage.get_thumbnail(id, "preview", 2).unwrap();
+    assert_eq!(dimensions(&data_dir, &preview), (600, 400), "Thumbnails never upscale");
+
+    assert!(storage.get_thumbnail(id, "huge", 1).is_err());
+    assert!(storage.get_thumbnail(id, "list", 3).is_err());
+    let text = storage.add_text("not an image".to_string()).unwrap();
+    assert!(storage.get_thumbnail(text, "list", 1).is_err());
+}
+
+#[test]
+fn test_regenerate_after_settings_change() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+    let id = add_image(&mut storage, 600, 400);
+    let old_list = storage.get_thumbnail(id, "list", 1).unwrap();
+    storage.get_thumbnail(id, "list", 2).unwrap();
+    storage.get_thumbnail(id, "preview", 1).unwrap();
+    assert_eq!(storage.missing_thumbnails().unwrap().len(), 1, "Only preview@2x is missing");
+
+    storage.update_settings(Settings {
+        thumbnail_sizes: vec![ThumbnailSize::new("list", 120, 90), ThumbnailSize::new("preview", 800, 600)],
+        ..Default::default()
+    }).unwrap();
+    assert!(matches!(storage.find_thumbnail(id, "list", 1).unwrap(), Thumbnail::Missing(_)), "Outdated sizes miss");
+
+    assert_eq!(storage.prune_thumbnails().unwrap(), 2);
+    assert!(!data_dir.join("images").join(&old_list).exists(), "Outdated thumbnails are deleted");
+
+    let jobs = storage.missing_thumbnails().unwrap();
+    assert_eq!(jobs.len(), 3);
+    for job in jobs {
+        let bytes = job.render().unwrap();
+        storage.save_thumbnail(&job, &bytes).unwrap();
+    }
+    assert!(storage.missing_thumbnails().unwrap().is_empty());
+    let list = storage.get_thumbnail(id, "list", 1).unwrap();
+    assert_eq!(dimensions(&data_dir, &list), (120, 80));
+
+    // 缓存文件丢失时重新生成
+    std::fs::remove_file(data_dir.join("images").join(&list)).unwrap();
+    assert_eq!(storage.missing_thumbnails().unwrap().len(), 1);
+}
+
+#[test]
+fn test_thumbnail_settings_validation() {
+    let temp_dir = create_test_dir();
+    let data_dir = get_test_data_dir(&temp_dir);
+    let mut storage = Storage::new(&data_dir).unwrap();
+
+    for invalid in [
+        Settings { thumbnail_sizes: vec![ThumbnailSize::new("a", 100, 100), ThumbnailSize::new("a", 200, 200)], ..Default::default() },
+        Settings { thumbnail_sizes: vec![ThumbnailSize::new("bad name", 100, 100)], ..Default::default() },
+        Settings { thumbnail_sizes: vec![ThumbnailSize::new("tiny", 8, 8)], ..Default::default() },
+        Settings { thumbnail_scales: vec![1, 5], ..Default::default() },
+        Settings { thumbnail_scales: vec![], ..Default::default() },
+    ] {
+        assert!(storage.update_settings(invalid).is_err());
+    }
+}
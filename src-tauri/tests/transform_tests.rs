@@ -0,0 +1,89 @@
+/// "粘贴为" 文本转换测试
+/// 验证注册表和各个转换的输出
+
+use pastee_lib::persist::ClipData;
+use pastee_lib::transform::{apply, find, list, registry};
+
+fn run(id: &str, input: &str) -> String {
+    match apply(id, &ClipData::Text(input.to_string())).unwrap() {
+        ClipData::Text(text) => text,
+        other => panic!("Transform {} should produce text, got {:?}", id, other),
+    }
+}
+
+#[test]
+fn test_registry_ids_are_unique() {
+    let mut ids: Vec<&str> = registry().iter().map(|t| t.id).collect();
+    let total = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), total, "Transform ids should be unique");
+    assert_eq!(list().len(), total);
+    assert!(find("upper").is_some());
+    assert!(find("nope").is_none());
+}
+
+#[test]
+fn test_case_transforms() {
+    assert_eq!(run("upper", "Hello wörld"), "HELLO WÖRLD");
+    assert_eq!(run("lower", "Hello WÖRLD"), "hello wörld");
+    assert_eq!(run("title", "the QUICK brown-fox's tail"), "The Quick Brown-Fox's Tail");
+}
+
+#[test]
+fn test_line_transforms() {
+    assert_eq!(run("trim_lines", "  a  \n\tb\n c"), "a\nb\nc");
+    assert_eq!(run("sort_lines", "pear\napple\nfig"), "apple\nfig\npear");
+    assert_eq!(run("dedupe_lines", "b\na\nb\nc\na"), "b\na\nc");
+}
+
+#[test]
+fn test_json_transforms() {
+    assert_eq!(run("json_minify", "{\n  \"b\": 1,\n  \"a\": [1, 2]\n}"), r#"{"b":1,"a":[1,2]}"#);
+    assert_eq!(run("json_pretty", r#"{"b":1,"a":true}"#), "{\n  \"b\": 1,\n  \"a\": true\n}");
+    assert!(apply("json_pretty", &ClipData::Text("{not json".to_string())).is_err());
+}
+
+#[test]
+fn test_base64_roundtrip() {
+    assert_eq!(run("base64_encode", "hello 世界"), "aGVsbG8g5LiW55WM");
+    assert_eq!(run("base64_decode", "aGVsbG8g5LiW55WM"), "hello 世界");
+    // 无填充和 URL-safe 字母表
+    assert_eq!(run("base64_decode", "aGk"), "hi");
+    assert_eq!(run("base64_decode", "Pz8_"), "???");
+    assert!(apply("base64_decode", &ClipData::Text("@@@".to_string())).is_err());
+}
+
+#[test]
+fn test_url_roundtrip() {
+    assert_eq!(run("url_encode", "a b&c=d/é"), "a%20b%26c%3Dd%2F%C3%A9");
+    assert_eq!(run("url_decode", "a%20b%26c%3Dd%2F%C3%A9"), "a b&c=d/é");
+    assert!(apply("url_decode", &ClipData::Text("%zz".to_string())).is_err());
+    // 转义中的符号不是十六进制数字
+    assert!(apply("url_decode", &ClipData::Text("%+1".to_string())).is_err());
+    assert!(apply("url_decode", &ClipData::Text("%-1".to_string())).is_err());
+}
+
+#[test]
+fn test_escape_transforms() {
+    assert_eq!(run("shell_escape", "it's $HOME"), r#"'it'\''s $HOME'"#);
+    assert_eq!(run("json_escape", "say \"hi\"\n"), r#""say \"hi\"\n""#);
+}
+
+#[test]
+fn test_clip_data_sources() {
+    let html = ClipData::Html { text: "Bold".to_string(), html: "<b>Bold</b>".to_string() };
+    match apply("plain_text", &html).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, "Bold"),
+        other => panic!("Unexpected {:?}", other),
+    }
+
+    let files = ClipData::Files(vec!["/b".to_string(), "/a".to_string()]);
+    match apply("sort_lines", &files).unwrap() {
+        ClipData::Text(text) => assert_eq!(text, "/a\n/b"),
+        other => panic!("Unexpected {:?}", other),
+    }
+
+    assert!(apply("upper", &ClipData::Image(vec![])).is_err(), "Images cannot be transformed");
+    assert!(apply("missing", &ClipData::Text("x".to_string())).is_err());
+}
@@ -17,11 +17,37 @@ export const getRecentClips = (limit: number = 20, offset: number = 0): Promise<
     return invoke<ClipItemData[]>("get_recent_clips", { limit, offset });
 };
 
+export interface ClipPageData {
+    items: ClipItemData[];
+    next_cursor: string | null;
+    has_more: boolean;
+}
+
+/**
+ * 游标分页获取剪贴板项，cursor 传上一页返回的 next_cursor
+ */
+export const getClipsPage = (limit: number = 20, cursor: string | null = null): Promise<ClipPageData> => {
+    return invoke<ClipPageData>("get_clips_page", { limit, cursor });
+};
+
+export interface SearchHitData extends ClipItemData {
+    snippet: string;
+    highlights: { start: number; end: number }[];
+    match_count: number;
+}
+
+export interface SearchPageData {
+    hits: SearchHitData[];
+    total: number;
+    limit: number;
+    offset: number;
+}
+
 /**
- * 搜索剪贴板项
+ * 搜索剪贴板项（分页，带命中片段和高亮位置）
  */
-export const searchClips = (query: string): Promise<ClipItemData[]> => {
-    return invoke<ClipItemData[]>("search_clips", { query });
+export const searchClips = (query: string, limit: number = 50, offset: number = 0): Promise<SearchPageData> => {
+    return invoke<SearchPageData>("search_clips", { query, limit, offset });
 };
 
 /**
@@ -46,15 +72,32 @@ export const deleteClip = (id: number): Promise<void> => {
 };
 
 /**
- * 获取图片URL
+ * 即时缩放的目标框（按比例缩小，不放大）
+ */
+export interface ImageResize {
+    width?: number;
+    height?: number;
+}
+
+/**
+ * pastee:// 协议下的图片 URL，各平台的 URL 形式由 convertFileSrc 处理
  */
-export const getImageUrl = async (id: number, thumbnail: boolean = false): Promise<string> => {
+const pasteeUrl = async (path: string, resize?: ImageResize): Promise<string> => {
     const { convertFileSrc } = await import("@tauri-apps/api/core");
-    const relativePath = await invoke<string>("get_image_url", { id, thumbnail });
-    const { documentDir } = await import("@tauri-apps/api/path");
-    const docDir = await documentDir();
-    const fullPath = `${docDir}/pastee/images/${relativePath}`;
-    return convertFileSrc(fullPath);
+    const url = convertFileSrc(path, "pastee");
+    const query = new URLSearchParams();
+    if (resize?.width) query.set("w", String(Math.round(resize.width)));
+    if (resize?.height) query.set("h", String(Math.round(resize.height)));
+    const search = query.toString();
+    return search ? `${url}?${search}` : url;
+};
+
+/**
+ * 获取图片URL
+ */
+export const getImageUrl = async (id: number, thumbnail: boolean = false, resize?: ImageResize): Promise<string> => {
+    const path = await invoke<string>("get_image_url", { id, thumbnail });
+    return pasteeUrl(path, resize);
 };
 
 /**
@@ -72,3 +115,457 @@ export const onClipboardNewClip = (callback: (data: any) => void): Promise<() =>
         callback(event.payload);
     });
 };
+
+export interface ClipFilterData {
+    query?: string | null;
+    types?: ClipItemData["content_type"][];
+    tags?: string[];
+    pinned?: boolean | null;
+    within_days?: number | null;
+}
+
+export interface SavedSearchData {
+    id: number;
+    name: string;
+    filter: ClipFilterData;
+    count: number;
+    created_at: number;
+}
+
+/**
+ * 保存的搜索（智能分组）
+ */
+export const listSavedSearches = (): Promise<SavedSearchData[]> => {
+    return invoke<SavedSearchData[]>("list_saved_searches");
+};
+
+export const createSavedSearch = (name: string, filter: ClipFilterData): Promise<number> => {
+    return invoke<number>("create_saved_search", { name, filter });
+};
+
+export const updateSavedSearch = (id: number, name: string, filter: ClipFilterData): Promise<void> => {
+    return invoke("update_saved_search", { id, name, filter });
+};
+
+export const deleteSavedSearch = (id: number): Promise<void> => {
+    return invoke("delete_saved_search", { id });
+};
+
+export const getSavedSearchClips = (id: number, limit: number = 20, offset: number = 0): Promise<ClipItemData[]> => {
+    return invoke<ClipItemData[]>("get_saved_search_clips", { id, limit, offset });
+};
+
+export interface BoardData {
+    id: number;
+    name: string;
+    item_count: number;
+    created_at: number;
+}
+
+/**
+ * 看板（手动整理的分组）
+ */
+export const listBoards = (): Promise<BoardData[]> => {
+    return invoke<BoardData[]>("list_boards");
+};
+
+export const createBoard = (name: string): Promise<number> => {
+    return invoke<number>("create_board", { name });
+};
+
+export const renameBoard = (id: number, name: string): Promise<void> => {
+    return invoke("rename_board", { id, name });
+};
+
+export const deleteBoard = (id: number): Promise<void> => {
+    return invoke("delete_board", { id });
+};
+
+export const addClipToBoard = (boardId: number, id: number): Promise<void> => {
+    return invoke("add_clip_to_board", { boardId, id });
+};
+
+export const removeClipFromBoard = (boardId: number, id: number): Promise<void> => {
+    return invoke("remove_clip_from_board", { boardId, id });
+};
+
+export const reorderBoard = (boardId: number, ids: number[]): Promise<void> => {
+    return invoke("reorder_board", { boardId, ids });
+};
+
+export const getBoardClips = (boardId: number): Promise<ClipItemData[]> => {
+    return invoke<ClipItemData[]>("get_board_clips", { boardId });
+};
+
+export const getClipBoards = (id: number): Promise<number[]> => {
+    return invoke<number[]>("get_clip_boards", { id });
+};
+
+export interface SnippetData {
+    id: number;
+    name: string;
+    abbreviation: string | null;
+    body: string;
+    inputs: string[];
+    created_at: number;
+    updated_at: number;
+}
+
+/**
+ * 代码片段库
+ */
+export const listSnippets = (): Promise<SnippetData[]> => {
+    return invoke<SnippetData[]>("list_snippets");
+};
+
+export const createSnippet = (name: string, abbreviation: string | null, body: string): Promise<number> => {
+    return invoke<number>("create_snippet", { name, abbreviation, body });
+};
+
+export const updateSnippet = (id: number, name: string, abbreviation: string | null, body: string): Promise<void> => {
+    return invoke("update_snippet", { id, name, abbreviation, body });
+};
+
+export const deleteSnippet = (id: number): Promise<void> => {
+    return invoke("delete_snippet", { id });
+};
+
+/**
+ * 展开片段模板，inputs 对应片段的 {input:Name} 输入项
+ */
+export const renderSnippet = (id: number, inputs: Record<string, string> = {}): Promise<string> => {
+    return invoke<string>("render_snippet", { id, inputs });
+};
+
+export interface TransformInfo {
+    id: string;
+    label: string;
+    /** html 表示富文本结果，粘贴时同时写入纯文本后备 */
+    output: 'text' | 'html';
+}
+
+/**
+ * "粘贴为" 文本转换
+ */
+export const listTransforms = (): Promise<TransformInfo[]> => {
+    return invoke<TransformInfo[]>("list_transforms");
+};
+
+export const previewTransform = (id: number, transform: string): Promise<any> => {
+    return invoke("preview_transform", { id, transform });
+};
+
+export const pasteTransformed = (id: number, transform: string): Promise<void> => {
+    return invoke("paste_transformed", { id, transform });
+};
+
+export interface ClipTable {
+    format: 'tsv' | 'csv' | 'html';
+    /** 第一行为表头，所有行等宽 */
+    rows: string[][];
+    columns: number;
+}
+
+/**
+ * 解析记录中的表格，不是表格时返回 null
+ */
+export const getClipTable = (id: number): Promise<ClipTable | null> => {
+    return invoke<ClipTable | null>("get_clip_table", { id });
+};
+
+export interface JsonError {
+    message: string;
+    line: number;
+    column: number;
+    offset: number;
+}
+
+export interface JsonReport {
+    valid: boolean;
+    root: 'object' | 'array' | 'string' | 'number' | 'boolean' | 'null' | null;
+    error: JsonError | null;
+}
+
+/**
+ * JSON 检查器：校验、路径查询（JSONPath 或 jq 子集）
+ */
+export const validateClipJson = (id: number): Promise<JsonReport> => {
+    return invoke<JsonReport>("validate_clip_json", { id });
+};
+
+export const queryClipJson = (id: number, expression: string): Promise<any[]> => {
+    return invoke<any[]>("query_clip_json", { id, expression });
+};
+
+export const pasteJsonQuery = (id: number, expression: string): Promise<void> => {
+    return invoke("paste_json_query", { id, expression });
+};
+
+export interface Inspection {
+    detector: string;
+    label: string;
+    fields: { name: string; value: string }[];
+}
+
+/**
+ * 检查文本记录（时间戳、Base64、JWT、十六进制、URL、摘要）
+ */
+export const inspectClip = (id: number): Promise<Inspection[]> => {
+    return invoke<Inspection[]>("inspect_clip", { id });
+};
+
+/**
+ * 记录的分类元数据，以标签为键，例如 { url: { count: 1, domains: ["example.com"] } }
+ */
+export const getClipMetadata = (id: number): Promise<Record<string, Record<string, unknown>>> => {
+    return invoke<Record<string, Record<string, unknown>>>("get_clip_metadata", { id });
+};
+
+/**
+ * 按当前分类规则重新计算所有文本记录的标签，返回有变化的记录数
+ */
+export const reclassifyClips = (): Promise<number> => {
+    return invoke<number>("reclassify_clips");
+};
+
+export interface ClipAction {
+    id: string;
+    label: string;
+    detail: string;
+}
+
+/**
+ * 记录可用的动作（打开链接、显示文件、写邮件、复制颜色）
+ */
+export const listClipActions = (id: number): Promise<ClipAction[]> => {
+    return invoke<ClipAction[]>("list_clip_actions", { id });
+};
+
+/**
+ * 执行记录上的动作
+ */
+export const runClipAction = (id: number, action: string): Promise<void> => {
+    return invoke("run_clip_action", { id, action });
+};
+
+export type ColorNotation = "hex" | "rgb" | "hsl" | "hwb" | "lab" | "lch" | "oklab" | "oklch" | "name";
+
+/**
+ * 将颜色记录转换为指定写法
+ */
+export const convertColor = (id: number, notation: ColorNotation): Promise<string> => {
+    return invoke<string>("convert_color", { id, notation });
+};
+
+export interface ColorContrast {
+    ratio: number;
+    aa: boolean;
+    aa_large: boolean;
+    aaa: boolean;
+    aaa_large: boolean;
+}
+
+/**
+ * 两条颜色记录之间的 WCAG 对比度
+ */
+export const colorContrast = (foreground: number, background: number): Promise<ColorContrast> => {
+    return invoke<ColorContrast>("color_contrast", { foreground, background });
+};
+
+/**
+ * 颜色记录的浅色 / 深色序列（HEX）
+ */
+export const colorVariants = (id: number, steps: number = 5): Promise<{ tints: string[]; shades: string[] }> => {
+    return invoke<{ tints: string[]; shades: string[] }>("color_variants", { id, steps });
+};
+
+export interface Swatch {
+    color: string;
+    share: number;
+}
+
+/**
+ * 图片记录的主色调色板（按占比从高到低）
+ */
+export const getClipPalette = (id: number): Promise<Swatch[]> => {
+    return invoke<Swatch[]>("get_clip_palette", { id });
+};
+
+/**
+ * 把调色板中的第 index 种颜色保存为颜色记录
+ */
+export const savePaletteColor = (id: number, index: number): Promise<number> => {
+    return invoke<number>("save_palette_color", { id, index });
+};
+
+/**
+ * 图片中识别出的二维码和条形码内容
+ *
+ * 包括 QR 码和 EAN、UPC、Code 128 等一维条形码
+ */
+export const getQrCodes = (id: number): Promise<string[]> => {
+    return invoke<string[]>("get_qr_codes", { id });
+};
+
+/**
+ * 把识别出的第 index 条二维码内容保存为文本记录，返回记录 ID
+ */
+export const saveQrText = (id: number, index: number): Promise<number> => {
+    return invoke<number>("save_qr_text", { id, index });
+};
+
+/**
+ * 把文本记录生成为二维码图片记录，返回新记录的 ID
+ */
+export const createQrImage = (id: number): Promise<number> => {
+    return invoke<number>("create_qr_image", { id });
+};
+
+/**
+ * 监听新图片中识别出的二维码
+ */
+export const onQrDetected = (callback: (detected: { id: number; codes: string[] }) => void): Promise<() => void> => {
+    return listen<{ id: number; codes: string[] }>("clipboard://qr-detected", (event) => {
+        callback(event.payload);
+    });
+};
+
+export interface SimilarImage extends ClipItemData {
+    distance: number;
+}
+
+/**
+ * 与指定图片相似的图片（按汉明距离从近到远），threshold 缺省时使用设置中的阈值
+ */
+export const findSimilarImages = (id: number, threshold?: number): Promise<SimilarImage[]> => {
+    return invoke<SimilarImage[]>("find_similar_images", { id, threshold });
+};
+
+/**
+ * 按相似程度归组的图片记录 ID（每组至少两张）
+ */
+export const similarImageGroups = (): Promise<number[][]> => {
+    return invoke<number[][]>("similar_image_groups");
+};
+
+/**
+ * 指定尺寸的缩略图 URL，倍率默认按屏幕像素比取 1x 或 2x
+ */
+export const getThumbnailUrl = async (
+    id: number,
+    size: string,
+    scale: number = window.devicePixelRatio >= 2 ? 2 : 1,
+): Promise<string> => {
+    const path = await invoke<string>("get_thumbnail_url", { id, size, scale });
+    return pasteeUrl(path);
+};
+
+/**
+ * 清理旧规格的缩略图并在后台生成缺失的缩略图，返回待生成的数量
+ */
+export const regenerateThumbnails = (): Promise<number> => {
+    return invoke<number>("regenerate_thumbnails");
+};
+
+/**
+ * 监听缩略图重建的进度
+ */
+export const onThumbnailProgress = (callback: (progress: { done: number; total: number }) => void): Promise<() => void> => {
+    return listen<{ done: number; total: number }>("images://thumbnail-progress", (event) => {
+        callback(event.payload);
+    });
+};
+
+export interface RecompressProgress {
+    done: number;
+    total: number;
+    saved_bytes: number;
+}
+
+/**
+ * 在后台按当前编码设置重新压缩已保存的原图，返回待处理的图片数
+ */
+export const recompressImages = (): Promise<number> => {
+    return invoke<number>("recompress_images");
+};
+
+/**
+ * 监听重新压缩的进度
+ */
+export const onRecompressProgress = (callback: (progress: RecompressProgress) => void): Promise<() => void> => {
+    return listen<RecompressProgress>("images://recompress-progress", (event) => {
+        callback(event.payload);
+    });
+};
+
+/**
+ * 导出为 Markdown（HTML 记录会转换标记）
+ */
+export const exportClipMarkdown = (id: number): Promise<string> => {
+    return invoke<string>("export_clip_markdown", { id });
+};
+
+/**
+ * 识别记录的编程语言（syntect 语法名）
+ */
+export const detectClipLanguage = (id: number): Promise<string | null> => {
+    return invoke<string | null>("detect_clip_language", { id });
+};
+
+export const listHighlightThemes = (): Promise<string[]> => {
+    return invoke<string[]>("list_highlight_themes");
+};
+
+export type SimilarImages = "group" | "replace";
+
+/**
+ * 图片原图的存储编码
+ *
+ * 不提供 AVIF：解码需要系统安装的 libdav1d，存成 AVIF 的原图可能无法粘贴回剪贴板
+ */
+export type ImageCodec = "png" | "webp_lossless" | "webp_lossy";
+
+export interface ThumbnailSize {
+    name: string;
+    width: number;
+    height: number;
+}
+
+export interface Settings {
+    highlight_theme: string;
+    similar_image_threshold: number;
+    similar_images: SimilarImages;
+    image_codec: ImageCodec;
+    image_quality: number;
+    max_image_dimension: number;
+    thumbnail_sizes: ThumbnailSize[];
+    thumbnail_scales: number[];
+    max_image_pixels: number;
+    max_text_bytes: number;
+    max_html_bytes: number;
+    oversized_content: OversizedContent;
+    large_body_bytes: number;
+    search_text_bytes: number;
+}
+
+/**
+ * 文本或 HTML 超出大小上限时的处理方式：丢弃、截断、截断并另存完整内容
+ */
+export type OversizedContent = "drop" | "truncate" | "spill";
+
+/**
+ * 监听因超出大小上限被丢弃的捕获内容
+ */
+export const onCaptureRejected = (callback: (reason: string) => void): Promise<() => void> => {
+    return listen<{ reason: string }>("clipboard://capture-rejected", (event) => {
+        callback(event.payload.reason);
+    });
+};
+
+export const getSettings = (): Promise<Settings> => {
+    return invoke<Settings>("get_settings");
+};
+
+export const updateSettings = (settings: Settings): Promise<void> => {
+    return invoke("update_settings", { settings });
+};
@@ -101,8 +101,8 @@ export const useClipStore = create<ClipStore>((set, get) => ({
       return;
     }
     try {
-      const result = await invoke<ClipItem[]>('search_clips', { query });
-      set({ searchResults: result });
+      const result = await invoke<{ hits: ClipItem[]; total: number }>('search_clips', { query });
+      set({ searchResults: result.hits });
     } catch (error) {
       console.error('❌ 搜索失败:', error);
     }